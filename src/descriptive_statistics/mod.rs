@@ -75,6 +75,52 @@ pub fn variance_rs(data:  &Array1<f64>) -> f64 {
     sum_of_squared_diffs / data_copy.len() as f64
 }
 
+// Unbiased (n - 1) variance, variance_rs above divides by n
+pub fn sample_variance_rs(data: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    variance_rs(data) * n / (n - 1.0)
+}
+
+pub fn correlation_rs(x: &Array1<f64>, y: &Array1<f64>) -> f64 {
+    let (x_mean, y_mean) = (mean_rs(x), mean_rs(y));
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for (&xi, &yi) in x.iter().zip(y.iter()) {
+        sxy += (xi - x_mean) * (yi - y_mean);
+        sxx += (xi - x_mean).powi(2);
+        syy += (yi - y_mean).powi(2);
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+// Sorted data with floor(n * trim_percent) values cut from each end
+pub fn trim_rs(data: &Array1<f64>, trim_percent: f64) -> Array1<f64> {
+    let n_to_trim = ((data.len() as f64) * trim_percent) as usize;
+    let mut sorted_data = data.to_vec();
+    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Array1::from_vec(sorted_data[n_to_trim..data.len() - n_to_trim].to_vec())
+}
+
+pub fn trimmed_mean_rs(data: &Array1<f64>, trim_percent: f64) -> f64 {
+    mean_rs(&trim_rs(data, trim_percent))
+}
+
+// k-th central moment with divisor n
+pub fn moment_rs(data: &Array1<f64>, k: i32) -> f64 {
+    let mean = mean_rs(data);
+    data.iter().map(|&x| (x - mean).powi(k)).sum::<f64>() / data.len() as f64
+}
+
+// Moment (biased) skewness m3 / m2^1.5, the quantity normality tests are derived for.
+// The skewness pyfunction applies a small sample adjustment on top of this.
+pub fn moment_skewness_rs(data: &Array1<f64>) -> f64 {
+    moment_rs(data, 3) / moment_rs(data, 2).powf(1.5)
+}
+
+// Moment (biased, non-excess) kurtosis m4 / m2^2, equal to 3 for a normal distribution
+pub fn moment_kurtosis_rs(data: &Array1<f64>) -> f64 {
+    moment_rs(data, 4) / moment_rs(data, 2).powi(2)
+}
+
 pub fn percentile_rs(data: &Array1<f64>, percentile: f64) -> f64 {
     let mut data_copy = data.to_vec();
     data_copy.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
@@ -97,6 +143,41 @@ pub fn percentile_rs(data: &Array1<f64>, percentile: f64) -> f64 {
     }
 }
 
+pub fn median_absolute_deviation_rs(data: &Array1<f64>) -> f64 {
+    let median = median_rs(data);
+    median_rs(&data.mapv(|x| (x - median).abs()))
+}
+
+pub fn iqr_rs(data: &Array1<f64>) -> f64 {
+    percentile_rs(data, 75.0) - percentile_rs(data, 25.0)
+}
+
+pub fn range_rs(data: &Array1<f64>) -> f64 {
+    let min = data.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max - min
+}
+
+// Adjusted skewness n sum(z^3) / ((n - 1)(n - 2)), z standardized with the n divisor sd
+pub fn skewness_rs(data: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    let mean = mean_rs(data);
+    let std = variance_rs(data).sqrt();
+    let standardized_scores: f64 = data.iter().map(|&x| ((x - mean) / std).powi(3)).sum();
+    (n * standardized_scores) / ((n - 1.0) * (n - 2.0))
+}
+
+// Adjusted excess kurtosis, 0 for a normal distribution
+pub fn kurtosis_rs(data: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    let normalization_factor: f64 = (n * (n + 1.0)) / ((n - 1.0) * (n - 2.0) * (n - 3.0));
+    let mean = mean_rs(data);
+    let std = variance_rs(data).sqrt();
+    let sum_fourth_powers: f64 = data.iter().map(|&x| ((x - mean) / std).powi(4)).sum();
+    let bias_correction: f64 = 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0));
+    normalization_factor * sum_fourth_powers - bias_correction
+}
+
 
 #[pyfunction]
 pub fn mean(x: &PyAny) -> PyResult<f64> {
@@ -118,13 +199,7 @@ pub fn trimmed_mean(x: &PyAny, trim_percent: f64) -> PyResult<f64> {
         Err(_e) => return Err(StatsError::Conversion.into()),
     };
     validate_statistical_input!(trimmed, &x_data, trim_percent);
-    let n_to_trim = ((x_data.len() as f64) * trim_percent) as usize;
-    let mut sorted_data = x_data.to_vec();
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-    let trimmed_data = &sorted_data[n_to_trim..&x_data.len() - n_to_trim];
-    let sum: f64 = trimmed_data.iter().sum();
-    let trimmed_count = trimmed_data.len();
-    Ok(sum / trimmed_count as f64)
+    Ok(trimmed_mean_rs(&x_data, trim_percent))
 }
 
 
@@ -228,12 +303,7 @@ pub fn median_absolute_deviation(x: &PyAny) -> PyResult<f64> {
         Err(_e) => return Err(StatsError::Conversion.into()),
     };
     validate_statistical_input!(basic, &x_data);
-    let median = median_rs(&x_data);
-    let absolute_deviation: Array1<f64> = x_data.iter()
-        .map(|&x| (x - median).abs())
-        .collect();
-    let mad: f64 = median_rs(&absolute_deviation);
-    Ok(mad)
+    Ok(median_absolute_deviation_rs(&x_data))
 }
 
 
@@ -246,9 +316,7 @@ pub fn iqr(x: &PyAny) -> PyResult<f64> {
     validate_statistical_input!(basic, &x_data);
     // need way to find 75th and 25th percentile
     if x_data.len() < 2 { return Err(StatsError::InvalidInputValue.into()); }
-    let lower_quartile = percentile_rs(&x_data, 25.0);
-    let upper_quartile = percentile_rs(&x_data, 75.0);
-    Ok(upper_quartile - lower_quartile)
+    Ok(iqr_rs(&x_data))
 }
 
 
@@ -259,16 +327,7 @@ pub fn range(x: &PyAny) -> PyResult<f64> {
         Err(_e) => return Err(StatsError::Conversion.into()),
     };
     validate_statistical_input!(basic, &x_data);
-
-    let min_val = x_data.iter()
-        .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-        .ok_or(StatsError::MinMaxError)?;
-
-    let max_val = x_data.iter()
-        .max_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-        .ok_or(StatsError::MinMaxError)?;
-
-    Ok((max_val - min_val).abs())
+    Ok(range_rs(&x_data))
 }
 
 
@@ -360,18 +419,8 @@ pub fn skewness(x: &PyAny) -> PyResult<f64> {
 
     let n = x_data.len() as f64;
     if n < 2.0 { return Err(StatsError::InvalidInputValue.into()); }
-
-    let mean: f64 = mean_rs(&x_data);
-    let var: f64 = variance_rs(&x_data);
-    let std: f64 = var.sqrt();
-    if std == 0.0 { return Err(StatsError::ZeroVariance.into()); }
-
-    let standardized_scores: f64 = x_data.iter()
-        .map(|&x| ((x - mean)/std).powi(3))
-        .sum();
-
-    let skew: f64 = (n * standardized_scores) / ((n - 1.0) * (n - 2.0));
-    Ok(skew)
+    if variance_rs(&x_data) == 0.0 { return Err(StatsError::ZeroVariance.into()); }
+    Ok(skewness_rs(&x_data))
 }
 
 
@@ -387,21 +436,8 @@ pub fn kurtosis(x: &PyAny) -> PyResult<f64> {
     validate_statistical_input!(basic, &x_data);
     let n = x_data.len() as f64;
     if n < 3.0 { return Err(StatsError::InvalidInputValue.into()); }
-
-    let normalization_factor: f64 = (n * (n + 1.0)) / ((n - 1.0) * (n - 2.0) * (n - 3.0));
-
-    let mean: f64 = mean_rs(&x_data);
-    let var: f64 = variance_rs(&x_data);
-    let std: f64 = var.sqrt();
-    if std == 0.0 { return Err(StatsError::ZeroVariance.into()); }
-
-    let sum_fourth_powers: f64 = x_data.iter()
-        .map(|&x| ((x - mean) / std).powi(4))
-        .sum();
-
-    let bias_correction: f64 = 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0));
-
-    Ok(normalization_factor * sum_fourth_powers - bias_correction)
+    if variance_rs(&x_data) == 0.0 { return Err(StatsError::ZeroVariance.into()); }
+    Ok(kurtosis_rs(&x_data))
 }
 
 #[pyfunction]
@@ -0,0 +1,172 @@
+// Continuous distribution classes over statrs
+// Normal(loc, scale), StudentsT(df, loc, scale), ChiSquared(df), F(dfn, dfd), Beta(a, b),
+// Gamma(shape, scale), Exponential(scale), LogNormal(mu, sigma), Weibull(shape, scale),
+// Uniform(low, high), Cauchy(loc, scale)
+//
+// Methods: pdf, logpdf, cdf, sf, ppf, mean, variance, std, skewness and sample(size, seed).
+// Scale parameters follow scipy, statrs' rate parameterizations are converted on construction.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+use rand::distributions::Distribution as Sampler;
+use statrs::distribution::{Continuous, ContinuousCDF};
+use statrs::statistics::Distribution as Moments;
+
+use crate::distributions::{continuous_ppf_rs, describe, evaluate, sample_rs};
+use crate::inferential_statistics::errors::StatsError;
+
+
+// Defines a python class wrapping a statrs continuous distribution. `$build` turns the
+// constructor arguments into Option<distribution>, None meaning invalid parameters.
+macro_rules! continuous_distribution {
+    ($(#[$doc:meta])* $name:ident, $py_name:literal, $inner:ty,
+     ($($signature:tt)*), ($($param:ident: $ty:ty),*) => $build:expr) => {
+        $(#[$doc])*
+        #[pyclass(name = $py_name)]
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            pub inner: $inner,
+            params: Vec<(&'static str, f64)>,
+        }
+
+        impl $name {
+            #[allow(clippy::unnecessary_cast)]
+            pub fn new_rs($($param: $ty),*) -> Result<$name, StatsError> {
+                match $build {
+                    Some(inner) => Ok($name { inner, params: vec![$((stringify!($param), $param as f64)),*] }),
+                    None => Err(StatsError::InvalidInputValue),
+                }
+            }
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = ($($signature)*))]
+            fn new($($param: $ty),*) -> PyResult<Self> {
+                Ok($name::new_rs($($param),*)?)
+            }
+
+            fn pdf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| self.inner.pdf(v))
+            }
+
+            fn logpdf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| self.inner.ln_pdf(v))
+            }
+
+            fn cdf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| self.inner.cdf(v))
+            }
+
+            fn sf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| self.inner.sf(v))
+            }
+
+            fn ppf(&self, py: Python, q: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, q, |p| continuous_ppf_rs(&self.inner, p))
+            }
+
+            // None where the moment does not exist
+            fn mean(&self) -> Option<f64> {
+                self.inner.mean()
+            }
+
+            fn variance(&self) -> Option<f64> {
+                self.inner.variance()
+            }
+
+            fn std(&self) -> Option<f64> {
+                self.inner.std_dev()
+            }
+
+            fn skewness(&self) -> Option<f64> {
+                self.inner.skewness()
+            }
+
+            #[pyo3(signature = (size = 1, seed = None))]
+            fn sample<'py>(&self, py: Python<'py>, size: usize, seed: Option<u64>) -> &'py PyArray1<f64> {
+                sample_rs(size, seed.unwrap_or_else(rand::random), |rng| self.inner.sample(rng)).into_pyarray(py)
+            }
+
+            fn __repr__(&self) -> String {
+                describe($py_name, &self.params)
+            }
+        }
+    };
+}
+
+fn positive(value: f64) -> Option<f64> {
+    if value > 0.0 && value.is_finite() { Some(value) } else { None }
+}
+
+continuous_distribution!(
+    /// Normal distribution with mean loc and standard deviation scale
+    Normal, "Normal", statrs::distribution::Normal,
+    (loc = 0.0, scale = 1.0), (loc: f64, scale: f64) => statrs::distribution::Normal::new(loc, scale).ok()
+);
+
+continuous_distribution!(
+    /// Student's t with df degrees of freedom, shifted by loc and stretched by scale
+    StudentsT, "StudentsT", statrs::distribution::StudentsT,
+    (df, loc = 0.0, scale = 1.0), (df: f64, loc: f64, scale: f64) =>
+        statrs::distribution::StudentsT::new(loc, scale, df).ok()
+);
+
+continuous_distribution!(
+    /// Chi-squared with df degrees of freedom
+    ChiSquared, "ChiSquared", statrs::distribution::ChiSquared,
+    (df), (df: f64) => statrs::distribution::ChiSquared::new(df).ok()
+);
+
+continuous_distribution!(
+    /// Fisher-Snedecor F with dfn numerator and dfd denominator degrees of freedom
+    FDistribution, "F", statrs::distribution::FisherSnedecor,
+    (dfn, dfd), (dfn: f64, dfd: f64) => statrs::distribution::FisherSnedecor::new(dfn, dfd).ok()
+);
+
+continuous_distribution!(
+    /// Beta with shape parameters a and b
+    Beta, "Beta", statrs::distribution::Beta,
+    (a, b), (a: f64, b: f64) => statrs::distribution::Beta::new(a, b).ok()
+);
+
+continuous_distribution!(
+    /// Gamma with the given shape and scale (1 / rate)
+    Gamma, "Gamma", statrs::distribution::Gamma,
+    (shape, scale = 1.0), (shape: f64, scale: f64) =>
+        positive(scale).and_then(|scale| statrs::distribution::Gamma::new(shape, 1.0 / scale).ok())
+);
+
+continuous_distribution!(
+    /// Exponential with the given scale (mean, 1 / rate)
+    Exponential, "Exponential", statrs::distribution::Exp,
+    (scale = 1.0), (scale: f64) => positive(scale).and_then(|scale| statrs::distribution::Exp::new(1.0 / scale).ok())
+);
+
+continuous_distribution!(
+    /// Log-normal, exp(X) for X normal with mean mu and standard deviation sigma
+    LogNormal, "LogNormal", statrs::distribution::LogNormal,
+    (mu = 0.0, sigma = 1.0), (mu: f64, sigma: f64) => statrs::distribution::LogNormal::new(mu, sigma).ok()
+);
+
+continuous_distribution!(
+    /// Weibull with the given shape and scale
+    Weibull, "Weibull", statrs::distribution::Weibull,
+    (shape, scale = 1.0), (shape: f64, scale: f64) => statrs::distribution::Weibull::new(shape, scale).ok()
+);
+
+continuous_distribution!(
+    /// Uniform on [low, high]
+    Uniform, "Uniform", statrs::distribution::Uniform,
+    (low = 0.0, high = 1.0), (low: f64, high: f64) => statrs::distribution::Uniform::new(low, high).ok()
+);
+
+continuous_distribution!(
+    /// Cauchy with location loc and scale, it has no moments
+    Cauchy, "Cauchy", statrs::distribution::Cauchy,
+    (loc = 0.0, scale = 1.0), (loc: f64, scale: f64) => statrs::distribution::Cauchy::new(loc, scale).ok()
+);
@@ -0,0 +1,208 @@
+// Discrete distribution classes over statrs
+// Poisson(mu), Binomial(n, p), NegativeBinomial(r, p) -- failures before the r-th success,
+// Geometric(p) -- trials up to and including the first success
+//
+// Methods: pmf, logpmf, cdf, sf, ppf, mean, variance, std, skewness and sample(size, seed).
+// pmf is 0 off the integers, cdf and sf are step functions evaluated at floor(x), samples are int64.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+use rand::distributions::Distribution as Sampler;
+use rand_chacha::ChaCha8Rng;
+use statrs::distribution::{Discrete, DiscreteCDF};
+use statrs::statistics::{Distribution as Moments, DiscreteDistribution, Max, Min};
+
+use crate::distributions::{describe, evaluate, sample_rs};
+use crate::inferential_statistics::errors::StatsError;
+
+
+// What the classes need from a statrs discrete distribution. statrs is not uniform here,
+// NegativeBinomial samples u64 and reports its moments through DiscreteDistribution.
+pub trait DiscreteModel: Discrete<u64, f64> + DiscreteCDF<u64, f64> + Min<u64> + Max<u64> {
+    fn draw(&self, rng: &mut ChaCha8Rng) -> i64;
+    fn moments(&self) -> (Option<f64>, Option<f64>, Option<f64>);
+}
+
+macro_rules! discrete_model {
+    ($($inner:ty),*) => {
+        $(impl DiscreteModel for $inner {
+            fn draw(&self, rng: &mut ChaCha8Rng) -> i64 {
+                Sampler::<f64>::sample(self, rng) as i64
+            }
+
+            fn moments(&self) -> (Option<f64>, Option<f64>, Option<f64>) {
+                (Moments::mean(self), Moments::variance(self), Moments::skewness(self))
+            }
+        })*
+    };
+}
+
+discrete_model!(statrs::distribution::Poisson, statrs::distribution::Binomial, statrs::distribution::Geometric);
+
+impl DiscreteModel for statrs::distribution::NegativeBinomial {
+    // statrs' own sampler has mean r p / (1 - p), disagreeing with its pmf, so draw from the
+    // gamma-poisson mixture: lambda ~ Gamma(r, scale (1 - p) / p), k ~ Poisson(lambda)
+    fn draw(&self, rng: &mut ChaCha8Rng) -> i64 {
+        if self.p() == 1.0 { return 0; }
+        let rate = self.p() / (1.0 - self.p());
+        let lambda = Sampler::<f64>::sample(&statrs::distribution::Gamma::new(self.r(), rate).unwrap(), rng);
+        match statrs::distribution::Poisson::new(lambda) {
+            Ok(poisson) => Sampler::<f64>::sample(&poisson, rng) as i64,
+            Err(_e) => 0,
+        }
+    }
+
+    fn moments(&self) -> (Option<f64>, Option<f64>, Option<f64>) {
+        (DiscreteDistribution::mean(self), DiscreteDistribution::variance(self), DiscreteDistribution::skewness(self))
+    }
+}
+
+pub fn discrete_pmf_rs<D: DiscreteModel>(distribution: &D, x: f64) -> f64 {
+    if x < 0.0 || x.fract() != 0.0 || x.is_nan() { return 0.0; }
+    distribution.pmf(x as u64)
+}
+
+// statrs' ln_pmf, which stays finite far in the tails where pmf underflows to 0
+pub fn discrete_ln_pmf_rs<D: DiscreteModel>(distribution: &D, x: f64) -> f64 {
+    if x < 0.0 || x.fract() != 0.0 || x.is_nan() { return f64::NEG_INFINITY; }
+    distribution.ln_pmf(x as u64)
+}
+
+pub fn discrete_cdf_rs<D: DiscreteModel>(distribution: &D, x: f64) -> f64 {
+    if x.is_nan() { return f64::NAN; }
+    if x < 0.0 { return 0.0; }
+    distribution.cdf(x.floor() as u64)
+}
+
+pub fn discrete_sf_rs<D: DiscreteModel>(distribution: &D, x: f64) -> f64 {
+    if x.is_nan() { return f64::NAN; }
+    if x < 0.0 { return 1.0; }
+    distribution.sf(x.floor() as u64)
+}
+
+// Smallest k with cdf(k) >= q, infinite at q = 1 for unbounded support
+pub fn discrete_ppf_rs<D: DiscreteModel>(distribution: &D, q: f64) -> f64 {
+    if !(0.0..=1.0).contains(&q) { return f64::NAN; }
+    if q == 1.0 && distribution.max() == u64::MAX { return f64::INFINITY; }
+    // statrs 0.16.0's inverse_cdf never leaves its bisection once the bracket is one apart, so
+    // double an upper bound past q and bisect on the integers, keeping cdf(low) < q <= cdf(high)
+    let (min, max) = (distribution.min(), distribution.max());
+    if distribution.cdf(min) >= q { return min as f64; }
+    let (mut low, mut high) = (min, min + 1);
+    while distribution.cdf(high) < q {
+        if high == max { return max as f64; }
+        low = high;
+        high = high.saturating_mul(2).min(max);
+    }
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if distribution.cdf(mid) >= q { high = mid; } else { low = mid; }
+    }
+    high as f64
+}
+
+
+// Defines a python class wrapping a statrs discrete distribution, as in continuous.rs
+macro_rules! discrete_distribution {
+    ($(#[$doc:meta])* $name:ident, $py_name:literal, $inner:ty,
+     ($($signature:tt)*), ($($param:ident: $ty:ty),*) => $build:expr) => {
+        $(#[$doc])*
+        #[pyclass(name = $py_name)]
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            pub inner: $inner,
+            params: Vec<(&'static str, f64)>,
+        }
+
+        impl $name {
+            #[allow(clippy::unnecessary_cast)]
+            pub fn new_rs($($param: $ty),*) -> Result<$name, StatsError> {
+                match $build {
+                    Some(inner) => Ok($name { inner, params: vec![$((stringify!($param), $param as f64)),*] }),
+                    None => Err(StatsError::InvalidInputValue),
+                }
+            }
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = ($($signature)*))]
+            fn new($($param: $ty),*) -> PyResult<Self> {
+                Ok($name::new_rs($($param),*)?)
+            }
+
+            fn pmf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| discrete_pmf_rs(&self.inner, v))
+            }
+
+            fn logpmf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| discrete_ln_pmf_rs(&self.inner, v))
+            }
+
+            fn cdf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| discrete_cdf_rs(&self.inner, v))
+            }
+
+            fn sf(&self, py: Python, x: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, x, |v| discrete_sf_rs(&self.inner, v))
+            }
+
+            fn ppf(&self, py: Python, q: &PyAny) -> PyResult<PyObject> {
+                evaluate(py, q, |p| discrete_ppf_rs(&self.inner, p))
+            }
+
+            fn mean(&self) -> Option<f64> {
+                self.inner.moments().0
+            }
+
+            fn variance(&self) -> Option<f64> {
+                self.inner.moments().1
+            }
+
+            fn std(&self) -> Option<f64> {
+                self.inner.moments().1.map(f64::sqrt)
+            }
+
+            fn skewness(&self) -> Option<f64> {
+                self.inner.moments().2
+            }
+
+            #[pyo3(signature = (size = 1, seed = None))]
+            fn sample<'py>(&self, py: Python<'py>, size: usize, seed: Option<u64>) -> &'py PyArray1<i64> {
+                sample_rs(size, seed.unwrap_or_else(rand::random), |rng| self.inner.draw(rng)).into_pyarray(py)
+            }
+
+            fn __repr__(&self) -> String {
+                describe($py_name, &self.params)
+            }
+        }
+    };
+}
+
+discrete_distribution!(
+    /// Poisson with mean mu
+    Poisson, "Poisson", statrs::distribution::Poisson,
+    (mu), (mu: f64) => statrs::distribution::Poisson::new(mu).ok()
+);
+
+discrete_distribution!(
+    /// Binomial, successes in n trials with success probability p
+    Binomial, "Binomial", statrs::distribution::Binomial,
+    (n, p), (n: u64, p: f64) => statrs::distribution::Binomial::new(p, n).ok()
+);
+
+discrete_distribution!(
+    /// Negative binomial, failures before the r-th success with success probability p
+    NegativeBinomial, "NegativeBinomial", statrs::distribution::NegativeBinomial,
+    (r, p), (r: f64, p: f64) => statrs::distribution::NegativeBinomial::new(r, p).ok()
+);
+
+discrete_distribution!(
+    /// Geometric, trials up to and including the first success with success probability p
+    Geometric, "Geometric", statrs::distribution::Geometric,
+    (p), (p: f64) => statrs::distribution::Geometric::new(p).ok()
+);
@@ -0,0 +1,351 @@
+// Maximum likelihood fitting of the distribution families
+// fit -- estimates, standard errors from the observed Fisher information, log-likelihood, AIC/BIC
+//        and a one-sample Kolmogorov-Smirnov check of the fitted (continuous) distribution
+//
+// Closed form: normal, lognormal, exponential, poisson, binomial (trials known), geometric.
+// Numerical: gamma and weibull (profile score root in the shape), negative binomial (profile score
+// root in r) and beta (Nelder-Mead on the log parameters from the method of moments start).
+// The KS p-value treats the parameters as known, so it is conservative for fitted ones.
+
+use ndarray::{Array1, Array2};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use statrs::distribution::{Continuous, ContinuousCDF};
+use statrs::function::gamma::digamma;
+
+use crate::descriptive_statistics::mean_rs;
+use crate::distributions::continuous::{Beta, Exponential, Gamma, LogNormal, Normal, Weibull};
+use crate::distributions::discrete::{discrete_ln_pmf_rs, Binomial, Geometric, NegativeBinomial, Poisson};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::kolmogorov_smirnov_one_sample_rs;
+use crate::utils::{bisect_rs, from_pyarray1, invert_rs, nelder_mead_rs};
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitFamily {
+    Normal,
+    LogNormal,
+    Exponential,
+    Gamma,
+    Weibull,
+    Beta,
+    Poisson,
+    // number of trials, which is not estimated
+    Binomial(u64),
+    NegativeBinomial,
+    Geometric,
+}
+
+impl FitFamily {
+    pub fn parse(distribution: &str, trials: Option<u64>) -> Result<FitFamily, StatsError> {
+        match distribution {
+            "normal" => Ok(FitFamily::Normal),
+            "lognormal" => Ok(FitFamily::LogNormal),
+            "exponential" => Ok(FitFamily::Exponential),
+            "gamma" => Ok(FitFamily::Gamma),
+            "weibull" => Ok(FitFamily::Weibull),
+            "beta" => Ok(FitFamily::Beta),
+            "poisson" => Ok(FitFamily::Poisson),
+            "binomial" => match trials {
+                Some(n) if n > 0 => Ok(FitFamily::Binomial(n)),
+                _ => Err(StatsError::InvalidInputValue),
+            },
+            "negative_binomial" => Ok(FitFamily::NegativeBinomial),
+            "geometric" => Ok(FitFamily::Geometric),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FitFamily::Normal => "normal",
+            FitFamily::LogNormal => "lognormal",
+            FitFamily::Exponential => "exponential",
+            FitFamily::Gamma => "gamma",
+            FitFamily::Weibull => "weibull",
+            FitFamily::Beta => "beta",
+            FitFamily::Poisson => "poisson",
+            FitFamily::Binomial(_) => "binomial",
+            FitFamily::NegativeBinomial => "negative_binomial",
+            FitFamily::Geometric => "geometric",
+        }
+    }
+
+    // Same names and order as the constructor arguments of the distribution classes
+    pub fn parameter_names(&self) -> &'static [&'static str] {
+        match self {
+            FitFamily::Normal => &["loc", "scale"],
+            FitFamily::LogNormal => &["mu", "sigma"],
+            FitFamily::Exponential => &["scale"],
+            FitFamily::Gamma | FitFamily::Weibull => &["shape", "scale"],
+            FitFamily::Beta => &["a", "b"],
+            FitFamily::Poisson => &["mu"],
+            FitFamily::Binomial(_) | FitFamily::Geometric => &["p"],
+            FitFamily::NegativeBinomial => &["r", "p"],
+        }
+    }
+
+    fn check_support(&self, x: &Array1<f64>) -> Result<(), StatsError> {
+        let integers = x.iter().all(|v| v.fract() == 0.0);
+        let valid = match self {
+            FitFamily::Normal => true,
+            FitFamily::LogNormal | FitFamily::Gamma | FitFamily::Weibull => x.iter().all(|&v| v > 0.0),
+            FitFamily::Exponential => x.iter().all(|&v| v >= 0.0),
+            FitFamily::Beta => x.iter().all(|&v| v > 0.0 && v < 1.0),
+            FitFamily::Poisson | FitFamily::NegativeBinomial => integers && x.iter().all(|&v| v >= 0.0),
+            FitFamily::Binomial(n) => integers && x.iter().all(|&v| v >= 0.0 && v <= *n as f64),
+            FitFamily::Geometric => integers && x.iter().all(|&v| v >= 1.0),
+        };
+        if valid { Ok(()) } else { Err(StatsError::InvalidInputValue) }
+    }
+
+    // Log-likelihood of x, -inf where the parameters are invalid
+    pub fn log_likelihood(&self, params: &[f64], x: &Array1<f64>) -> f64 {
+        let total: Result<f64, StatsError> = match self {
+            FitFamily::Normal => Normal::new_rs(params[0], params[1]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::LogNormal => LogNormal::new_rs(params[0], params[1]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::Exponential => Exponential::new_rs(params[0]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::Gamma => Gamma::new_rs(params[0], params[1]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::Weibull => Weibull::new_rs(params[0], params[1]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::Beta => Beta::new_rs(params[0], params[1]).map(|d| x.iter().map(|&v| d.inner.ln_pdf(v)).sum()),
+            FitFamily::Poisson => Poisson::new_rs(params[0])
+                .map(|d| x.iter().map(|&v| discrete_ln_pmf_rs(&d.inner, v)).sum()),
+            FitFamily::Binomial(n) => Binomial::new_rs(*n, params[0])
+                .map(|d| x.iter().map(|&v| discrete_ln_pmf_rs(&d.inner, v)).sum()),
+            FitFamily::NegativeBinomial => NegativeBinomial::new_rs(params[0], params[1])
+                .map(|d| x.iter().map(|&v| discrete_ln_pmf_rs(&d.inner, v)).sum()),
+            FitFamily::Geometric => Geometric::new_rs(params[0])
+                .map(|d| x.iter().map(|&v| discrete_ln_pmf_rs(&d.inner, v)).sum()),
+        };
+        match total {
+            Ok(value) if !value.is_nan() => value,
+            _ => f64::NEG_INFINITY,
+        }
+    }
+
+    // KS statistic and p-value of x against the fitted distribution, continuous families only
+    fn goodness_of_fit(&self, params: &[f64], x: &Array1<f64>) -> Option<(f64, f64)> {
+        let result = match self {
+            FitFamily::Normal => Normal::new_rs(params[0], params[1]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            FitFamily::LogNormal => LogNormal::new_rs(params[0], params[1]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            FitFamily::Exponential => Exponential::new_rs(params[0]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            FitFamily::Gamma => Gamma::new_rs(params[0], params[1]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            FitFamily::Weibull => Weibull::new_rs(params[0], params[1]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            FitFamily::Beta => Beta::new_rs(params[0], params[1]).map(|d| kolmogorov_smirnov_one_sample_rs(x, |v| d.inner.cdf(v))),
+            _ => return None,
+        };
+        result.ok()
+    }
+
+    // Maximum likelihood estimates, in parameter_names order
+    pub fn estimate(&self, x: &Array1<f64>) -> Result<Vec<f64>, StatsError> {
+        let n = x.len() as f64;
+        let mean = mean_rs(x);
+        let biased_variance = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        match self {
+            FitFamily::Normal => {
+                if biased_variance == 0.0 { return Err(StatsError::ZeroVariance); }
+                Ok(vec![mean, biased_variance.sqrt()])
+            }
+            FitFamily::LogNormal => {
+                let logs = x.mapv(f64::ln);
+                let log_mean = mean_rs(&logs);
+                let log_variance = logs.iter().map(|v| (v - log_mean).powi(2)).sum::<f64>() / n;
+                if log_variance == 0.0 { return Err(StatsError::ZeroVariance); }
+                Ok(vec![log_mean, log_variance.sqrt()])
+            }
+            FitFamily::Exponential => {
+                if mean == 0.0 { return Err(StatsError::ZeroVariance); }
+                Ok(vec![mean])
+            }
+            FitFamily::Gamma => {
+                // ln(k) - digamma(k) = ln(mean) - mean(ln x), the left side lies in (1 / 2k, 1 / k)
+                let s = mean.ln() - x.iter().map(|v| v.ln()).sum::<f64>() / n;
+                if s <= 0.0 { return Err(StatsError::ZeroVariance); }
+                let shape = bisect_rs(|k| k.ln() - digamma(k) - s, 0.5 / s, 1.0 / s, 1e-14 / s);
+                Ok(vec![shape, mean / shape])
+            }
+            FitFamily::Weibull => {
+                if biased_variance == 0.0 { return Err(StatsError::ZeroVariance); }
+                let logs = x.mapv(f64::ln);
+                let log_max = logs.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let log_mean = mean_rs(&logs);
+                // weights (x / max)^k keep the sums finite for large shapes
+                let weights = |k: f64| logs.mapv(|l| (k * (l - log_max)).exp());
+                let score = |k: f64| {
+                    let w = weights(k);
+                    w.dot(&logs) / w.sum() - 1.0 / k - log_mean
+                };
+                let mut hi = 1.0;
+                while score(hi) < 0.0 && hi < 1e6 { hi *= 2.0; }
+                let mut lo = 1.0;
+                while score(lo) > 0.0 && lo > 1e-6 { lo /= 2.0; }
+                let shape = bisect_rs(score, lo, hi, 1e-14 * hi);
+                Ok(vec![shape, log_max.exp() * (weights(shape).sum() / n).powf(1.0 / shape)])
+            }
+            FitFamily::Beta => {
+                if biased_variance == 0.0 { return Err(StatsError::ZeroVariance); }
+                let common = mean * (1.0 - mean) / biased_variance - 1.0;
+                let start = if common > 0.0 { [(mean * common).ln(), ((1.0 - mean) * common).ln()] } else { [0.0, 0.0] };
+                let best = nelder_mead_rs(|theta| -self.log_likelihood(&[theta[0].exp(), theta[1].exp()], x),
+                                          &start, 1e-15, 5000);
+                Ok(vec![best[0].exp(), best[1].exp()])
+            }
+            FitFamily::Poisson => {
+                if mean == 0.0 { return Err(StatsError::NoSolution); }
+                Ok(vec![mean])
+            }
+            FitFamily::Binomial(trials) => Ok(vec![mean / *trials as f64]),
+            FitFamily::NegativeBinomial => {
+                // without overdispersion the likelihood increases towards the poisson limit r -> inf
+                if biased_variance <= mean { return Err(StatsError::NoSolution); }
+                let score = |r: f64| {
+                    x.iter().map(|&v| digamma(v + r)).sum::<f64>() - n * digamma(r) + n * (r / (r + mean)).ln()
+                };
+                let start = mean * mean / (biased_variance - mean);
+                let (mut lo, mut hi) = (start, start);
+                while score(hi) > 0.0 && hi < 1e10 { hi *= 2.0; }
+                while score(lo) < 0.0 && lo > 1e-10 { lo /= 2.0; }
+                let r = bisect_rs(score, lo, hi, 1e-14 * hi);
+                Ok(vec![r, r / (r + mean)])
+            }
+            FitFamily::Geometric => Ok(vec![1.0 / mean]),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitResult {
+    #[pyo3(get)]
+    pub distribution: String,
+    #[pyo3(get)]
+    pub parameter_names: Vec<String>,
+    #[pyo3(get)]
+    pub estimates: Vec<f64>,
+    #[pyo3(get)]
+    pub standard_errors: Vec<f64>,
+    #[pyo3(get)]
+    pub log_likelihood: f64,
+    #[pyo3(get)]
+    pub aic: f64,
+    #[pyo3(get)]
+    pub bic: f64,
+    #[pyo3(get)]
+    pub n: usize,
+    #[pyo3(get)]
+    pub ks_statistic: Option<f64>,
+    #[pyo3(get)]
+    pub ks_p_value: Option<f64>,
+}
+
+// Observed information, minus the central difference hessian of the log-likelihood
+fn observed_information(family: &FitFamily, params: &[f64], x: &Array1<f64>) -> Array2<f64> {
+    let k = params.len();
+    let steps: Vec<f64> = params.iter().map(|p| 1e-4 * p.abs().max(1e-2)).collect();
+    let at = |shifts: &[(usize, f64)]| {
+        let mut shifted = params.to_vec();
+        for &(i, sign) in shifts { shifted[i] += sign * steps[i]; }
+        family.log_likelihood(&shifted, x)
+    };
+    let center = at(&[]);
+    let mut information = Array2::<f64>::zeros((k, k));
+    for i in 0..k {
+        information[[i, i]] = -(at(&[(i, 1.0)]) - 2.0 * center + at(&[(i, -1.0)])) / (steps[i] * steps[i]);
+        for j in 0..i {
+            let cross = at(&[(i, 1.0), (j, 1.0)]) - at(&[(i, 1.0), (j, -1.0)])
+                - at(&[(i, -1.0), (j, 1.0)]) + at(&[(i, -1.0), (j, -1.0)]);
+            information[[i, j]] = -cross / (4.0 * steps[i] * steps[j]);
+            information[[j, i]] = information[[i, j]];
+        }
+    }
+    information
+}
+
+pub fn fit_rs(family: FitFamily, x: &Array1<f64>) -> Result<FitResult, StatsError> {
+    if x.len() < 2 { return Err(StatsError::EmptyDataSet); }
+    family.check_support(x)?;
+    let estimates = family.estimate(x)?;
+    let log_likelihood = family.log_likelihood(&estimates, x);
+
+    // NaN where the information is singular, e.g. an estimate on the edge of the parameter space
+    let standard_errors = match invert_rs(&observed_information(&family, &estimates, x)) {
+        Some(covariance) => covariance.diag().iter().map(|&v| if v > 0.0 { v.sqrt() } else { f64::NAN }).collect(),
+        None => vec![f64::NAN; estimates.len()],
+    };
+
+    let (k, n) = (estimates.len() as f64, x.len() as f64);
+    let goodness_of_fit = family.goodness_of_fit(&estimates, x);
+    Ok(FitResult {
+        distribution: family.name().to_string(),
+        parameter_names: family.parameter_names().iter().map(|name| name.to_string()).collect(),
+        estimates,
+        standard_errors,
+        log_likelihood,
+        aic: 2.0 * k - 2.0 * log_likelihood,
+        bic: k * n.ln() - 2.0 * log_likelihood,
+        n: x.len(),
+        ks_statistic: goodness_of_fit.map(|(d, _)| d),
+        ks_p_value: goodness_of_fit.map(|(_, p)| p),
+    })
+}
+
+
+/// Pymethods
+
+#[pymethods]
+impl FitResult {
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        // {name: estimate}, ready to unpack into the matching distribution class
+        named(py, &self.parameter_names, &self.estimates)
+    }
+
+    fn __repr__(&self) -> String {
+        let params: Vec<String> = self.parameter_names.iter().zip(self.estimates.iter())
+            .map(|(name, estimate)| format!("{}={}", name, estimate))
+            .collect();
+        format!("FitResult(distribution='{}', {}, log_likelihood={}, aic={}, bic={})",
+                self.distribution, params.join(", "), self.log_likelihood, self.aic, self.bic)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("distribution", &self.distribution)?;
+        dict.set_item("params", self.params(py)?)?;
+        dict.set_item("standard_errors", named(py, &self.parameter_names, &self.standard_errors)?)?;
+        dict.set_item("log_likelihood", self.log_likelihood)?;
+        dict.set_item("aic", self.aic)?;
+        dict.set_item("bic", self.bic)?;
+        dict.set_item("n", self.n)?;
+        dict.set_item("ks_statistic", self.ks_statistic)?;
+        dict.set_item("ks_p_value", self.ks_p_value)?;
+        Ok(dict)
+    }
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (distribution, x, trials = None))]
+pub fn fit(distribution: &str, x: &PyAny, trials: Option<u64>) -> PyResult<FitResult> {
+    // distribution: normal, lognormal, exponential, gamma, weibull, beta, poisson,
+    // binomial (needs trials), negative_binomial or geometric
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    Ok(fit_rs(FitFamily::parse(distribution, trials)?, &x_data)?)
+}
+
+fn named<'py>(py: Python<'py>, names: &[String], values: &[f64]) -> PyResult<&'py PyDict> {
+    let dict = PyDict::new(py);
+    for (name, value) in names.iter().zip(values.iter()) {
+        dict.set_item(name, value)?;
+    }
+    Ok(dict)
+}
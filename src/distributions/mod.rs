@@ -0,0 +1,71 @@
+// distributions mod.rs
+// noncentral -- noncentral t, F and chi-square used by power analysis and effect size intervals
+// continuous -- Normal, StudentsT, ChiSquared, F, Beta, Gamma, Exponential, LogNormal, Weibull,
+//               Uniform and Cauchy classes
+// discrete -- Poisson, Binomial, NegativeBinomial and Geometric classes
+// fitting -- maximum likelihood fits of the common families
+//
+// Every class evaluates its functions on a float or a numpy array (returning a float or an array),
+// reports its moments and draws seeded samples into numpy arrays.
+pub mod noncentral;
+pub mod continuous;
+pub mod discrete;
+pub mod fitting;
+
+#[cfg(test)]
+mod unit_tests;
+
+use ndarray::Array1;
+use numpy::IntoPyArray;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use statrs::distribution::ContinuousCDF;
+use statrs::statistics::{Max, Min};
+
+use crate::inferential_statistics::errors::StatsError;
+use crate::utils::{bisect_rs, from_pyarray1};
+
+
+// Quantile of a continuous distribution. statrs' default inverse_cdf stops after 16 bisection
+// steps, so the root is bracketed from the support and bisected to full precision instead
+pub fn continuous_ppf_rs<D: ContinuousCDF<f64, f64> + Min<f64> + Max<f64>>(distribution: &D, q: f64) -> f64 {
+    if !(0.0..=1.0).contains(&q) { return f64::NAN; }
+    let (mut lo, mut hi) = (distribution.min(), distribution.max());
+    if q == 0.0 { return lo; }
+    if q == 1.0 { return hi; }
+    if lo.is_infinite() {
+        lo = -1.0;
+        while distribution.cdf(lo) > q { lo *= 2.0; }
+    }
+    if hi.is_infinite() {
+        hi = lo.max(0.0) + 1.0;
+        while distribution.cdf(hi) < q { hi *= 2.0; }
+    }
+    let tol = f64::EPSILON * lo.abs().max(hi.abs());
+    bisect_rs(|x| distribution.cdf(x) - q, lo, hi, tol)
+}
+
+// `size` draws, the same seed always giving the same sample
+pub fn sample_rs<T, F: FnMut(&mut ChaCha8Rng) -> T>(size: usize, seed: u64, mut draw: F) -> Array1<T> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    (0..size).map(|_| draw(&mut rng)).collect()
+}
+
+// Applies f to a python float, or elementwise to a numpy array
+pub(crate) fn evaluate<F: Fn(f64) -> f64>(py: Python, x: &PyAny, f: F) -> PyResult<PyObject> {
+    if let Ok(value) = x.extract::<f64>() {
+        return Ok(f(value).into_py(py));
+    }
+    let data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    Ok(data.mapv(f).into_pyarray(py).into_py(py))
+}
+
+// "Name(a=1, b=2)", shared by the class __repr__s
+pub(crate) fn describe(name: &str, params: &[(&str, f64)]) -> String {
+    let params: Vec<String> = params.iter().map(|(param, value)| format!("{}={}", param, value)).collect();
+    format!("{}({})", name, params.join(", "))
+}
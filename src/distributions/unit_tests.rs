@@ -0,0 +1,204 @@
+// Unit Tests
+use crate::distributions::*;
+use crate::distributions::continuous::*;
+use crate::distributions::discrete::*;
+use crate::distributions::fitting::*;
+use crate::inferential_statistics::errors::StatsError;
+use statrs::distribution::ContinuousCDF;
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tol: f64) {
+        assert!((actual - expected).abs() <= tol, "expected {}, got {}", expected, actual);
+    }
+
+    mod continuous_tests {
+        use super::*;
+
+        #[test]
+        fn test_continuous_ppf_known_values() {
+            let chi2 = ChiSquared::new_rs(1.0).unwrap();
+            assert_close(continuous_ppf_rs(&chi2.inner, 0.95), 3.841458820694124, 1e-10);
+            let t = StudentsT::new_rs(10.0, 0.0, 1.0).unwrap();
+            assert_close(continuous_ppf_rs(&t.inner, 0.975), 2.2281388519649385, 1e-10);
+            let exponential = Exponential::new_rs(2.0).unwrap();
+            assert_close(continuous_ppf_rs(&exponential.inner, 0.5), 2.0 * 2.0_f64.ln(), 1e-12);
+            assert!(continuous_ppf_rs(&exponential.inner, 1.5).is_nan());
+        }
+
+        #[test]
+        fn test_continuous_ppf_inverts_cdf() {
+            let f = FDistribution::new_rs(5.0, 10.0).unwrap();
+            let beta = Beta::new_rs(2.0, 5.0).unwrap();
+            let weibull = Weibull::new_rs(1.5, 2.0).unwrap();
+            for &q in &[1e-6, 0.01, 0.3, 0.5, 0.9, 0.999] {
+                assert_close(f.inner.cdf(continuous_ppf_rs(&f.inner, q)), q, 1e-10);
+                assert_close(beta.inner.cdf(continuous_ppf_rs(&beta.inner, q)), q, 1e-10);
+                assert_close(weibull.inner.cdf(continuous_ppf_rs(&weibull.inner, q)), q, 1e-10);
+            }
+        }
+
+        #[test]
+        fn test_scale_parameterization() {
+            let gamma = Gamma::new_rs(2.0, 3.0).unwrap();
+            assert_close(statrs::statistics::Distribution::mean(&gamma.inner).unwrap(), 6.0, 1e-12);
+            assert_close(statrs::statistics::Distribution::variance(&gamma.inner).unwrap(), 18.0, 1e-12);
+            assert_eq!(Gamma::new_rs(2.0, 0.0).unwrap_err(), StatsError::InvalidInputValue);
+            assert!(Normal::new_rs(0.0, -1.0).is_err());
+        }
+    }
+
+    mod discrete_tests {
+        use super::*;
+
+        #[test]
+        fn test_discrete_step_functions() {
+            let poisson = Poisson::new_rs(3.0).unwrap();
+            let expected = 8.5 * (-3.0_f64).exp();
+            assert_close(discrete_cdf_rs(&poisson.inner, 2.5), expected, 1e-12);
+            assert_close(discrete_sf_rs(&poisson.inner, 2.0), 1.0 - expected, 1e-12);
+            assert_eq!(discrete_pmf_rs(&poisson.inner, 2.5), 0.0);
+            assert_eq!(discrete_cdf_rs(&poisson.inner, -1.0), 0.0);
+            assert_eq!(discrete_ppf_rs(&poisson.inner, 1.0), f64::INFINITY);
+            assert_eq!(discrete_ppf_rs(&poisson.inner, expected), 2.0);
+
+            let binomial = Binomial::new_rs(10, 0.5).unwrap();
+            assert_close(discrete_pmf_rs(&binomial.inner, 5.0), 252.0 / 1024.0, 1e-12);
+            assert_eq!(discrete_ppf_rs(&binomial.inner, 1.0), 10.0);
+            assert_eq!(binomial.inner.moments(), (Some(5.0), Some(2.5), Some(0.0)));
+        }
+
+        #[test]
+        fn test_discrete_ln_pmf_in_the_tail() {
+            let poisson = Poisson::new_rs(3.0).unwrap();
+            assert_close(discrete_ln_pmf_rs(&poisson.inner, 2.0), discrete_pmf_rs(&poisson.inner, 2.0).ln(), 1e-12);
+            // pmf(400) underflows to 0, its log is -3 + 400 ln 3 - ln 400!
+            assert_eq!(discrete_pmf_rs(&poisson.inner, 400.0), 0.0);
+            let expected = -3.0 + 400.0 * 3.0_f64.ln() - statrs::function::gamma::ln_gamma(401.0);
+            assert_close(discrete_ln_pmf_rs(&poisson.inner, 400.0), expected, 1e-8);
+            assert_eq!(discrete_ln_pmf_rs(&poisson.inner, 2.5), f64::NEG_INFINITY);
+        }
+
+        #[test]
+        fn test_discrete_ppf_mid_range() {
+            let poisson = Poisson::new_rs(3.0).unwrap();
+            assert_eq!(discrete_ppf_rs(&poisson.inner, 0.42), 2.0);
+            assert_eq!(discrete_ppf_rs(&poisson.inner, 0.0), 0.0);
+            assert_eq!(discrete_ppf_rs(&Poisson::new_rs(1000.0).unwrap().inner, 0.5), 1000.0);
+            let binomial = Binomial::new_rs(10, 0.5).unwrap();
+            assert_eq!(discrete_ppf_rs(&binomial.inner, 0.6), 5.0);
+            // the smallest k whose cdf reaches q, for every q in a grid
+            for i in 1..20 {
+                let q = i as f64 / 20.0;
+                let k = discrete_ppf_rs(&poisson.inner, q);
+                assert!(discrete_cdf_rs(&poisson.inner, k) >= q);
+                assert!(discrete_cdf_rs(&poisson.inner, k - 1.0) < q);
+            }
+        }
+    }
+
+    mod sampling_tests {
+        use super::*;
+        use rand::distributions::Distribution as Sampler;
+
+        #[test]
+        fn test_seeded_sampling() {
+            let normal = Normal::new_rs(1.0, 2.0).unwrap();
+            let first = sample_rs(5000, 42, |rng| normal.inner.sample(rng));
+            let second = sample_rs(5000, 42, |rng| normal.inner.sample(rng));
+            assert_eq!(first, second);
+            assert_close(first.mean().unwrap(), 1.0, 0.1);
+
+            let negative_binomial = NegativeBinomial::new_rs(4.0, 0.4).unwrap();
+            let draws = sample_rs(5000, 7, |rng| negative_binomial.inner.draw(rng));
+            assert!(draws.iter().all(|&k| k >= 0));
+            assert_close(draws.mapv(|k| k as f64).mean().unwrap(), 6.0, 0.3);
+        }
+    }
+
+    mod fitting_tests {
+        use super::*;
+
+        fn positive_sample() -> ndarray::Array1<f64> {
+            ndarray::array![2.1, 3.4, 1.9, 5.6, 4.4, 3.8, 2.7, 6.1, 3.3, 4.9, 0.8, 2.2]
+        }
+
+        // The estimates must beat small moves of every parameter
+        fn assert_local_maximum(family: FitFamily, x: &ndarray::Array1<f64>, estimates: &[f64]) {
+            let best = family.log_likelihood(estimates, x);
+            for i in 0..estimates.len() {
+                for &factor in &[0.999, 1.001] {
+                    let mut moved = estimates.to_vec();
+                    moved[i] *= factor;
+                    assert!(family.log_likelihood(&moved, x) < best, "{:?} parameter {} is not a maximum", family, i);
+                }
+            }
+        }
+
+        #[test]
+        fn test_fit_closed_form_standard_errors() {
+            let x = positive_sample();
+            let n = x.len() as f64;
+            let normal = fit_rs(FitFamily::Normal, &x).unwrap();
+            let scale = normal.estimates[1];
+            assert_close(normal.estimates[0], x.mean().unwrap(), 1e-12);
+            assert_close(normal.standard_errors[0], scale / n.sqrt(), 1e-4 * scale);
+            assert_close(normal.standard_errors[1], scale / (2.0 * n).sqrt(), 1e-4 * scale);
+            assert_close(normal.aic, 4.0 - 2.0 * normal.log_likelihood, 1e-12);
+            assert!(normal.ks_statistic.is_some());
+
+            let exponential = fit_rs(FitFamily::Exponential, &x).unwrap();
+            assert_close(exponential.standard_errors[0], exponential.estimates[0] / n.sqrt(), 1e-4);
+
+            let counts = ndarray::array![0.0, 2.0, 1.0, 3.0, 1.0, 0.0, 4.0, 2.0, 1.0, 2.0];
+            let poisson = fit_rs(FitFamily::Poisson, &counts).unwrap();
+            assert_close(poisson.standard_errors[0], (1.6_f64 / 10.0).sqrt(), 1e-5);
+            assert_eq!(poisson.ks_p_value, None);
+        }
+
+        #[test]
+        fn test_fit_numerical_families() {
+            let x = positive_sample();
+            for family in [FitFamily::Gamma, FitFamily::Weibull, FitFamily::LogNormal] {
+                let result = fit_rs(family, &x).unwrap();
+                assert_local_maximum(family, &x, &result.estimates);
+                assert!(result.standard_errors.iter().all(|se| se.is_finite() && *se > 0.0));
+            }
+
+            let proportions = x.mapv(|v| v / 7.0);
+            let beta = fit_rs(FitFamily::Beta, &proportions).unwrap();
+            assert_local_maximum(FitFamily::Beta, &proportions, &beta.estimates);
+
+            let counts = ndarray::array![0.0, 5.0, 1.0, 9.0, 0.0, 2.0, 14.0, 3.0, 0.0, 6.0, 1.0, 7.0];
+            let negative_binomial = fit_rs(FitFamily::NegativeBinomial, &counts).unwrap();
+            assert_local_maximum(FitFamily::NegativeBinomial, &counts, &negative_binomial.estimates);
+            let (r, p) = (negative_binomial.estimates[0], negative_binomial.estimates[1]);
+            assert_close(r * (1.0 - p) / p, counts.mean().unwrap(), 1e-8);
+        }
+
+        #[test]
+        fn test_fit_discrete_tail_data() {
+            // pmf(200; mu) underflows for mu near the bulk, the fit must still see a finite likelihood
+            let mut counts = ndarray::Array1::from_elem(50, 1.0);
+            counts[49] = 200.0;
+            let poisson = fit_rs(FitFamily::Poisson, &counts).unwrap();
+            assert!(poisson.log_likelihood.is_finite() && poisson.aic.is_finite());
+            assert!(FitFamily::Poisson.log_likelihood(&[0.5], &counts).is_finite());
+            assert_close(poisson.estimates[0], counts.mean().unwrap(), 1e-12);
+            assert!(poisson.standard_errors[0].is_finite());
+        }
+
+        #[test]
+        fn test_fit_rejects_bad_input() {
+            let underdispersed = ndarray::array![2.0, 3.0, 2.0, 3.0, 2.0];
+            assert_eq!(fit_rs(FitFamily::NegativeBinomial, &underdispersed), Err(StatsError::NoSolution));
+            assert_eq!(fit_rs(FitFamily::Gamma, &ndarray::array![1.0, -2.0, 3.0]), Err(StatsError::InvalidInputValue));
+            assert_eq!(fit_rs(FitFamily::Poisson, &ndarray::array![1.0, 2.5]), Err(StatsError::InvalidInputValue));
+            assert_eq!(FitFamily::parse("binomial", None), Err(StatsError::InvalidInputValue));
+        }
+    }
+}
@@ -0,0 +1,306 @@
+// 2x2 contingency table tests
+// Tables are laid out [[a, b], [c, d]], the columns being the two samples and the
+// first row counting successes.
+// fisher_exact_test -- conditional exact test, odds ratio by conditional MLE
+// barnard_exact_test -- unconditional, wald statistic maximised over the nuisance proportion
+// boschloo_exact_test -- unconditional, fisher's p-value used as the test statistic
+
+use ndarray::Array2;
+use pyo3::prelude::*;
+use statrs::function::factorial::ln_binomial;
+
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::utils::from_pyarray2;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Table2x2 {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+    pub d: u64,
+}
+
+impl Table2x2 {
+    pub fn from_array(table: &Array2<f64>) -> Result<Table2x2, StatsError> {
+        if table.shape() != [2, 2] { return Err(StatsError::InvalidShape); }
+        if table.iter().any(|&v| !v.is_finite() || v < 0.0 || v.fract() != 0.0) {
+            return Err(StatsError::InvalidInputValue);
+        }
+        Ok(Table2x2 {
+            a: table[[0, 0]] as u64,
+            b: table[[0, 1]] as u64,
+            c: table[[1, 0]] as u64,
+            d: table[[1, 1]] as u64,
+        })
+    }
+}
+
+// Distribution of the top-left cell given all margins, the noncentrality psi being the odds ratio.
+// Everything is kept as log densities so large counts never overflow.
+pub struct NoncentralHypergeometric {
+    pub lo: u64,
+    pub hi: u64,
+    log_dc: Vec<f64>,
+}
+
+impl NoncentralHypergeometric {
+    pub fn new(table: &Table2x2) -> NoncentralHypergeometric {
+        let m = table.a + table.c; // first column total
+        let n = table.b + table.d; // second column total
+        let k = table.a + table.b; // first row total
+        let lo = k.saturating_sub(n);
+        let hi = k.min(m);
+        let log_total = ln_binomial(m + n, k);
+        let log_dc = (lo..=hi)
+            .map(|x| ln_binomial(m, x) + ln_binomial(n, k - x) - log_total)
+            .collect();
+        NoncentralHypergeometric { lo, hi, log_dc }
+    }
+
+    pub fn support(&self) -> impl Iterator<Item = u64> {
+        self.lo..=self.hi
+    }
+
+    // Probabilities over the support for odds ratio exp(log_psi)
+    pub fn pmf(&self, log_psi: f64) -> Vec<f64> {
+        let weights: Vec<f64> = self.log_dc.iter().zip(self.support())
+            .map(|(&l, x)| l + log_psi * x as f64)
+            .collect();
+        let max_weight = weights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let unnormalized: Vec<f64> = weights.iter().map(|w| (w - max_weight).exp()).collect();
+        let total: f64 = unnormalized.iter().sum();
+        unnormalized.iter().map(|w| w / total).collect()
+    }
+
+    pub fn mean(&self, log_psi: f64) -> f64 {
+        self.pmf(log_psi).iter().zip(self.support())
+            .map(|(p, x)| p * x as f64)
+            .sum()
+    }
+
+    // P(X <= q) or P(X >= q) when upper
+    pub fn tail(&self, q: u64, log_psi: f64, upper: bool) -> f64 {
+        self.pmf(log_psi).iter().zip(self.support())
+            .filter(|(_, x)| if upper { *x >= q } else { *x <= q })
+            .map(|(p, _)| p)
+            .sum()
+    }
+
+    // Central (psi = 1) p-value of observing x, two sided sums every table no more likely than x
+    pub fn p_value(&self, x: u64, alternative: Alternative) -> f64 {
+        let p = match alternative {
+            Alternative::Less => self.tail(x, 0.0, false),
+            Alternative::Greater => self.tail(x, 0.0, true),
+            Alternative::TwoSided => {
+                // relative tolerance guards against tables that only differ by rounding
+                let cutoff = self.log_dc[(x - self.lo) as usize] + (1.0 + 1e-7f64).ln();
+                self.log_dc.iter().filter(|&&l| l <= cutoff).map(|l| l.exp()).sum()
+            }
+        };
+        p.min(1.0)
+    }
+}
+
+// Solves g(log_psi) = target for g increasing, widening the bracket until it holds
+fn solve_log_psi<F: Fn(f64) -> f64>(g: F, target: f64) -> f64 {
+    let (mut lo, mut hi) = (-1.0, 1.0);
+    while g(lo) > target && lo > -700.0 { lo *= 2.0; }
+    while g(hi) < target && hi < 700.0 { hi *= 2.0; }
+    crate::utils::bisect_rs(|l| g(l) - target, lo, hi, 1e-12)
+}
+
+// Conditional maximum likelihood estimate of the odds ratio, NaN when a zero margin fixes
+// the table and the likelihood does not depend on the odds ratio at all
+pub fn conditional_odds_ratio_rs(table: &Table2x2) -> f64 {
+    let dist = NoncentralHypergeometric::new(table);
+    if dist.lo == dist.hi { return f64::NAN; }
+    if table.a == dist.lo { return 0.0; }
+    if table.a == dist.hi { return f64::INFINITY; }
+    solve_log_psi(|l| dist.mean(l), table.a as f64).exp()
+}
+
+// Exact conditional confidence interval for the odds ratio
+pub fn odds_ratio_interval_rs(table: &Table2x2, confidence_level: f64, alternative: Alternative) -> (f64, f64) {
+    let dist = NoncentralHypergeometric::new(table);
+    let x = table.a;
+    // upper tail P(X >= x) increases with psi, lower tail P(X <= x) decreases
+    let lower_bound = |alpha: f64| {
+        if x == dist.lo { 0.0 } else { solve_log_psi(|l| dist.tail(x, l, true), alpha).exp() }
+    };
+    let upper_bound = |alpha: f64| {
+        if x == dist.hi { f64::INFINITY } else { solve_log_psi(|l| -dist.tail(x, l, false), -alpha).exp() }
+    };
+    let alpha = 1.0 - confidence_level;
+    match alternative {
+        Alternative::TwoSided => (lower_bound(alpha / 2.0), upper_bound(alpha / 2.0)),
+        Alternative::Less => (0.0, upper_bound(alpha)),
+        Alternative::Greater => (lower_bound(alpha), f64::INFINITY),
+    }
+}
+
+// Returns (conditional odds ratio, p, (ci lower, ci upper))
+pub fn fisher_exact_rs(table: &Table2x2, alternative: Alternative,
+                       confidence_level: f64) -> Result<(f64, f64, (f64, f64)), StatsError> {
+    if confidence_level <= 0.0 || confidence_level >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    let dist = NoncentralHypergeometric::new(table);
+    let p = dist.p_value(table.a, alternative);
+    let odds_ratio = conditional_odds_ratio_rs(table);
+    let interval = odds_ratio_interval_rs(table, confidence_level, alternative);
+    Ok((odds_ratio, p, interval))
+}
+
+// log Binomial(n, pi) probabilities for 0..=n
+fn binomial_log_pmf(n: u64, pi: f64) -> Vec<f64> {
+    (0..=n).map(|x| {
+        let xf = x as f64;
+        let nf = n as f64;
+        // 0 * ln(0) is taken as 0 at the edges of the nuisance range
+        let success = if x == 0 { 0.0 } else { xf * pi.ln() };
+        let failure = if x == n { 0.0 } else { (nf - xf) * (1.0 - pi).ln() };
+        ln_binomial(n, x) + success + failure
+    }).collect()
+}
+
+// Largest probability of the extreme tables over the nuisance proportion, grid search
+// with `n_grid` points followed by a golden section refinement around the best point.
+fn maximize_nuisance(extreme: &[(u64, u64)], n1: u64, n2: u64, n_grid: usize) -> f64 {
+    let probability = |pi: f64| -> f64 {
+        let lp1 = binomial_log_pmf(n1, pi);
+        let lp2 = binomial_log_pmf(n2, pi);
+        extreme.iter().map(|&(x1, x2)| (lp1[x1 as usize] + lp2[x2 as usize]).exp()).sum()
+    };
+
+    let step = 1.0 / (n_grid + 1) as f64;
+    let (mut best_pi, mut best_p) = (step, f64::NEG_INFINITY);
+    for i in 1..=n_grid {
+        let pi = i as f64 * step;
+        let p = probability(pi);
+        if p > best_p {
+            best_p = p;
+            best_pi = pi;
+        }
+    }
+
+    let golden = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = ((best_pi - step).max(1e-12), (best_pi + step).min(1.0 - 1e-12));
+    for _ in 0..60 {
+        let left = hi - golden * (hi - lo);
+        let right = lo + golden * (hi - lo);
+        if probability(left) > probability(right) { hi = right; } else { lo = left; }
+    }
+    best_p.max(probability(0.5 * (lo + hi))).min(1.0)
+}
+
+fn sample_sizes(table: &Table2x2) -> Result<(u64, u64), StatsError> {
+    let (n1, n2) = (table.a + table.c, table.b + table.d);
+    if n1 == 0 || n2 == 0 { return Err(StatsError::InvalidInputValue); }
+    Ok((n1, n2))
+}
+
+// Returns (wald statistic, p)
+pub fn barnard_exact_rs(table: &Table2x2, alternative: Alternative,
+                        pooled: bool, n_grid: usize) -> Result<(f64, f64), StatsError> {
+    let (n1, n2) = sample_sizes(table)?;
+    if n_grid < 1 { return Err(StatsError::InvalidInputValue); }
+    let (n1f, n2f) = (n1 as f64, n2 as f64);
+    let wald = |x1: u64, x2: u64| -> f64 {
+        let (p1, p2) = (x1 as f64 / n1f, x2 as f64 / n2f);
+        let variance = if pooled {
+            let p = (x1 + x2) as f64 / (n1f + n2f);
+            p * (1.0 - p) * (1.0 / n1f + 1.0 / n2f)
+        } else {
+            p1 * (1.0 - p1) / n1f + p2 * (1.0 - p2) / n2f
+        };
+        // all-success or all-failure tables carry no evidence either way
+        if variance <= 0.0 { 0.0 } else { (p1 - p2) / variance.sqrt() }
+    };
+
+    let observed = wald(table.a, table.b);
+    let tol = 1e-9;
+    let mut extreme = Vec::new();
+    for x1 in 0..=n1 {
+        for x2 in 0..=n2 {
+            let t = wald(x1, x2);
+            let is_extreme = match alternative {
+                Alternative::Greater => t >= observed - tol,
+                Alternative::Less => t <= observed + tol,
+                Alternative::TwoSided => t.abs() >= observed.abs() - tol,
+            };
+            if is_extreme { extreme.push((x1, x2)); }
+        }
+    }
+    Ok((observed, maximize_nuisance(&extreme, n1, n2, n_grid)))
+}
+
+// Returns (fisher p-value of the observed table, p)
+pub fn boschloo_exact_rs(table: &Table2x2, alternative: Alternative,
+                         n_grid: usize) -> Result<(f64, f64), StatsError> {
+    let (n1, n2) = sample_sizes(table)?;
+    if n_grid < 1 { return Err(StatsError::InvalidInputValue); }
+    let fisher_p = |x1: u64, x2: u64| -> f64 {
+        let candidate = Table2x2 { a: x1, b: x2, c: n1 - x1, d: n2 - x2 };
+        NoncentralHypergeometric::new(&candidate).p_value(x1, alternative)
+    };
+
+    let observed = fisher_p(table.a, table.b);
+    let cutoff = observed * (1.0 + 1e-7);
+    let mut extreme = Vec::new();
+    for x1 in 0..=n1 {
+        for x2 in 0..=n2 {
+            if fisher_p(x1, x2) <= cutoff { extreme.push((x1, x2)); }
+        }
+    }
+    Ok((observed, maximize_nuisance(&extreme, n1, n2, n_grid)))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (table, alternative = "two-sided", confidence_level = 0.95))]
+pub fn fisher_exact_test(table: &PyAny, alternative: &str,
+                         confidence_level: f64) -> PyResult<TestResult> {
+    // The statistic and estimate are the conditional MLE odds ratio, with its confidence interval
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let table = Table2x2::from_array(&table_data)?;
+    let alternative = Alternative::parse(alternative)?;
+    let (odds_ratio, p, interval) = fisher_exact_rs(&table, alternative, confidence_level)?;
+    Ok(TestResult::new("Fisher's exact test", odds_ratio, p)
+        .with_alternative(alternative)
+        .with_estimate(odds_ratio)
+        .with_confidence_interval(interval))
+}
+
+#[pyfunction]
+#[pyo3(signature = (table, alternative = "two-sided", pooled = true, n = 32))]
+pub fn barnard_exact_test(table: &PyAny, alternative: &str, pooled: bool, n: usize) -> PyResult<TestResult> {
+    // n is the number of grid points searched for the nuisance proportion
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let table = Table2x2::from_array(&table_data)?;
+    let alternative = Alternative::parse(alternative)?;
+    let (statistic, p) = barnard_exact_rs(&table, alternative, pooled, n)?;
+    Ok(TestResult::new("Barnard's exact test", statistic, p).with_alternative(alternative))
+}
+
+#[pyfunction]
+#[pyo3(signature = (table, alternative = "two-sided", n = 32))]
+pub fn boschloo_exact_test(table: &PyAny, alternative: &str, n: usize) -> PyResult<TestResult> {
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let table = Table2x2::from_array(&table_data)?;
+    let alternative = Alternative::parse(alternative)?;
+    let (statistic, p) = boschloo_exact_rs(&table, alternative, n)?;
+    Ok(TestResult::new("Boschloo's exact test", statistic, p).with_alternative(alternative))
+}
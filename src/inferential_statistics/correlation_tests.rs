@@ -0,0 +1,361 @@
+// Correlation significance tests
+// pearson_test -- t test of r, Fisher z confidence interval
+// spearman_test -- t approximation on the rank correlation
+// kendall_tau_test -- tau-b, exact null distribution without ties or the tie corrected normal approximation
+// compare_correlations -- Fisher z test for correlations from two independent samples
+// steiger_test -- two dependent correlations from one sample, overlapping (r_xy vs r_xz) or
+//                 not (r_wx vs r_yz), with Steiger's (1980) pooled covariance
+// partial_correlation -- partial or semi-partial correlation controlling for covariates
+//
+// The test statistic goes in `statistic`: t for pearson, spearman and partial correlations, z for the
+// comparisons, and for kendall the tie corrected z, or C - D when the exact distribution is used.
+// The correlation, or the difference of the two correlations being compared, goes in `estimate`.
+
+use ndarray::{Array1, Array2};
+use pyo3::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+use crate::descriptive_statistics::correlation_rs;
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::rank_tests::Method;
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::utils::{from_pyarray1, from_pyarray2, rank_rs, residualize_rs};
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PartialKind {
+    Partial,
+    // covariates removed from x only
+    Semipartial,
+}
+
+impl PartialKind {
+    pub fn parse(kind: &str) -> Result<PartialKind, StatsError> {
+        match kind {
+            "partial" => Ok(PartialKind::Partial),
+            "semipartial" => Ok(PartialKind::Semipartial),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+fn check_pair(x: &Array1<f64>, y: &Array1<f64>, min_len: usize) -> Result<(), StatsError> {
+    if x.len() != y.len() { return Err(StatsError::InconsistentLength); }
+    if x.len() < min_len { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+fn t_p_value(t: f64, df: f64, alternative: Alternative) -> f64 {
+    // |r| = 1 gives an infinite t
+    if t.is_infinite() {
+        let upper = t > 0.0;
+        return match alternative {
+            Alternative::TwoSided => 0.0,
+            Alternative::Greater => if upper { 0.0 } else { 1.0 },
+            Alternative::Less => if upper { 1.0 } else { 0.0 },
+        };
+    }
+    let dist = StudentsT::new(0.0, 1.0, df).unwrap();
+    match alternative {
+        Alternative::TwoSided => (2.0 * dist.sf(t.abs())).min(1.0),
+        Alternative::Greater => dist.sf(t),
+        Alternative::Less => dist.cdf(t),
+    }
+}
+
+// t statistic of a correlation with df degrees of freedom
+fn correlation_t(r: f64, df: f64) -> f64 {
+    if r.abs() >= 1.0 { r.signum() * f64::INFINITY } else { r * (df / (1.0 - r * r)).sqrt() }
+}
+
+// Fisher z interval for r, one sided bounds for one sided alternatives. se is on the z scale.
+pub fn fisher_z_interval_rs(r: f64, se: f64, confidence_level: f64, alternative: Alternative) -> Result<(f64, f64), StatsError> {
+    if confidence_level <= 0.0 || confidence_level >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    let z = r.clamp(-1.0, 1.0).atanh();
+    Ok(match alternative {
+        Alternative::TwoSided => {
+            let critical = normal.inverse_cdf(0.5 + confidence_level / 2.0);
+            ((z - critical * se).tanh(), (z + critical * se).tanh())
+        }
+        Alternative::Greater => ((z - normal.inverse_cdf(confidence_level) * se).tanh(), 1.0),
+        Alternative::Less => (-1.0, (z + normal.inverse_cdf(confidence_level) * se).tanh()),
+    })
+}
+
+pub fn pearson_test_rs(x: &Array1<f64>, y: &Array1<f64>, alternative: Alternative,
+                       confidence_level: f64) -> Result<TestResult, StatsError> {
+    check_pair(x, y, 3)?;
+    let r = correlation_rs(x, y);
+    if r.is_nan() { return Err(StatsError::ZeroVariance); }
+    let n = x.len() as f64;
+    let df = n - 2.0;
+    let t = correlation_t(r, df);
+    let mut result = TestResult::new("Pearson correlation test", t, t_p_value(t, df, alternative))
+        .with_df(df)
+        .with_alternative(alternative)
+        .with_estimate(r);
+    if n > 3.0 {
+        result = result.with_confidence_interval(fisher_z_interval_rs(r, 1.0 / (n - 3.0).sqrt(), confidence_level, alternative)?);
+    }
+    Ok(result)
+}
+
+pub fn spearman_test_rs(x: &Array1<f64>, y: &Array1<f64>, alternative: Alternative) -> Result<TestResult, StatsError> {
+    check_pair(x, y, 3)?;
+    let (x_ranks, _) = rank_rs(&x.to_vec());
+    let (y_ranks, _) = rank_rs(&y.to_vec());
+    let rho = correlation_rs(&x_ranks, &y_ranks);
+    if rho.is_nan() { return Err(StatsError::ZeroVariance); }
+    let df = x.len() as f64 - 2.0;
+    let t = correlation_t(rho, df);
+    Ok(TestResult::new("Spearman rank correlation test", t, t_p_value(t, df, alternative))
+        .with_df(df)
+        .with_alternative(alternative)
+        .with_estimate(rho))
+}
+
+// Number of permutations of n items with k inversions (Mahonian numbers), k = 0..n(n-1)/2
+fn inversion_counts(n: usize) -> Vec<f64> {
+    let mut counts = vec![1.0];
+    for i in 2..=n {
+        let mut next = vec![0.0; counts.len() + i - 1];
+        // next[k] = sum of counts[k - j] for j < i, kept as a sliding window
+        let mut window = 0.0;
+        for (k, value) in next.iter_mut().enumerate() {
+            if k < counts.len() { window += counts[k]; }
+            if k >= i && k - i < counts.len() { window -= counts[k - i]; }
+            *value = window;
+        }
+        counts = next;
+    }
+    counts
+}
+
+pub fn kendall_tau_test_rs(x: &Array1<f64>, y: &Array1<f64>, alternative: Alternative,
+                           method: Method) -> Result<TestResult, StatsError> {
+    check_pair(x, y, 2)?;
+    let n = x.len();
+    let (mut concordant, mut discordant) = (0.0, 0.0);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            // pairs tied on either variable are neither concordant nor discordant
+            if x[i] == x[j] || y[i] == y[j] { continue; }
+            if (x[i] - x[j]) * (y[i] - y[j]) > 0.0 { concordant += 1.0 } else { discordant += 1.0 }
+        }
+    }
+    let (_, x_ties) = rank_rs(&x.to_vec());
+    let (_, y_ties) = rank_rs(&y.to_vec());
+    let tie_pairs = |ties: &[usize]| ties.iter().map(|&t| (t * (t - 1)) as f64 / 2.0).sum::<f64>();
+    let pairs = (n * (n - 1)) as f64 / 2.0;
+    let (x_tied, y_tied) = (tie_pairs(&x_ties), tie_pairs(&y_ties));
+    if x_tied == pairs || y_tied == pairs { return Err(StatsError::ZeroVariance); }
+    let tau = (concordant - discordant) / ((pairs - x_tied) * (pairs - y_tied)).sqrt();
+
+    let has_ties = x_tied > 0.0 || y_tied > 0.0;
+    let exact = match method {
+        Method::Exact => {
+            if has_ties { return Err(StatsError::InvalidInputValue); }
+            true
+        }
+        Method::Auto => !has_ties && n <= 50,
+        Method::Asymptotic => false,
+    };
+
+    let (statistic, p) = if exact {
+        // without ties the discordant pairs are the inversions of a random permutation
+        let counts = inversion_counts(n);
+        let total: f64 = counts.iter().sum();
+        let d = discordant as usize;
+        let at_most = counts[..=d].iter().sum::<f64>() / total;
+        let at_least = counts[d..].iter().sum::<f64>() / total;
+        let p = match alternative {
+            Alternative::Greater => at_most,
+            Alternative::Less => at_least,
+            Alternative::TwoSided => (2.0 * at_most.min(at_least)).min(1.0),
+        };
+        (concordant - discordant, p)
+    } else {
+        // tie corrected variance of C - D
+        let nf = n as f64;
+        let sums = |ties: &[usize]| ties.iter().fold((0.0, 0.0, 0.0), |(t0, t1, t2), &t| {
+            let t = t as f64;
+            (t0 + t * (t - 1.0), t1 + t * (t - 1.0) * (t - 2.0), t2 + t * (t - 1.0) * (2.0 * t + 5.0))
+        });
+        let (x_tie, x0, x1) = sums(&x_ties);
+        let (y_tie, y0, y1) = sums(&y_ties);
+        let m = nf * (nf - 1.0);
+        let mut var = (m * (2.0 * nf + 5.0) - x1 - y1) / 18.0 + 2.0 * x_tie * y_tie / m;
+        if n > 2 { var += x0 * y0 / (9.0 * m * (nf - 2.0)); }
+        let z = (concordant - discordant) / var.sqrt();
+        (z, alternative.normal_p_value(z))
+    };
+    let name = if exact { "Kendall's tau-b test (exact)" } else { "Kendall's tau-b test" };
+    Ok(TestResult::new(name, statistic, p).with_alternative(alternative).with_estimate(tau))
+}
+
+pub fn compare_correlations_rs(r1: f64, n1: usize, r2: f64, n2: usize, alternative: Alternative) -> Result<TestResult, StatsError> {
+    if n1 < 4 || n2 < 4 { return Err(StatsError::InvalidInputValue); }
+    if r1.abs() >= 1.0 || r2.abs() >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    let se = (1.0 / (n1 as f64 - 3.0) + 1.0 / (n2 as f64 - 3.0)).sqrt();
+    let z = (r1.atanh() - r2.atanh()) / se;
+    Ok(TestResult::new("Fisher z test for two independent correlations", z, alternative.normal_p_value(z))
+        .with_alternative(alternative)
+        .with_estimate(r1 - r2))
+}
+
+// Steiger's z for r_jk against r_hm given the covariance term psi, computed at the pooled r
+fn steiger_z(r_jk: f64, r_hm: f64, psi: f64, r_bar: f64, n: usize) -> f64 {
+    let c = psi / (1.0 - r_bar * r_bar).powi(2);
+    (r_jk.atanh() - r_hm.atanh()) * (n as f64 - 3.0).sqrt() / (2.0 - 2.0 * c).sqrt()
+}
+
+fn check_correlations(correlations: &[f64], n: usize) -> Result<(), StatsError> {
+    if n < 4 { return Err(StatsError::InvalidInputValue); }
+    if correlations.iter().any(|r| r.abs() >= 1.0 || r.is_nan()) { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+// r_xy against r_xz, which share x
+pub fn steiger_overlapping_rs(r_xy: f64, r_xz: f64, r_yz: f64, n: usize, alternative: Alternative) -> Result<TestResult, StatsError> {
+    check_correlations(&[r_xy, r_xz, r_yz], n)?;
+    let r_bar = (r_xy + r_xz) / 2.0;
+    let r2 = r_bar * r_bar;
+    let psi = r_yz * (1.0 - 2.0 * r2) - 0.5 * r2 * (1.0 - 2.0 * r2 - r_yz * r_yz);
+    let z = steiger_z(r_xy, r_xz, psi, r_bar, n);
+    Ok(TestResult::new("Steiger's test for dependent overlapping correlations", z, alternative.normal_p_value(z))
+        .with_alternative(alternative)
+        .with_estimate(r_xy - r_xz))
+}
+
+// r_wx against r_yz, four distinct variables
+#[allow(clippy::too_many_arguments)]
+pub fn steiger_nonoverlapping_rs(r_wx: f64, r_yz: f64, r_wy: f64, r_wz: f64, r_xy: f64, r_xz: f64,
+                                 n: usize, alternative: Alternative) -> Result<TestResult, StatsError> {
+    check_correlations(&[r_wx, r_yz, r_wy, r_wz, r_xy, r_xz], n)?;
+    let r_bar = (r_wx + r_yz) / 2.0;
+    // Pearson-Filon covariance of r_jk and r_hm (j = w, k = x, h = y, m = z) with r_bar for both
+    let psi = 0.5 * ((r_wy - r_bar * r_xy) * (r_xz - r_xy * r_bar)
+        + (r_wz - r_wy * r_bar) * (r_xy - r_bar * r_wy)
+        + (r_wy - r_wz * r_bar) * (r_xz - r_bar * r_wz)
+        + (r_wz - r_bar * r_xz) * (r_xy - r_xz * r_bar));
+    let z = steiger_z(r_wx, r_yz, psi, r_bar, n);
+    Ok(TestResult::new("Steiger's test for dependent non-overlapping correlations", z, alternative.normal_p_value(z))
+        .with_alternative(alternative)
+        .with_estimate(r_wx - r_yz))
+}
+
+// covariates is n x k, one column per covariate
+pub fn partial_correlation_rs(x: &Array1<f64>, y: &Array1<f64>, covariates: &Array2<f64>, kind: PartialKind,
+                              alternative: Alternative, confidence_level: f64) -> Result<TestResult, StatsError> {
+    if covariates.nrows() != x.len() { return Err(StatsError::InconsistentLength); }
+    let k = covariates.ncols() as f64;
+    check_pair(x, y, covariates.ncols() + 3)?;
+
+    let x_residual = residualize_rs(x, covariates);
+    let r = match kind {
+        PartialKind::Partial => correlation_rs(&x_residual, &residualize_rs(y, covariates)),
+        PartialKind::Semipartial => correlation_rs(&x_residual, y),
+    };
+    if r.is_nan() { return Err(StatsError::ZeroVariance); }
+
+    let n = x.len() as f64;
+    let df = n - 2.0 - k;
+    let t = correlation_t(r, df);
+    let name = match kind {
+        PartialKind::Partial => "Partial correlation test",
+        PartialKind::Semipartial => "Semi-partial correlation test",
+    };
+    let mut result = TestResult::new(name, t, t_p_value(t, df, alternative))
+        .with_df(df)
+        .with_alternative(alternative)
+        .with_estimate(r);
+    if n - 3.0 - k > 0.0 {
+        result = result.with_confidence_interval(fisher_z_interval_rs(r, 1.0 / (n - 3.0 - k).sqrt(), confidence_level, alternative)?);
+    }
+    Ok(result)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alternative = "two-sided", confidence_level = 0.95))]
+pub fn pearson_test(x: &PyAny, y: &PyAny, alternative: &str, confidence_level: f64) -> PyResult<TestResult> {
+    let (x_data, y_data) = convert_pair(x, y)?;
+    Ok(pearson_test_rs(&x_data, &y_data, Alternative::parse(alternative)?, confidence_level)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alternative = "two-sided"))]
+pub fn spearman_test(x: &PyAny, y: &PyAny, alternative: &str) -> PyResult<TestResult> {
+    let (x_data, y_data) = convert_pair(x, y)?;
+    Ok(spearman_test_rs(&x_data, &y_data, Alternative::parse(alternative)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alternative = "two-sided", method = "auto"))]
+pub fn kendall_tau_test(x: &PyAny, y: &PyAny, alternative: &str, method: &str) -> PyResult<TestResult> {
+    // method: auto (exact for n <= 50 without ties), exact or asymptotic
+    // statistic is C - D for the exact test and the tie corrected z otherwise, tau is the estimate
+    let (x_data, y_data) = convert_pair(x, y)?;
+    Ok(kendall_tau_test_rs(&x_data, &y_data, Alternative::parse(alternative)?, Method::parse(method)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (r1, n1, r2, n2, alternative = "two-sided"))]
+pub fn compare_correlations(r1: f64, n1: usize, r2: f64, n2: usize, alternative: &str) -> PyResult<TestResult> {
+    Ok(compare_correlations_rs(r1, n1, r2, n2, Alternative::parse(alternative)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (r_xy, r_xz, r_yz, n, alternative = "two-sided"))]
+pub fn steiger_test(r_xy: f64, r_xz: f64, r_yz: f64, n: usize, alternative: &str) -> PyResult<TestResult> {
+    // Compares r_xy with r_xz measured on the same n subjects
+    Ok(steiger_overlapping_rs(r_xy, r_xz, r_yz, n, Alternative::parse(alternative)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (r_wx, r_yz, r_wy, r_wz, r_xy, r_xz, n, alternative = "two-sided"))]
+#[allow(clippy::too_many_arguments)]
+pub fn steiger_test_nonoverlapping(r_wx: f64, r_yz: f64, r_wy: f64, r_wz: f64, r_xy: f64, r_xz: f64,
+                                   n: usize, alternative: &str) -> PyResult<TestResult> {
+    // Compares r_wx with r_yz measured on the same n subjects
+    Ok(steiger_nonoverlapping_rs(r_wx, r_yz, r_wy, r_wz, r_xy, r_xz, n, Alternative::parse(alternative)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, covariates, kind = "partial", alternative = "two-sided", confidence_level = 0.95))]
+pub fn partial_correlation(x: &PyAny, y: &PyAny, covariates: &PyAny, kind: &str, alternative: &str,
+                           confidence_level: f64) -> PyResult<TestResult> {
+    // covariates: 1d array for a single covariate or an n x k array
+    // kind: partial, or semipartial to remove the covariates from x only
+    let (x_data, y_data) = convert_pair(x, y)?;
+    let covariate_data = match from_pyarray2(covariates) {
+        Ok(data) => data,
+        Err(_e) => match from_pyarray1(covariates) {
+            Ok(data) => data.insert_axis(ndarray::Axis(1)),
+            Err(_e) => return Err(StatsError::Conversion.into()),
+        },
+    };
+    validate_statistical_input!(basic, covariate_data);
+    Ok(partial_correlation_rs(&x_data, &y_data, &covariate_data, PartialKind::parse(kind)?,
+                              Alternative::parse(alternative)?, confidence_level)?)
+}
+
+fn convert_pair(x: &PyAny, y: &PyAny) -> PyResult<(Array1<f64>, Array1<f64>)> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+    Ok((x_data, y_data))
+}
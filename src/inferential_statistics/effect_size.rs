@@ -0,0 +1,545 @@
+// Effect sizes with confidence intervals
+// cohens_d -- standardized mean difference, pooled / av / rm standardizers
+// hedges_g -- cohen's d with the small sample bias correction
+// glass_delta -- mean difference standardized by the control (y) group sd
+// anova_effect_sizes -- eta squared, partial eta squared and omega squared from an anova table
+// cliffs_delta -- dominance of x over y, P(x > y) - P(x < y)
+// rank_biserial -- rank based correlation for mann-whitney / wilcoxon designs
+// odds_ratio, risk_ratio -- from a 2x2 table laid out as in contingency_tests
+//
+// Intervals come from the noncentral t / F distributions where those apply and from
+// asymptotic standard errors on a transformed scale otherwise. The rank based and ratio
+// estimators can take a percentile bootstrap interval instead.
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use statrs::distribution::{ContinuousCDF, Normal};
+use statrs::function::gamma::ln_gamma;
+
+use crate::descriptive_statistics::{correlation_rs, mean_rs, sample_variance_rs};
+use crate::distributions::noncentral::{noncentral_f_cdf, noncentral_t_cdf};
+use crate::inferential_statistics::contingency_tests::Table2x2;
+use crate::inferential_statistics::errors::*;
+use crate::resampling::{bootstrap_interval_rs, bootstrap_samples_rs, Interval};
+use crate::utils::{bisect_rs, from_pyarray1, from_pyarray2, rank_rs};
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CohensD {
+    // independent groups, pooled sd
+    Pooled,
+    // paired, average of the two sds
+    Av,
+    // paired, repeated measures standardizer accounting for the correlation
+    Rm,
+}
+
+impl CohensD {
+    pub fn parse(kind: &str) -> Result<CohensD, StatsError> {
+        match kind {
+            "pooled" => Ok(CohensD::Pooled),
+            "av" => Ok(CohensD::Av),
+            "rm" => Ok(CohensD::Rm),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// How the rank based and ratio estimators build their interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EffectInterval {
+    // the estimator's own asymptotic interval
+    Asymptotic,
+    // percentile interval of nonparametric bootstrap replicates, each sample resampled on its own
+    Bootstrap { n_resamples: usize, seed: u64 },
+}
+
+impl EffectInterval {
+    pub fn parse(interval: &str, n_resamples: usize, seed: Option<u64>) -> Result<EffectInterval, StatsError> {
+        match interval {
+            "asymptotic" => Ok(EffectInterval::Asymptotic),
+            "bootstrap" => {
+                if n_resamples == 0 { return Err(StatsError::InvalidInputValue); }
+                Ok(EffectInterval::Bootstrap { n_resamples, seed: seed.unwrap_or_else(rand::random) })
+            }
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// confidence levels must sit strictly inside (0, 1)
+fn validate_confidence(confidence_level: f64) -> Result<(), StatsError> {
+    if confidence_level <= 0.0 || confidence_level >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+fn z_critical(confidence_level: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - (1.0 - confidence_level) / 2.0)
+}
+
+// Widening steps allowed while bracketing a noncentrality before giving up
+const MAX_BRACKET_STEPS: usize = 100;
+
+// Noncentrality parameters whose t distributions put the observed t at the
+// upper and lower alpha / 2 quantiles
+pub fn noncentral_t_interval_rs(t: f64, df: f64, confidence_level: f64) -> Result<(f64, f64), StatsError> {
+    let alpha = 1.0 - confidence_level;
+    // the cdf at t falls as the noncentrality grows
+    let solve = |target: f64| {
+        let (mut lo, mut hi) = (t - 10.0, t + 10.0);
+        let mut steps = 0;
+        while noncentral_t_cdf(t, df, lo) < target || noncentral_t_cdf(t, df, hi) > target {
+            if steps == MAX_BRACKET_STEPS { return Err(StatsError::NoSolution); }
+            if noncentral_t_cdf(t, df, lo) < target { lo -= 10.0; } else { hi += 10.0; }
+            steps += 1;
+        }
+        Ok(bisect_rs(|ncp| noncentral_t_cdf(t, df, ncp) - target, lo, hi, 1e-10))
+    };
+    Ok((solve(1.0 - alpha / 2.0)?, solve(alpha / 2.0)?))
+}
+
+// Same inversion for the noncentral F, noncentrality bounded below by zero
+pub fn noncentral_f_interval_rs(f: f64, df1: f64, df2: f64, confidence_level: f64) -> Result<(f64, f64), StatsError> {
+    let alpha = 1.0 - confidence_level;
+    let solve = |target: f64| {
+        if noncentral_f_cdf(f, df1, df2, 0.0) <= target { return Ok(0.0); }
+        let mut hi = 10.0;
+        let mut steps = 0;
+        while noncentral_f_cdf(f, df1, df2, hi) > target {
+            if steps == MAX_BRACKET_STEPS { return Err(StatsError::NoSolution); }
+            hi *= 2.0;
+            steps += 1;
+        }
+        Ok(bisect_rs(|ncp| noncentral_f_cdf(f, df1, df2, ncp) - target, 0.0, hi, 1e-10))
+    };
+    Ok((solve(1.0 - alpha / 2.0)?, solve(alpha / 2.0)?))
+}
+
+// Exact small sample correction J(df) = gamma(df/2) / (sqrt(df/2) gamma((df-1)/2))
+pub fn hedges_correction_rs(df: f64) -> f64 {
+    (ln_gamma(df / 2.0) - (df / 2.0).ln() / 2.0 - ln_gamma((df - 1.0) / 2.0)).exp()
+}
+
+// Returns (d, (lower, upper))
+pub fn cohens_d_rs(x: &Array1<f64>, y: &Array1<f64>, kind: CohensD,
+                   confidence_level: f64) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (n1, n2) = (x.len() as f64, y.len() as f64);
+    if n1 < 2.0 || n2 < 2.0 { return Err(StatsError::InvalidInputValue); }
+    let (v1, v2) = (sample_variance_rs(x), sample_variance_rs(y));
+    let mean_diff = mean_rs(x) - mean_rs(y);
+
+    match kind {
+        CohensD::Pooled => {
+            let pooled_sd = (((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0)).sqrt();
+            if pooled_sd == 0.0 { return Err(StatsError::ZeroVariance); }
+            let d = mean_diff / pooled_sd;
+            let scale = (1.0 / n1 + 1.0 / n2).sqrt();
+            let (lo, hi) = noncentral_t_interval_rs(d / scale, n1 + n2 - 2.0, confidence_level)?;
+            Ok((d, (lo * scale, hi * scale)))
+        }
+        CohensD::Av | CohensD::Rm => {
+            if x.len() != y.len() { return Err(StatsError::InconsistentLength); }
+            let diffs = x - y;
+            let sd_diff = sample_variance_rs(&diffs).sqrt();
+            if sd_diff == 0.0 || v1 == 0.0 || v2 == 0.0 { return Err(StatsError::ZeroVariance); }
+            let standardizer = match kind {
+                CohensD::Av => ((v1 + v2) / 2.0).sqrt(),
+                _ => {
+                    let r = correlation_rs(x, y);
+                    sd_diff / (2.0 * (1.0 - r)).sqrt()
+                }
+            };
+            // interval for the paired standardized difference d_z, rescaled to this standardizer
+            let d = mean_diff / standardizer;
+            let (lo, hi) = noncentral_t_interval_rs(mean_diff / sd_diff * n1.sqrt(), n1 - 1.0, confidence_level)?;
+            let scale = sd_diff / standardizer / n1.sqrt();
+            Ok((d, (lo * scale, hi * scale)))
+        }
+    }
+}
+
+pub fn hedges_g_rs(x: &Array1<f64>, y: &Array1<f64>, confidence_level: f64) -> Result<(f64, (f64, f64)), StatsError> {
+    let (d, (lo, hi)) = cohens_d_rs(x, y, CohensD::Pooled, confidence_level)?;
+    let j = hedges_correction_rs((x.len() + y.len()) as f64 - 2.0);
+    Ok((d * j, (lo * j, hi * j)))
+}
+
+pub fn glass_delta_rs(x: &Array1<f64>, y: &Array1<f64>, confidence_level: f64) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (n1, n2) = (x.len() as f64, y.len() as f64);
+    if n1 < 2.0 || n2 < 2.0 { return Err(StatsError::InvalidInputValue); }
+    let control_sd = sample_variance_rs(y).sqrt();
+    if control_sd == 0.0 { return Err(StatsError::ZeroVariance); }
+    let delta = (mean_rs(x) - mean_rs(y)) / control_sd;
+    // with equal variances delta / sqrt(1/n1 + 1/n2) is noncentral t on the control sd's n2 - 1 df
+    let scale = (1.0 / n1 + 1.0 / n2).sqrt();
+    let (lo, hi) = noncentral_t_interval_rs(delta / scale, n2 - 1.0, confidence_level)?;
+    Ok((delta, (lo * scale, hi * scale)))
+}
+
+pub struct AnovaEffectSizes {
+    pub eta_squared: f64,
+    pub partial_eta_squared: f64,
+    pub omega_squared: f64,
+    // interval for partial eta squared from the noncentral F
+    pub interval: (f64, f64),
+}
+
+pub fn anova_effect_sizes_rs(ss_effect: f64, ss_error: f64, df_effect: f64, df_error: f64,
+                             ss_total: Option<f64>, confidence_level: f64) -> Result<AnovaEffectSizes, StatsError> {
+    validate_confidence(confidence_level)?;
+    // one way designs have nothing but effect and error in the total
+    let ss_total = ss_total.unwrap_or(ss_effect + ss_error);
+    let values = [ss_effect, ss_error, df_effect, df_error, ss_total];
+    if values.iter().any(|v| !v.is_finite() || *v < 0.0) { return Err(StatsError::InvalidInputValue); }
+    if df_effect <= 0.0 || df_error <= 0.0 || ss_error == 0.0 { return Err(StatsError::InvalidInputValue); }
+    if ss_total < ss_effect + ss_error - 1e-9 * ss_total { return Err(StatsError::InvalidInputValue); }
+
+    let ms_error = ss_error / df_error;
+    let f = (ss_effect / df_effect) / ms_error;
+    let (lo, hi) = noncentral_f_interval_rs(f, df_effect, df_error, confidence_level)?;
+    let to_eta = |ncp: f64| ncp / (ncp + df_effect + df_error + 1.0);
+
+    Ok(AnovaEffectSizes {
+        eta_squared: ss_effect / ss_total,
+        partial_eta_squared: ss_effect / (ss_effect + ss_error),
+        omega_squared: (ss_effect - df_effect * ms_error) / (ss_total + ms_error),
+        interval: (to_eta(lo), to_eta(hi)),
+    })
+}
+
+fn dominance(a: f64, b: f64) -> f64 {
+    if a > b { 1.0 } else if a < b { -1.0 } else { 0.0 }
+}
+
+// Percentile bootstrap interval of an estimator over the given samples
+fn bootstrap_effect_interval<F>(samples: &[&Array1<f64>], estimate: f64, estimator: F, confidence_level: f64,
+                                n_resamples: usize, seed: u64) -> Result<(f64, f64), StatsError>
+    where F: Fn(&[Array1<f64>]) -> Result<f64, StatsError> + Sync {
+    let distribution = bootstrap_samples_rs(samples, estimator, n_resamples, seed)?;
+    bootstrap_interval_rs(estimate, &distribution, confidence_level, Interval::Percentile, None)
+}
+
+// Returns (delta, (lower, upper)), asymptotically with Cliff's consistent variance and asymmetric interval
+pub fn cliffs_delta_rs(x: &Array1<f64>, y: &Array1<f64>, confidence_level: f64,
+                       interval: EffectInterval) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (n1, n2) = (x.len(), y.len());
+    if n1 < 2 || n2 < 2 { return Err(StatsError::InvalidInputValue); }
+
+    let (n1f, n2f) = (n1 as f64, n2 as f64);
+    let mut row_means = vec![0.0; n1];
+    let mut col_means = vec![0.0; n2];
+    let mut total = 0.0;
+    for (i, &xi) in x.iter().enumerate() {
+        for (j, &yj) in y.iter().enumerate() {
+            let d = dominance(xi, yj);
+            row_means[i] += d / n2f;
+            col_means[j] += d / n1f;
+            total += d;
+        }
+    }
+    let delta = total / (n1f * n2f);
+    if let EffectInterval::Bootstrap { n_resamples, seed } = interval {
+        let resampled_delta = |s: &[Array1<f64>]| {
+            let total: f64 = s[0].iter().flat_map(|&xi| s[1].iter().map(move |&yj| dominance(xi, yj))).sum();
+            Ok(total / (n1f * n2f))
+        };
+        return Ok((delta, bootstrap_effect_interval(&[x, y], delta, resampled_delta, confidence_level, n_resamples, seed)?));
+    }
+
+    let mut cell_ss = 0.0;
+    for &xi in x.iter() {
+        for &yj in y.iter() {
+            cell_ss += (dominance(xi, yj) - delta).powi(2);
+        }
+    }
+    let row_ss: f64 = row_means.iter().map(|m| (m - delta).powi(2)).sum();
+    let col_ss: f64 = col_means.iter().map(|m| (m - delta).powi(2)).sum();
+    let variance = ((n2f * n2f * row_ss + n1f * n1f * col_ss - cell_ss)
+        / (n1f * n2f * (n1f - 1.0) * (n2f - 1.0))).max(0.0);
+
+    let z = z_critical(confidence_level);
+    let s = variance.sqrt();
+    let d2 = delta * delta;
+    let spread = z * s * ((1.0 - d2).powi(2) + z * z * variance).sqrt();
+    let denom = 1.0 - d2 + z * z * variance;
+    let lower = (delta - delta.powi(3) - spread) / denom;
+    let upper = (delta - delta.powi(3) + spread) / denom;
+    Ok((delta, (lower.max(-1.0), upper.min(1.0))))
+}
+
+// Returns (r, se of its fisher z), y None for a one sample design
+fn rank_biserial_estimate(x: &Array1<f64>, y: Option<&Array1<f64>>, paired: bool) -> Result<(f64, f64), StatsError> {
+    match (y, paired) {
+        (Some(y), false) => {
+            let (n1, n2) = (x.len() as f64, y.len() as f64);
+            let combined: Vec<f64> = x.iter().chain(y.iter()).cloned().collect();
+            let (ranks, _) = rank_rs(&combined);
+            let r1: f64 = ranks.iter().take(x.len()).sum();
+            let u1 = r1 - n1 * (n1 + 1.0) / 2.0;
+            Ok((2.0 * u1 / (n1 * n2) - 1.0, ((n1 + n2 + 1.0) / (3.0 * n1 * n2)).sqrt()))
+        }
+        _ => {
+            let diffs: Vec<f64> = match y {
+                Some(y) => {
+                    if x.len() != y.len() { return Err(StatsError::InconsistentLength); }
+                    x.iter().zip(y.iter()).map(|(a, b)| a - b).filter(|&d| d != 0.0).collect()
+                }
+                None => x.iter().cloned().filter(|&d| d != 0.0).collect(),
+            };
+            if diffs.is_empty() { return Err(StatsError::EmptyDataSet); }
+            let abs_diffs: Vec<f64> = diffs.iter().map(|d| d.abs()).collect();
+            let (ranks, _) = rank_rs(&abs_diffs);
+            let t_plus: f64 = diffs.iter().zip(ranks.iter()).filter(|(&d, _)| d > 0.0).map(|(_, &r)| r).sum();
+            let n = diffs.len() as f64;
+            let max_w = n * (n + 1.0) / 2.0;
+            let t_minus = max_w - t_plus;
+            let se = ((2.0 * n.powi(3) + 3.0 * n * n + n) / 6.0).sqrt() / max_w;
+            Ok(((t_plus - t_minus) / max_w, se))
+        }
+    }
+}
+
+// Independent samples (y given, not paired): 2U / (n1 n2) - 1
+// One sample / paired differences: (T+ - T-) / (T+ + T-)
+// Paired designs are bootstrapped over the differences, independent samples each on their own.
+pub fn rank_biserial_rs(x: &Array1<f64>, y: Option<&Array1<f64>>, paired: bool, confidence_level: f64,
+                        interval: EffectInterval) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (r, se) = rank_biserial_estimate(x, y, paired)?;
+    match interval {
+        EffectInterval::Asymptotic => {
+            // interval on fisher's z scale keeps it inside [-1, 1]
+            let z = z_critical(confidence_level);
+            let centre = r.clamp(-1.0 + 1e-12, 1.0 - 1e-12).atanh();
+            Ok((r, ((centre - z * se).tanh(), (centre + z * se).tanh())))
+        }
+        EffectInterval::Bootstrap { n_resamples, seed } => {
+            let ci = match (y, paired) {
+                (Some(y), false) => bootstrap_effect_interval(&[x, y], r, |s| Ok(rank_biserial_estimate(&s[0], Some(&s[1]), false)?.0),
+                                                              confidence_level, n_resamples, seed)?,
+                _ => {
+                    let diffs = match y {
+                        Some(y) => x - y,
+                        None => x.clone(),
+                    };
+                    bootstrap_effect_interval(&[&diffs], r, |s| Ok(rank_biserial_estimate(&s[0], None, true)?.0),
+                                              confidence_level, n_resamples, seed)?
+                }
+            };
+            Ok((r, ci))
+        }
+    }
+}
+
+// Haldane-Anscombe correction, add a half to every cell when one is empty
+fn corrected_cells(table: &Table2x2) -> (f64, f64, f64, f64) {
+    let cells = (table.a as f64, table.b as f64, table.c as f64, table.d as f64);
+    if table.a == 0 || table.b == 0 || table.c == 0 || table.d == 0 {
+        (cells.0 + 0.5, cells.1 + 0.5, cells.2 + 0.5, cells.3 + 0.5)
+    } else {
+        cells
+    }
+}
+
+// The table's two columns as 0 / 1 outcomes, 1 for the first row
+fn table_columns(table: &Table2x2) -> (Array1<f64>, Array1<f64>) {
+    let column = |first: u64, second: u64| -> Array1<f64> {
+        (0..first + second).map(|i| if i < first { 1.0 } else { 0.0 }).collect()
+    };
+    (column(table.a, table.c), column(table.b, table.d))
+}
+
+fn columns_table(first: &Array1<f64>, second: &Array1<f64>) -> Table2x2 {
+    let (a, b) = (first.sum() as u64, second.sum() as u64);
+    Table2x2 { a, b, c: first.len() as u64 - a, d: second.len() as u64 - b }
+}
+
+// Percentile bootstrap interval of a table statistic, each column resampled on its own
+fn table_bootstrap_interval<F>(table: &Table2x2, estimate: f64, statistic: F, confidence_level: f64,
+                               n_resamples: usize, seed: u64) -> Result<(f64, f64), StatsError>
+    where F: Fn(&Table2x2) -> f64 + Sync {
+    let (first, second) = table_columns(table);
+    bootstrap_effect_interval(&[&first, &second], estimate, |s| Ok(statistic(&columns_table(&s[0], &s[1]))),
+                              confidence_level, n_resamples, seed)
+}
+
+// Returns (log odds ratio, Woolf's standard error)
+fn log_odds_ratio(table: &Table2x2) -> (f64, f64) {
+    let (a, b, c, d) = corrected_cells(table);
+    ((a * d / (b * c)).ln(), (1.0 / a + 1.0 / b + 1.0 / c + 1.0 / d).sqrt())
+}
+
+// Returns (log risk ratio, its delta method standard error)
+fn log_risk_ratio(table: &Table2x2) -> (f64, f64) {
+    let (a, b, c, d) = corrected_cells(table);
+    (((a / (a + c)) / (b / (b + d))).ln(), (1.0 / a - 1.0 / (a + c) + 1.0 / b - 1.0 / (b + d)).sqrt())
+}
+
+// Sample odds ratio, asymptotically with Woolf's log interval
+pub fn odds_ratio_rs(table: &Table2x2, confidence_level: f64, interval: EffectInterval) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (log_or, se) = log_odds_ratio(table);
+    let ci = match interval {
+        EffectInterval::Asymptotic => {
+            let z = z_critical(confidence_level);
+            ((log_or - z * se).exp(), (log_or + z * se).exp())
+        }
+        EffectInterval::Bootstrap { n_resamples, seed } => table_bootstrap_interval(
+            table, log_or.exp(), |t| log_odds_ratio(t).0.exp(), confidence_level, n_resamples, seed)?,
+    };
+    Ok((log_or.exp(), ci))
+}
+
+// Ratio of first row proportions, first column over second, asymptotically with a log scale interval
+pub fn risk_ratio_rs(table: &Table2x2, confidence_level: f64, interval: EffectInterval) -> Result<(f64, (f64, f64)), StatsError> {
+    validate_confidence(confidence_level)?;
+    let (log_rr, se) = log_risk_ratio(table);
+    let ci = match interval {
+        EffectInterval::Asymptotic => {
+            let z = z_critical(confidence_level);
+            ((log_rr - z * se).exp(), (log_rr + z * se).exp())
+        }
+        EffectInterval::Bootstrap { n_resamples, seed } => table_bootstrap_interval(
+            table, log_rr.exp(), |t| log_risk_ratio(t).0.exp(), confidence_level, n_resamples, seed)?,
+    };
+    Ok((log_rr.exp(), ci))
+}
+
+// Converts and validates the (x, y) pair every two sample estimator takes
+fn two_samples(x: &PyAny, y: &PyAny) -> PyResult<(Array1<f64>, Array1<f64>)> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+    Ok((x_data, y_data))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, y, kind = "pooled", confidence_level = 0.95))]
+pub fn cohens_d(x: &PyAny, y: &PyAny, kind: &str, confidence_level: f64) -> PyResult<(f64, (f64, f64))> {
+    // kind: "pooled" for independent groups, "av" or "rm" for paired measurements
+    let (x_data, y_data) = two_samples(x, y)?;
+    Ok(cohens_d_rs(&x_data, &y_data, CohensD::parse(kind)?, confidence_level)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, confidence_level = 0.95))]
+pub fn hedges_g(x: &PyAny, y: &PyAny, confidence_level: f64) -> PyResult<(f64, (f64, f64))> {
+    let (x_data, y_data) = two_samples(x, y)?;
+    Ok(hedges_g_rs(&x_data, &y_data, confidence_level)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, confidence_level = 0.95))]
+pub fn glass_delta(x: &PyAny, y: &PyAny, confidence_level: f64) -> PyResult<(f64, (f64, f64))> {
+    // y is the control group
+    let (x_data, y_data) = two_samples(x, y)?;
+    Ok(glass_delta_rs(&x_data, &y_data, confidence_level)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (ss_effect, ss_error, df_effect, df_error, ss_total = None, confidence_level = 0.95))]
+pub fn anova_effect_sizes(py: Python, ss_effect: f64, ss_error: f64, df_effect: f64, df_error: f64,
+                          ss_total: Option<f64>, confidence_level: f64) -> PyResult<PyObject> {
+    // ss_total defaults to ss_effect + ss_error (one way anova)
+    let sizes = anova_effect_sizes_rs(ss_effect, ss_error, df_effect, df_error, ss_total, confidence_level)?;
+    let summary = PyDict::new(py);
+    summary.set_item("eta_squared", sizes.eta_squared)?;
+    summary.set_item("partial_eta_squared", sizes.partial_eta_squared)?;
+    summary.set_item("omega_squared", sizes.omega_squared)?;
+    summary.set_item("partial_eta_squared_ci", sizes.interval)?;
+    Ok(summary.into())
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, confidence_level = 0.95, interval = "asymptotic", n_resamples = 9999, seed = None))]
+pub fn cliffs_delta(x: &PyAny, y: &PyAny, confidence_level: f64, interval: &str, n_resamples: usize,
+                    seed: Option<u64>) -> PyResult<(f64, (f64, f64))> {
+    // interval: asymptotic or bootstrap (percentile, x and y resampled separately)
+    let (x_data, y_data) = two_samples(x, y)?;
+    Ok(cliffs_delta_rs(&x_data, &y_data, confidence_level, EffectInterval::parse(interval, n_resamples, seed)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y = None, paired = false, confidence_level = 0.95, interval = "asymptotic", n_resamples = 9999,
+                    seed = None))]
+pub fn rank_biserial(x: &PyAny, y: Option<&PyAny>, paired: bool, confidence_level: f64, interval: &str,
+                     n_resamples: usize, seed: Option<u64>) -> PyResult<(f64, (f64, f64))> {
+    // interval: asymptotic or bootstrap (percentile, paired designs resample the differences)
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let y_data = match y {
+        Some(y) => {
+            let y_data = match from_pyarray1(y) {
+                Ok(data) => data,
+                Err(_e) => return Err(StatsError::Conversion.into()),
+            };
+            validate_statistical_input!(basic, y_data);
+            Some(y_data)
+        }
+        None => None,
+    };
+    let interval = EffectInterval::parse(interval, n_resamples, seed)?;
+    Ok(rank_biserial_rs(&x_data, y_data.as_ref(), paired, confidence_level, interval)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (table, confidence_level = 0.95, interval = "asymptotic", n_resamples = 9999, seed = None))]
+pub fn odds_ratio(table: &PyAny, confidence_level: f64, interval: &str, n_resamples: usize,
+                  seed: Option<u64>) -> PyResult<(f64, (f64, f64))> {
+    // interval: asymptotic or bootstrap (percentile, each column resampled separately)
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let interval = EffectInterval::parse(interval, n_resamples, seed)?;
+    Ok(odds_ratio_rs(&Table2x2::from_array(&table_data)?, confidence_level, interval)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (table, confidence_level = 0.95, interval = "asymptotic", n_resamples = 9999, seed = None))]
+pub fn risk_ratio(table: &PyAny, confidence_level: f64, interval: &str, n_resamples: usize,
+                  seed: Option<u64>) -> PyResult<(f64, (f64, f64))> {
+    // interval: asymptotic or bootstrap (percentile, each column resampled separately)
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let interval = EffectInterval::parse(interval, n_resamples, seed)?;
+    Ok(risk_ratio_rs(&Table2x2::from_array(&table_data)?, confidence_level, interval)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, method = "cohens_d", confidence_level = 0.95))]
+pub fn effect_size(x: &PyAny, y: &PyAny, method: &str, confidence_level: f64) -> PyResult<(f64, (f64, f64))> {
+    // Two sample entry point, method picks one of the estimators above
+    let (x_data, y_data) = two_samples(x, y)?;
+    let result = match method {
+        "cohens_d" => cohens_d_rs(&x_data, &y_data, CohensD::Pooled, confidence_level),
+        "hedges_g" => hedges_g_rs(&x_data, &y_data, confidence_level),
+        "glass_delta" => glass_delta_rs(&x_data, &y_data, confidence_level),
+        "cliffs_delta" => cliffs_delta_rs(&x_data, &y_data, confidence_level, EffectInterval::Asymptotic),
+        "rank_biserial" => rank_biserial_rs(&x_data, Some(&y_data), false, confidence_level, EffectInterval::Asymptotic),
+        _ => Err(StatsError::InvalidInputValue),
+    };
+    Ok(result?)
+}
@@ -0,0 +1,165 @@
+// Equivalence testing with two one-sided tests (TOST)
+// tost_one_sample -- mean of x against mu
+// tost_paired -- mean of the differences x - y
+// tost_independent -- difference of two independent means, welch or pooled variance
+// tost_proportions -- difference of two independent proportions, normal approximation
+//
+// Equivalence is shown when the effect is significantly above the lower bound and significantly
+// below the upper bound, i.e. both one-sided p-values are below alpha, or equivalently when the
+// 1 - 2 alpha confidence interval lies inside the bounds. Standardized bounds are in Cohen's d
+// units (sd of x, of the differences, or pooled / averaged over both samples).
+//
+// Results are TestResults whose p_value is the larger one-sided p-value and whose statistic belongs to
+// that test, with the estimate and its 1 - 2 alpha interval. Extras hold the raw bounds, both
+// one-sided statistics and p-values, alpha and whether equivalence was shown.
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+use crate::descriptive_statistics::{mean_rs, sample_variance_rs};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::TestResult;
+use crate::utils::from_pyarray1;
+use crate::validate_statistical_input;
+
+
+// Bounds must be finite and ordered, and alpha below 0.5 for a 1 - 2 alpha interval
+fn check_tost_input(low: f64, high: f64, alpha: f64) -> Result<(), StatsError> {
+    if !low.is_finite() || !high.is_finite() || low >= high { return Err(StatsError::InvalidInputValue); }
+    if alpha <= 0.0 || alpha >= 0.5 { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+// Both one-sided tests of estimate / se against the raw bounds, on a t(df) or standard normal scale
+fn tost_rs(method: &str, estimate: f64, se: f64, df: Option<f64>, low: f64, high: f64, alpha: f64) -> Result<TestResult, StatsError> {
+    check_tost_input(low, high, alpha)?;
+    if se <= 0.0 || !se.is_finite() { return Err(StatsError::ZeroVariance); }
+    let (cdf, critical): (Box<dyn Fn(f64) -> f64>, f64) = match df {
+        Some(df) => {
+            let t = StudentsT::new(0.0, 1.0, df).map_err(|_| StatsError::InvalidInputValue)?;
+            let critical = t.inverse_cdf(1.0 - alpha);
+            (Box::new(move |v| t.cdf(v)), critical)
+        }
+        None => {
+            let normal = Normal::new(0.0, 1.0).unwrap();
+            (Box::new(move |v| normal.cdf(v)), normal.inverse_cdf(1.0 - alpha))
+        }
+    };
+    // H0: effect <= low against effect > low, and H0: effect >= high against effect < high
+    let lower_statistic = (estimate - low) / se;
+    let upper_statistic = (estimate - high) / se;
+    let lower_p_value = 1.0 - cdf(lower_statistic);
+    let upper_p_value = cdf(upper_statistic);
+    let p_value = lower_p_value.max(upper_p_value);
+    let statistic = if lower_p_value >= upper_p_value { lower_statistic } else { upper_statistic };
+    let result = TestResult::new(method, statistic, p_value)
+        .with_estimate(estimate)
+        .with_confidence_interval((estimate - critical * se, estimate + critical * se))
+        .with_extra("lower_bound", low)
+        .with_extra("upper_bound", high)
+        .with_extra("lower_statistic", lower_statistic)
+        .with_extra("upper_statistic", upper_statistic)
+        .with_extra("lower_p_value", lower_p_value)
+        .with_extra("upper_p_value", upper_p_value)
+        .with_extra("alpha", alpha)
+        .with_extra("equivalent", p_value < alpha);
+    Ok(match df {
+        Some(df) => result.with_df(df),
+        None => result,
+    })
+}
+
+pub fn tost_one_sample_rs(x: &Array1<f64>, mu: f64, low: f64, high: f64, standardized: bool, alpha: f64) -> Result<TestResult, StatsError> {
+    if x.len() < 2 { return Err(StatsError::InvalidInputValue); }
+    let n = x.len() as f64;
+    let sd = sample_variance_rs(x).sqrt();
+    let scale = if standardized { sd } else { 1.0 };
+    tost_rs("One-sample TOST", mean_rs(x) - mu, sd / n.sqrt(), Some(n - 1.0), low * scale, high * scale, alpha)
+}
+
+pub fn tost_paired_rs(x: &Array1<f64>, y: &Array1<f64>, low: f64, high: f64, standardized: bool, alpha: f64) -> Result<TestResult, StatsError> {
+    if x.len() != y.len() { return Err(StatsError::InconsistentLength); }
+    let result = tost_one_sample_rs(&(x - y), 0.0, low, high, standardized, alpha)?;
+    Ok(TestResult { method: "Paired TOST".to_string(), ..result })
+}
+
+pub fn tost_independent_rs(x: &Array1<f64>, y: &Array1<f64>, low: f64, high: f64, standardized: bool,
+                           equal_var: bool, alpha: f64) -> Result<TestResult, StatsError> {
+    if x.len() < 2 || y.len() < 2 { return Err(StatsError::InvalidInputValue); }
+    let (n1, n2) = (x.len() as f64, y.len() as f64);
+    let (v1, v2) = (sample_variance_rs(x), sample_variance_rs(y));
+    let pooled = ((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0);
+    let (method, se, df, sd) = if equal_var {
+        ("Two-sample TOST", (pooled * (1.0 / n1 + 1.0 / n2)).sqrt(), n1 + n2 - 2.0, pooled.sqrt())
+    } else {
+        let (a, b) = (v1 / n1, v2 / n2);
+        let df = (a + b).powi(2) / (a * a / (n1 - 1.0) + b * b / (n2 - 1.0));
+        ("Welch two-sample TOST", (a + b).sqrt(), df, ((v1 + v2) / 2.0).sqrt())
+    };
+    let scale = if standardized { sd } else { 1.0 };
+    tost_rs(method, mean_rs(x) - mean_rs(y), se, Some(df), low * scale, high * scale, alpha)
+}
+
+// Bounds on p1 - p2, with the unpooled (wald) standard error
+pub fn tost_proportions_rs(k1: u64, n1: u64, k2: u64, n2: u64, low: f64, high: f64, alpha: f64) -> Result<TestResult, StatsError> {
+    if n1 == 0 || n2 == 0 { return Err(StatsError::EmptyDataSet); }
+    if k1 > n1 || k2 > n2 { return Err(StatsError::InvalidInputValue); }
+    let (p1, p2) = (k1 as f64 / n1 as f64, k2 as f64 / n2 as f64);
+    let se = (p1 * (1.0 - p1) / n1 as f64 + p2 * (1.0 - p2) / n2 as f64).sqrt();
+    tost_rs("Two-proportion TOST", p1 - p2, se, None, low, high, alpha)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, low, high, mu = 0.0, standardized = false, alpha = 0.05))]
+pub fn tost_one_sample(x: &PyAny, low: f64, high: f64, mu: f64, standardized: bool, alpha: f64) -> PyResult<TestResult> {
+    // low and high bound mean(x) - mu, in Cohen's d units when standardized
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    Ok(tost_one_sample_rs(&x_data, mu, low, high, standardized, alpha)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, low, high, standardized = false, alpha = 0.05))]
+pub fn tost_paired(x: &PyAny, y: &PyAny, low: f64, high: f64, standardized: bool, alpha: f64) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+    Ok(tost_paired_rs(&x_data, &y_data, low, high, standardized, alpha)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, low, high, standardized = false, equal_var = false, alpha = 0.05))]
+pub fn tost_independent(x: &PyAny, y: &PyAny, low: f64, high: f64, standardized: bool, equal_var: bool,
+                        alpha: f64) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+    Ok(tost_independent_rs(&x_data, &y_data, low, high, standardized, equal_var, alpha)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (k1, n1, k2, n2, low, high, alpha = 0.05))]
+pub fn tost_proportions(k1: u64, n1: u64, k2: u64, n2: u64, low: f64, high: f64, alpha: f64) -> PyResult<TestResult> {
+    Ok(tost_proportions_rs(k1, n1, k2, n2, low, high, alpha)?)
+}
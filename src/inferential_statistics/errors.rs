@@ -12,6 +12,9 @@ pub enum StatsError{
     MinMaxError,
     ZeroVariance,
     UnderlyingError,
+    InsufficientGroups,
+    InvalidShape,
+    NoSolution,
     Conversion
 }
 
@@ -27,6 +30,9 @@ impl std::fmt::Display for StatsError {
                 StatsError::MinMaxError => "Cannot compute min or max: the dataset is empty or contains invalid values.",
                 StatsError::ZeroVariance => "Cannot compute: one or both variables have zero variance.",
                 StatsError::UnderlyingError => "Error in Underlying Rust Packages",
+                StatsError::InsufficientGroups => "Not enough groups supplied for this test.",
+                StatsError::InvalidShape => "Input table does not have the shape this test requires.",
+                StatsError::NoSolution => "No value in the valid range satisfies the requested constraints.",
                 StatsError::Conversion => "Cannot convert input array to numeric type."
             }
         )
@@ -42,6 +48,9 @@ impl From<StatsError> for PyErr {
             StatsError::MinMaxError => PyValueError::new_err(err.to_string()),
             StatsError::ZeroVariance => PyValueError::new_err(err.to_string()),
             StatsError::UnderlyingError => PyValueError::new_err(err.to_string()),
+            StatsError::InsufficientGroups => PyValueError::new_err(err.to_string()),
+            StatsError::InvalidShape => PyValueError::new_err(err.to_string()),
+            StatsError::NoSolution => PyValueError::new_err(err.to_string()),
             StatsError::Conversion => PyValueError::new_err(err.to_string())
         }
     }
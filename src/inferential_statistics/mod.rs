@@ -9,15 +9,31 @@
 use statrs::distribution::ContinuousCDF;
 /// imports
 pub mod errors;
+pub mod rank_tests;
+pub mod contingency_tests;
+pub mod power_analysis;
+pub mod effect_size;
+pub mod normality_tests;
+pub mod variance_tests;
+pub mod test_result;
+pub mod multiple_comparisons;
+pub mod permutation_tests;
+pub mod correlation_tests;
+pub mod proportion_tests;
+pub mod equivalence_tests;
+#[cfg(test)]
+mod unit_tests;
 pub use crate::inferential_statistics::errors::*;
 pub use crate::{validate_statistical_input,
                 descriptive_statistics::{mean_rs, median_rs, variance_rs,
                                                                percentile_rs}};
 pub use crate::utils::{from_pyarray1};
+pub use crate::inferential_statistics::test_result::TestResult;
 
 use statrs::distribution::{Normal};
 use pyo3::prelude::*;
 use numpy::{PyArray1};
+use ndarray::Array1;
 
 
 /// Data Validation Macro
@@ -25,12 +41,79 @@ use numpy::{PyArray1};
 // May need to develop a new one
 /// Rust Native Computations
 // Simply imported from descriptive Statistics
+
+// Direction of the alternative hypothesis, shared by every test taking `alternative=`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alternative {
+    TwoSided,
+    Less,
+    Greater,
+}
+
+impl Alternative {
+    pub fn parse(alternative: &str) -> Result<Alternative, StatsError> {
+        match alternative {
+            "two-sided" => Ok(Alternative::TwoSided),
+            "less" => Ok(Alternative::Less),
+            "greater" => Ok(Alternative::Greater),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Alternative::TwoSided => "two-sided",
+            Alternative::Less => "less",
+            Alternative::Greater => "greater",
+        }
+    }
+
+    // p-value of a standard normal statistic in this direction
+    pub fn normal_p_value(&self, z: f64) -> f64 {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        match self {
+            Alternative::TwoSided => (2.0 * normal.sf(z.abs())).min(1.0),
+            Alternative::Less => normal.cdf(z),
+            Alternative::Greater => normal.sf(z),
+        }
+    }
+}
+
+// Asymptotic two-sample Kolmogorov-Smirnov p-value, with Stephens' small sample correction
+pub fn kolmogorov_smirnov_p_value_rs(d: f64, n: usize, m: usize) -> f64 {
+    kolmogorov_p_value(d, ((n * m) as f64 / (n + m) as f64).sqrt())
+}
+
+// One-sample Kolmogorov-Smirnov statistic and asymptotic p-value against a fully specified cdf
+pub fn kolmogorov_smirnov_one_sample_rs<F: Fn(f64) -> f64>(x: &Array1<f64>, cdf: F) -> (f64, f64) {
+    let mut sorted = x.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Greater));
+    let n = sorted.len() as f64;
+    let d = sorted.iter().enumerate().fold(0.0_f64, |d, (i, &value)| {
+        let f = cdf(value);
+        d.max((i as f64 + 1.0) / n - f).max(f - i as f64 / n)
+    });
+    (d, kolmogorov_p_value(d, n.sqrt()))
+}
+
+// Kolmogorov distribution tail at the effective sample size en
+fn kolmogorov_p_value(d: f64, en: f64) -> f64 {
+    let lambda = (en + 0.12 + 0.11 / en) * d;
+    if lambda < 1e-3 { return 1.0; }
+    let mut p = 0.0;
+    for k in 1..=100 {
+        let term = 2.0 * (-1.0_f64).powi(k - 1) * (-2.0 * (k * k) as f64 * lambda * lambda).exp();
+        p += term;
+        if term.abs() < 1e-12 { break; }
+    }
+    p.clamp(0.0, 1.0)
+}
 /// Pyfunctions
 ///
 #[pyfunction]
-pub fn confidence_interval(x: &PyArray1<f64>, ci: f64) -> PyResult<(f64, f64)> {
+pub fn confidence_interval(x: &PyArray1<f64>, ci: f64) -> PyResult<TestResult> {
     // Takes array, ci.
-    // Maybe returns tuple with lower bound and upper bound
+    // Returns the normal interval for the mean, along with the z test of a zero mean
     let x_data = match from_pyarray1(x) {
         Ok(data) => data,
         Err(_e) => return Err(StatsError::Conversion.into()),
@@ -54,11 +137,14 @@ pub fn confidence_interval(x: &PyArray1<f64>, ci: f64) -> PyResult<(f64, f64)> {
     let lower_bound = mean - z * std_error;
     let upper_bound = mean + z * std_error;
 
-    Ok((lower_bound, upper_bound))
+    let statistic = mean / std_error;
+    Ok(TestResult::new("One-sample z interval", statistic, Alternative::TwoSided.normal_p_value(statistic))
+        .with_estimate(mean)
+        .with_confidence_interval((lower_bound, upper_bound)))
 }
 
 #[pyfunction]
-pub fn kolmogorov_smirnov_test(x: &PyArray1<f64>, y: &PyArray1<f64>) -> PyResult<f64> {
+pub fn kolmogorov_smirnov_test(x: &PyArray1<f64>, y: &PyArray1<f64>) -> PyResult<TestResult> {
     let x_data = match from_pyarray1(x) {
         Ok(data) => data,
         Err(_e) => return Err(StatsError::Conversion.into()),
@@ -98,5 +184,6 @@ pub fn kolmogorov_smirnov_test(x: &PyArray1<f64>, y: &PyArray1<f64>) -> PyResult
         }
     }
 
-    Ok(max_diff)
+    let p_value = kolmogorov_smirnov_p_value_rs(max_diff, x_sorted.len(), y_sorted.len());
+    Ok(TestResult::new("Two-sample Kolmogorov-Smirnov test", max_diff, p_value))
 }
\ No newline at end of file
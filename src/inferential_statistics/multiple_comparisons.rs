@@ -0,0 +1,157 @@
+// Multiple comparison adjustments of p-values
+// adjust_p_values -- family-wise error (bonferroni, holm, hochberg, hommel) and
+//                    false discovery rate (fdr_bh, fdr_by, storey q-values) corrections
+//
+// Adjusted values are directly comparable to alpha, a hypothesis is rejected when its adjusted
+// p-value is <= alpha.
+
+use ndarray::Array1;
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+
+use crate::inferential_statistics::errors::*;
+use crate::utils::from_pyarray1;
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjustment {
+    None,
+    Bonferroni,
+    Holm,
+    Hochberg,
+    Hommel,
+    BenjaminiHochberg,
+    BenjaminiYekutieli,
+    // Storey's q-values, lambda is the threshold used to estimate the proportion of true nulls
+    Storey(f64),
+}
+
+impl Adjustment {
+    pub fn parse(method: &str, lambda: f64) -> Result<Adjustment, StatsError> {
+        match method {
+            "none" => Ok(Adjustment::None),
+            "bonferroni" => Ok(Adjustment::Bonferroni),
+            "holm" => Ok(Adjustment::Holm),
+            "hochberg" => Ok(Adjustment::Hochberg),
+            "hommel" => Ok(Adjustment::Hommel),
+            "fdr_bh" => Ok(Adjustment::BenjaminiHochberg),
+            "fdr_by" => Ok(Adjustment::BenjaminiYekutieli),
+            "storey" => {
+                if lambda <= 0.0 || lambda >= 1.0 { return Err(StatsError::InvalidInputValue); }
+                Ok(Adjustment::Storey(lambda))
+            }
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// Running maximum from the front (step-down) or running minimum from the back (step-up)
+fn step_down(values: &mut [f64]) {
+    for i in 1..values.len() {
+        values[i] = values[i].max(values[i - 1]);
+    }
+}
+
+fn step_up(values: &mut [f64]) {
+    for i in (0..values.len().saturating_sub(1)).rev() {
+        values[i] = values[i].min(values[i + 1]);
+    }
+}
+
+// Linear step-up adjustment on ascending p-values, scaled by `factor`
+fn benjamini_hochberg(sorted: &[f64], factor: f64) -> Vec<f64> {
+    let m = sorted.len() as f64;
+    let mut adjusted: Vec<f64> = sorted.iter().enumerate()
+        .map(|(i, p)| factor * m / (i as f64 + 1.0) * p)
+        .collect();
+    step_up(&mut adjusted);
+    adjusted
+}
+
+// Wright's (1992) algorithm for Hommel's procedure, on ascending p-values
+fn hommel(sorted: &[f64]) -> Vec<f64> {
+    let m = sorted.len();
+    let mut adjusted = sorted.to_vec();
+    for k in (2..=m).rev() {
+        let tail = m - k;
+        let cim = sorted[tail..].iter().enumerate()
+            .map(|(j, p)| k as f64 * p / (j as f64 + 1.0))
+            .fold(f64::INFINITY, f64::min);
+        for value in adjusted[tail..].iter_mut() {
+            *value = value.max(cim);
+        }
+        for (value, p) in adjusted[..tail].iter_mut().zip(sorted[..tail].iter()) {
+            *value = value.max((k as f64 * p).min(cim));
+        }
+    }
+    adjusted.iter().zip(sorted.iter()).map(|(a, p)| a.max(*p)).collect()
+}
+
+// Adjusted p-values in the original order of `p_values`
+pub fn adjust_p_values_rs(p_values: &Array1<f64>, method: Adjustment) -> Result<Array1<f64>, StatsError> {
+    if p_values.is_empty() { return Err(StatsError::EmptyDataSet); }
+    if p_values.iter().any(|p| !(0.0..=1.0).contains(p)) { return Err(StatsError::InvalidInputValue); }
+    let m = p_values.len();
+
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&i, &j| p_values[i].partial_cmp(&p_values[j]).unwrap());
+    let sorted: Vec<f64> = order.iter().map(|&i| p_values[i]).collect();
+
+    let adjusted_sorted = match method {
+        Adjustment::None => sorted,
+        Adjustment::Bonferroni => sorted.iter().map(|p| m as f64 * p).collect(),
+        Adjustment::Holm => {
+            let mut adjusted: Vec<f64> = sorted.iter().enumerate().map(|(i, p)| (m - i) as f64 * p).collect();
+            step_down(&mut adjusted);
+            adjusted
+        }
+        Adjustment::Hochberg => {
+            let mut adjusted: Vec<f64> = sorted.iter().enumerate().map(|(i, p)| (m - i) as f64 * p).collect();
+            step_up(&mut adjusted);
+            adjusted
+        }
+        Adjustment::Hommel => hommel(&sorted),
+        Adjustment::BenjaminiHochberg => benjamini_hochberg(&sorted, 1.0),
+        Adjustment::BenjaminiYekutieli => {
+            let harmonic: f64 = (1..=m).map(|i| 1.0 / i as f64).sum();
+            benjamini_hochberg(&sorted, harmonic)
+        }
+        Adjustment::Storey(lambda) => {
+            let above = sorted.iter().filter(|&&p| p > lambda).count() as f64;
+            let pi0 = (above / (m as f64 * (1.0 - lambda))).min(1.0);
+            // every p-value at or below lambda, try a smaller lambda
+            if pi0 == 0.0 { return Err(StatsError::NoSolution); }
+            benjamini_hochberg(&sorted, pi0)
+        }
+    };
+
+    let mut adjusted = Array1::<f64>::zeros(m);
+    for (rank, &i) in order.iter().enumerate() {
+        adjusted[i] = adjusted_sorted[rank].min(1.0);
+    }
+    Ok(adjusted)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (p_values, method = "holm", alpha = 0.05, lambda_ = 0.5))]
+pub fn adjust_p_values<'py>(py: Python<'py>, p_values: &PyAny, method: &str, alpha: f64, lambda_: f64)
+                            -> PyResult<(&'py PyArray1<f64>, &'py PyArray1<bool>)> {
+    // method: bonferroni, holm, hochberg, hommel, fdr_bh, fdr_by or storey
+    // lambda_ is only used by storey. Returns (adjusted p-values, reject at alpha)
+    let p_data = match from_pyarray1(p_values) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, p_data);
+    if alpha <= 0.0 || alpha >= 1.0 { return Err(StatsError::InvalidInputValue.into()); }
+
+    let adjusted = adjust_p_values_rs(&p_data, Adjustment::parse(method, lambda_)?)?;
+    let reject = adjusted.mapv(|p| p <= alpha);
+    Ok((adjusted.into_pyarray(py), reject.into_pyarray(py)))
+}
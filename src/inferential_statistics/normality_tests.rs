@@ -0,0 +1,435 @@
+// Normality tests
+// shapiro_wilk_test -- Royston's (1992, 1995) approximation, 3 <= n <= 5000
+// dagostino_pearson_test -- K^2 omnibus test combining the skewness and kurtosis z scores
+// jarque_bera_test -- asymptotic chi-square test on skewness and kurtosis
+// anderson_darling_test -- A^2 with critical values for norm, expon, logistic and gumbel
+// lilliefors_test -- KS distance to the fitted normal, Dallal-Wilkinson p-values
+// cramer_von_mises_test -- W^2 distance to the fitted normal, Stephens' p-values
+//
+// Skewness and kurtosis based tests are defined on the moment estimators (moment_skewness_rs,
+// moment_kurtosis_rs) rather than the small sample adjusted `skewness` / `kurtosis`.
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+use crate::descriptive_statistics::{mean_rs, moment_kurtosis_rs, moment_skewness_rs, sample_variance_rs};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::TestResult;
+use crate::utils::{bisect_rs, from_pyarray1};
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AndersonDistribution {
+    Normal,
+    Exponential,
+    Logistic,
+    Gumbel,
+}
+
+impl AndersonDistribution {
+    pub fn parse(dist: &str) -> Result<AndersonDistribution, StatsError> {
+        match dist {
+            "norm" => Ok(AndersonDistribution::Normal),
+            "expon" => Ok(AndersonDistribution::Exponential),
+            "logistic" => Ok(AndersonDistribution::Logistic),
+            "gumbel" => Ok(AndersonDistribution::Gumbel),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// (A^2, critical values, significance levels in percent, p-value for the normal case)
+pub type AndersonResult = (f64, Vec<f64>, Vec<f64>, Option<f64>);
+
+fn sorted(data: &Array1<f64>) -> Vec<f64> {
+    let mut sorted_data = data.to_vec();
+    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    sorted_data
+}
+
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+// Fitted normal cdf values of the sorted data, mean and sd (n - 1) estimated from the sample
+fn fitted_normal_cdf(data: &Array1<f64>) -> Result<Vec<f64>, StatsError> {
+    let sd = sample_variance_rs(data).sqrt();
+    if sd == 0.0 { return Err(StatsError::ZeroVariance); }
+    let normal = Normal::new(mean_rs(data), sd).map_err(|_| StatsError::UnderlyingError)?;
+    Ok(sorted(data).iter().map(|&x| normal.cdf(x)).collect())
+}
+
+// Returns (W, p)
+pub fn shapiro_wilk_rs(data: &Array1<f64>) -> Result<(f64, f64), StatsError> {
+    let n = data.len();
+    if !(3..=5000).contains(&n) { return Err(StatsError::InvalidInputValue); }
+    let x = sorted(data);
+    let ss: f64 = {
+        let mean = mean_rs(data);
+        x.iter().map(|v| (v - mean).powi(2)).sum()
+    };
+    if ss == 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let nf = n as f64;
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    let mut a = vec![0.0; n];
+    if n == 3 {
+        a[0] = -0.5f64.sqrt();
+        a[2] = 0.5f64.sqrt();
+    } else {
+        let m: Vec<f64> = (1..=n).map(|i| normal.inverse_cdf((i as f64 - 0.375) / (nf + 0.25))).collect();
+        let m_sq: f64 = m.iter().map(|v| v * v).sum();
+        let u = 1.0 / nf.sqrt();
+        let c1 = [0.0, 0.221157, -0.147981, -2.071190, 4.434685, -2.706056];
+        let c2 = [0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633];
+        let a_n = m[n - 1] / m_sq.sqrt() + polynomial(&c1, u);
+        a[n - 1] = a_n;
+        a[0] = -a_n;
+        let (first, phi) = if n > 5 {
+            let a_n1 = m[n - 2] / m_sq.sqrt() + polynomial(&c2, u);
+            a[n - 2] = a_n1;
+            a[1] = -a_n1;
+            (2, (m_sq - 2.0 * m[n - 1].powi(2) - 2.0 * m[n - 2].powi(2)) / (1.0 - 2.0 * a_n.powi(2) - 2.0 * a_n1.powi(2)))
+        } else {
+            (1, (m_sq - 2.0 * m[n - 1].powi(2)) / (1.0 - 2.0 * a_n.powi(2)))
+        };
+        for i in first..n - first {
+            a[i] = m[i] / phi.sqrt();
+        }
+    }
+
+    let numerator: f64 = a.iter().zip(x.iter()).map(|(ai, xi)| ai * xi).sum();
+    let w = (numerator * numerator / ss).min(1.0);
+
+    let p = if n == 3 {
+        let pi6 = 6.0 / std::f64::consts::PI;
+        (pi6 * (w.sqrt().asin() - 0.75f64.sqrt().asin())).clamp(0.0, 1.0)
+    } else {
+        let w1 = (1.0 - w).ln();
+        let (y, mu, sigma) = if n <= 11 {
+            let gamma = polynomial(&[-2.273, 0.459], nf);
+            if w1 >= gamma { return Ok((w, 0.0)); }
+            (-(gamma - w1).ln(),
+             polynomial(&[0.5440, -0.39978, 0.025054, -6.714e-4], nf),
+             polynomial(&[1.3822, -0.77857, 0.062767, -0.0020322], nf).exp())
+        } else {
+            let ln_n = nf.ln();
+            (w1,
+             polynomial(&[-1.5861, -0.31082, -0.083751, 0.0038915], ln_n),
+             polynomial(&[-0.4803, -0.082676, 0.0030302], ln_n).exp())
+        };
+        normal.sf((y - mu) / sigma)
+    };
+    Ok((w, p))
+}
+
+// D'Agostino's transformation of the sample skewness to a standard normal z, n >= 8
+pub fn skewness_z_rs(data: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    let b1 = moment_skewness_rs(data);
+    let y = b1 * ((n + 1.0) * (n + 3.0) / (6.0 * (n - 2.0))).sqrt();
+    let beta2 = 3.0 * (n * n + 27.0 * n - 70.0) * (n + 1.0) * (n + 3.0)
+        / ((n - 2.0) * (n + 5.0) * (n + 7.0) * (n + 9.0));
+    let w2 = -1.0 + (2.0 * (beta2 - 1.0)).sqrt();
+    let delta = 1.0 / (0.5 * w2.ln()).sqrt();
+    let alpha = (2.0 / (w2 - 1.0)).sqrt();
+    let ratio = y / alpha;
+    delta * (ratio + (ratio * ratio + 1.0).sqrt()).ln()
+}
+
+// Anscombe & Glynn's transformation of the sample kurtosis to a standard normal z
+pub fn kurtosis_z_rs(data: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    let b2 = moment_kurtosis_rs(data);
+    let expected = 3.0 * (n - 1.0) / (n + 1.0);
+    let var_b2 = 24.0 * n * (n - 2.0) * (n - 3.0) / ((n + 1.0).powi(2) * (n + 3.0) * (n + 5.0));
+    let x = (b2 - expected) / var_b2.sqrt();
+    let sqrt_beta1 = 6.0 * (n * n - 5.0 * n + 2.0) / ((n + 7.0) * (n + 9.0))
+        * (6.0 * (n + 3.0) * (n + 5.0) / (n * (n - 2.0) * (n - 3.0))).sqrt();
+    let a = 6.0 + 8.0 / sqrt_beta1 * (2.0 / sqrt_beta1 + (1.0 + 4.0 / sqrt_beta1.powi(2)).sqrt());
+    let term1 = 1.0 - 2.0 / (9.0 * a);
+    let denom = 1.0 + x * (2.0 / (a - 4.0)).sqrt();
+    let term2 = denom.signum() * ((1.0 - 2.0 / a) / denom.abs()).cbrt();
+    (term1 - term2) / (2.0 / (9.0 * a)).sqrt()
+}
+
+// Returns (K^2, p)
+pub fn dagostino_pearson_rs(data: &Array1<f64>) -> Result<(f64, f64), StatsError> {
+    if data.len() < 8 { return Err(StatsError::InvalidInputValue); }
+    if sample_variance_rs(data) == 0.0 { return Err(StatsError::ZeroVariance); }
+    let k2 = skewness_z_rs(data).powi(2) + kurtosis_z_rs(data).powi(2);
+    Ok((k2, ChiSquared::new(2.0).unwrap().sf(k2)))
+}
+
+// Returns (JB, p)
+pub fn jarque_bera_rs(data: &Array1<f64>) -> Result<(f64, f64), StatsError> {
+    if data.len() < 2 { return Err(StatsError::InvalidInputValue); }
+    if sample_variance_rs(data) == 0.0 { return Err(StatsError::ZeroVariance); }
+    let n = data.len() as f64;
+    let s = moment_skewness_rs(data);
+    let k = moment_kurtosis_rs(data);
+    let jb = n / 6.0 * (s * s + (k - 3.0).powi(2) / 4.0);
+    Ok((jb, ChiSquared::new(2.0).unwrap().sf(jb)))
+}
+
+// Logistic maximum likelihood (loc, scale): sum tanh(u / 2) = 0 and sum u tanh(u / 2) = n
+fn fit_logistic(x: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let sd = (x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    let (min, max) = (x[0], x[x.len() - 1]);
+    let (mut loc, mut scale) = (mean, sd * 3f64.sqrt() / std::f64::consts::PI);
+    for _ in 0..200 {
+        let new_loc = bisect_rs(|l| x.iter().map(|v| ((v - l) / (2.0 * scale)).tanh()).sum(), min, max, 1e-12);
+        let score = |s: f64| x.iter().map(|v| {
+            let u = (v - new_loc) / s;
+            u * (u / 2.0).tanh()
+        }).sum::<f64>() - n;
+        let mut hi = scale * 2.0;
+        while score(hi) > 0.0 { hi *= 2.0; }
+        let new_scale = bisect_rs(score, 1e-12 * (max - min), hi, 1e-14 * hi);
+        let converged = (new_loc - loc).abs() < 1e-10 && (new_scale - scale).abs() < 1e-10;
+        loc = new_loc;
+        scale = new_scale;
+        if converged { break; }
+    }
+    (loc, scale)
+}
+
+// Right skewed gumbel maximum likelihood (loc, scale)
+fn fit_gumbel(x: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let shift = x[0];
+    // weights exp(-(x - min) / beta) stay in (0, 1] and never overflow
+    let weighted_mean = |beta: f64| {
+        let (mut num, mut den) = (0.0, 0.0);
+        for v in x {
+            let w = (-(v - shift) / beta).exp();
+            num += v * w;
+            den += w;
+        }
+        num / den
+    };
+    let spread = x[x.len() - 1] - x[0];
+    let mut hi = spread.max(1e-300);
+    while mean - weighted_mean(hi) - hi > 0.0 { hi *= 2.0; }
+    let beta = bisect_rs(|b| mean - weighted_mean(b) - b, 1e-12 * spread, hi, 1e-14 * hi);
+    let sum_w: f64 = x.iter().map(|v| (-(v - shift) / beta).exp()).sum();
+    let loc = shift - beta * (sum_w / n).ln();
+    (loc, beta)
+}
+
+pub fn anderson_darling_rs(data: &Array1<f64>, dist: AndersonDistribution) -> Result<AndersonResult, StatsError> {
+    let n = data.len();
+    if n < 3 { return Err(StatsError::InvalidInputValue); }
+    let x = sorted(data);
+    let nf = n as f64;
+    if x[0] == x[n - 1] { return Err(StatsError::ZeroVariance); }
+
+    // (log cdf, log sf) of each sorted point under the fitted distribution
+    let (log_cdf, log_sf): (Vec<f64>, Vec<f64>) = match dist {
+        AndersonDistribution::Normal => {
+            let cdf = fitted_normal_cdf(data)?;
+            cdf.iter().map(|&p| (p.ln(), (1.0 - p).ln())).unzip()
+        }
+        AndersonDistribution::Exponential => {
+            if x[0] < 0.0 { return Err(StatsError::InvalidInputValue); }
+            let scale = mean_rs(data);
+            x.iter().map(|&v| {
+                let z = v / scale;
+                ((-(-z).exp()).ln_1p(), -z)
+            }).unzip()
+        }
+        AndersonDistribution::Logistic => {
+            let (loc, scale) = fit_logistic(&x);
+            x.iter().map(|&v| {
+                let z = (v - loc) / scale;
+                (-(-z).exp().ln_1p(), -z.exp().ln_1p())
+            }).unzip()
+        }
+        AndersonDistribution::Gumbel => {
+            let (loc, scale) = fit_gumbel(&x);
+            x.iter().map(|&v| {
+                let z = (v - loc) / scale;
+                let log_cdf = -(-z).exp();
+                (log_cdf, (-log_cdf.exp()).ln_1p())
+            }).unzip()
+        }
+    };
+
+    let sum: f64 = (0..n).map(|i| {
+        (2.0 * i as f64 + 1.0) * (log_cdf[i] + log_sf[n - 1 - i])
+    }).sum();
+    let a2 = -nf - sum / nf;
+
+    let (base, significance, adjustment): (Vec<f64>, Vec<f64>, f64) = match dist {
+        AndersonDistribution::Normal => (vec![0.576, 0.656, 0.787, 0.918, 1.092],
+                                         vec![15.0, 10.0, 5.0, 2.5, 1.0], 1.0 + 4.0 / nf - 25.0 / (nf * nf)),
+        AndersonDistribution::Exponential => (vec![0.922, 1.078, 1.341, 1.606, 1.957],
+                                              vec![15.0, 10.0, 5.0, 2.5, 1.0], 1.0 + 0.6 / nf),
+        AndersonDistribution::Logistic => (vec![0.426, 0.563, 0.660, 0.769, 0.906, 1.010],
+                                           vec![25.0, 10.0, 5.0, 2.5, 1.0, 0.5], 1.0 + 0.25 / nf),
+        AndersonDistribution::Gumbel => (vec![0.474, 0.637, 0.757, 0.877, 1.038],
+                                         vec![25.0, 10.0, 5.0, 2.5, 1.0], 1.0 + 0.2 / nf.sqrt()),
+    };
+    let critical: Vec<f64> = base.iter().map(|v| ((v / adjustment) * 1000.0).round() / 1000.0).collect();
+
+    let p_value = match dist {
+        AndersonDistribution::Normal => {
+            // D'Agostino & Stephens (1986) for estimated mean and variance
+            let aa = a2 * (1.0 + 0.75 / nf + 2.25 / (nf * nf));
+            Some(if aa < 0.2 {
+                1.0 - (-13.436 + 101.14 * aa - 223.73 * aa * aa).exp()
+            } else if aa < 0.34 {
+                1.0 - (-8.318 + 42.796 * aa - 59.938 * aa * aa).exp()
+            } else if aa < 0.6 {
+                (0.9177 - 4.279 * aa - 1.38 * aa * aa).exp()
+            } else if aa < 10.0 {
+                (1.2937 - 5.709 * aa + 0.0186 * aa * aa).exp()
+            } else {
+                3.7e-24
+            })
+        }
+        _ => None,
+    };
+    Ok((a2, critical, significance, p_value))
+}
+
+// Returns (D, p)
+pub fn lilliefors_rs(data: &Array1<f64>) -> Result<(f64, f64), StatsError> {
+    let n = data.len();
+    if n < 5 { return Err(StatsError::InvalidInputValue); }
+    let cdf = fitted_normal_cdf(data)?;
+    let nf = n as f64;
+    let d = cdf.iter().enumerate().map(|(i, &p)| {
+        let above = (i as f64 + 1.0) / nf - p;
+        let below = p - i as f64 / nf;
+        above.max(below)
+    }).fold(0.0, f64::max);
+
+    // Dallal & Wilkinson (1986), with Stephens' modified statistic above p = 0.1
+    let (kd, nd) = if n <= 100 { (d, nf) } else { (d * (nf / 100.0).powf(0.49), 100.0) };
+    let mut p = (-7.01256 * kd * kd * (nd + 2.78019) + 2.99587 * kd * (nd + 2.78019).sqrt()
+        - 0.122119 + 0.974598 / nd.sqrt() + 1.67997 / nd).exp();
+    if p > 0.1 {
+        let kk = (nf.sqrt() - 0.01 + 0.85 / nf.sqrt()) * d;
+        p = if kk <= 0.302 {
+            1.0
+        } else if kk <= 0.5 {
+            polynomial(&[2.76773, -19.828315, 80.709644, -138.55152, 81.218052], kk)
+        } else if kk <= 0.9 {
+            polynomial(&[-4.901232, 40.662806, -97.490286, 94.029866, -32.355711], kk)
+        } else if kk <= 1.31 {
+            polynomial(&[6.198765, -19.558097, 23.186922, -12.234627, 2.423045], kk)
+        } else {
+            0.0
+        };
+    }
+    Ok((d, p.clamp(0.0, 1.0)))
+}
+
+// Returns (W^2, p)
+pub fn cramer_von_mises_rs(data: &Array1<f64>) -> Result<(f64, f64), StatsError> {
+    let n = data.len();
+    if n < 8 { return Err(StatsError::InvalidInputValue); }
+    let cdf = fitted_normal_cdf(data)?;
+    let nf = n as f64;
+    let w = 1.0 / (12.0 * nf) + cdf.iter().enumerate()
+        .map(|(i, &p)| (p - (2.0 * i as f64 + 1.0) / (2.0 * nf)).powi(2))
+        .sum::<f64>();
+
+    // Stephens (1986) for estimated mean and variance
+    let ww = (1.0 + 0.5 / nf) * w;
+    let p = if ww < 0.0275 {
+        1.0 - (-13.953 + 775.5 * ww - 12542.61 * ww * ww).exp()
+    } else if ww < 0.051 {
+        1.0 - (-5.903 + 179.546 * ww - 1515.29 * ww * ww).exp()
+    } else if ww < 0.092 {
+        (0.886 - 31.62 * ww + 10.897 * ww * ww).exp()
+    } else if ww < 1.1 {
+        (1.111 - 34.242 * ww + 12.832 * ww * ww).exp()
+    } else {
+        7.37e-10
+    };
+    Ok((w, p.clamp(0.0, 1.0)))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+pub fn shapiro_wilk_test(x: &PyAny) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, p) = shapiro_wilk_rs(&x_data)?;
+    Ok(TestResult::new("Shapiro-Wilk test", statistic, p))
+}
+
+#[pyfunction]
+pub fn dagostino_pearson_test(x: &PyAny) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, p) = dagostino_pearson_rs(&x_data)?;
+    Ok(TestResult::new("D'Agostino-Pearson omnibus test", statistic, p).with_df(2.0))
+}
+
+#[pyfunction]
+pub fn jarque_bera_test(x: &PyAny) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, p) = jarque_bera_rs(&x_data)?;
+    Ok(TestResult::new("Jarque-Bera test", statistic, p).with_df(2.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, dist = "norm"))]
+pub fn anderson_darling_test(x: &PyAny, dist: &str) -> PyResult<TestResult> {
+    // dist: norm, expon, logistic or gumbel (right skewed), parameters are fitted to x
+    // The statistic is A^2 with extras critical_values and significance_levels (in percent);
+    // p_value is only available for norm and is nan otherwise
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, critical_values, significance_levels, p) = anderson_darling_rs(&x_data, AndersonDistribution::parse(dist)?)?;
+    Ok(TestResult::new("Anderson-Darling test", statistic, p.unwrap_or(f64::NAN))
+        .with_extra("critical_values", critical_values)
+        .with_extra("significance_levels", significance_levels))
+}
+
+#[pyfunction]
+pub fn lilliefors_test(x: &PyAny) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, p) = lilliefors_rs(&x_data)?;
+    Ok(TestResult::new("Lilliefors test", statistic, p))
+}
+
+#[pyfunction]
+pub fn cramer_von_mises_test(x: &PyAny) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let (statistic, p) = cramer_von_mises_rs(&x_data)?;
+    Ok(TestResult::new("Cramer-von Mises test", statistic, p))
+}
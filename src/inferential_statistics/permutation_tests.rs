@@ -0,0 +1,252 @@
+// Permutation and randomization tests
+// permutation_test -- exact or Monte Carlo null distribution of a two sample statistic
+//
+// Designs: independent (observations exchanged between the samples), paired (x_i and y_i swapped
+// within each pair, i.e. sign flips of the differences) and correlation (y permuted against x).
+// Exact tests enumerate every rearrangement, Monte Carlo tests draw n_resamples of them with
+// replicate b taking its own stream of the seeded generator, so p-values are reproducible.
+
+use ndarray::{Array1, Axis};
+use pyo3::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::descriptive_statistics::{correlation_rs, mean_rs, median_rs};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::resampling::replicate_rng;
+use crate::utils::from_pyarray1;
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PermutationDesign {
+    Independent,
+    Paired,
+    Correlation,
+}
+
+impl PermutationDesign {
+    pub fn parse(design: &str) -> Result<PermutationDesign, StatsError> {
+        match design {
+            "independent" => Ok(PermutationDesign::Independent),
+            "paired" => Ok(PermutationDesign::Paired),
+            "correlation" => Ok(PermutationDesign::Correlation),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+
+    // Number of distinct rearrangements, as a float since it overflows quickly
+    pub fn count(&self, n_x: usize, n_y: usize) -> f64 {
+        match self {
+            PermutationDesign::Independent => {
+                let n = n_x + n_y;
+                (0..n_x).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64).round()
+            }
+            PermutationDesign::Paired => 2.0_f64.powi(n_x as i32),
+            PermutationDesign::Correlation => (1..=n_x).fold(1.0, |acc, i| acc * i as f64),
+        }
+    }
+}
+
+// "auto" is exact whenever there are no more rearrangements than n_resamples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PermutationMethod {
+    Auto,
+    Exact,
+    MonteCarlo,
+}
+
+impl PermutationMethod {
+    pub fn parse(method: &str) -> Result<PermutationMethod, StatsError> {
+        match method {
+            "auto" => Ok(PermutationMethod::Auto),
+            "exact" => Ok(PermutationMethod::Exact),
+            "monte-carlo" => Ok(PermutationMethod::MonteCarlo),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// Any statistic of the two (rearranged) samples
+pub type PairStatistic = fn(&Array1<f64>, &Array1<f64>) -> f64;
+
+fn mean_difference(x: &Array1<f64>, y: &Array1<f64>) -> f64 {
+    mean_rs(x) - mean_rs(y)
+}
+
+fn median_difference(x: &Array1<f64>, y: &Array1<f64>) -> f64 {
+    median_rs(x) - median_rs(y)
+}
+
+pub fn pair_statistic_by_name(name: &str) -> Result<PairStatistic, StatsError> {
+    match name {
+        "mean_difference" => Ok(mean_difference),
+        "median_difference" => Ok(median_difference),
+        "correlation" => Ok(correlation_rs),
+        _ => Err(StatsError::InvalidInputValue),
+    }
+}
+
+// Exact enumeration is refused beyond this many rearrangements
+const MAX_EXACT: f64 = 1e7;
+
+// Applies one rearrangement. Independent: a permutation of the pooled positions, the first n_x
+// going to x. Paired: one flag per pair, non-zero swaps the pair. Correlation: a permutation of y.
+fn rearrange(design: PermutationDesign, x: &Array1<f64>, y: &Array1<f64>, arrangement: &[usize])
+             -> (Array1<f64>, Array1<f64>) {
+    match design {
+        PermutationDesign::Independent => {
+            let pooled: Array1<f64> = x.iter().chain(y.iter()).cloned().collect();
+            let (to_x, to_y) = arrangement.split_at(x.len());
+            (pooled.select(Axis(0), to_x), pooled.select(Axis(0), to_y))
+        }
+        PermutationDesign::Paired => {
+            let mut x_new = x.clone();
+            let mut y_new = y.clone();
+            for (i, &swap) in arrangement.iter().enumerate() {
+                if swap != 0 {
+                    x_new[i] = y[i];
+                    y_new[i] = x[i];
+                }
+            }
+            (x_new, y_new)
+        }
+        PermutationDesign::Correlation => (x.clone(), y.select(Axis(0), arrangement)),
+    }
+}
+
+fn random_arrangement(design: PermutationDesign, n_x: usize, n_y: usize, rng: &mut impl Rng) -> Vec<usize> {
+    match design {
+        PermutationDesign::Independent | PermutationDesign::Correlation => {
+            let n = if design == PermutationDesign::Independent { n_x + n_y } else { n_x };
+            let mut arrangement: Vec<usize> = (0..n).collect();
+            arrangement.shuffle(rng);
+            arrangement
+        }
+        PermutationDesign::Paired => (0..n_x).map(|_| rng.gen_range(0..2)).collect(),
+    }
+}
+
+// Advances to the next lexicographic permutation, false once the last one is reached
+fn next_permutation(values: &mut [usize]) -> bool {
+    let Some(i) = (1..values.len()).rev().find(|&i| values[i - 1] < values[i]) else { return false };
+    let j = (i..values.len()).rev().find(|&j| values[j] > values[i - 1]).unwrap();
+    values.swap(i - 1, j);
+    values[i..].reverse();
+    true
+}
+
+// Every arrangement of the design, generated one at a time rather than held in memory
+fn all_arrangements(design: PermutationDesign, n_x: usize, n_y: usize) -> Box<dyn Iterator<Item = Vec<usize>> + Send> {
+    match design {
+        PermutationDesign::Independent => {
+            // every choice of n_x positions for x, as a 0/1 mask run through next_permutation
+            let n = n_x + n_y;
+            let mut mask: Option<Vec<usize>> = Some((0..n).map(|i| usize::from(i >= n - n_x)).collect());
+            Box::new(std::iter::from_fn(move || {
+                let mut current = mask.take()?;
+                let mut arrangement: Vec<usize> = (0..n).filter(|&i| current[i] == 1).collect();
+                arrangement.extend((0..n).filter(|&i| current[i] == 0));
+                if next_permutation(&mut current) { mask = Some(current); }
+                Some(arrangement)
+            }))
+        }
+        PermutationDesign::Paired => Box::new((0..1usize << n_x)
+            .map(move |mask| (0..n_x).map(|i| (mask >> i) & 1).collect())),
+        PermutationDesign::Correlation => {
+            let mut permutation: Option<Vec<usize>> = Some((0..n_x).collect());
+            Box::new(std::iter::from_fn(move || {
+                let current = permutation.take()?;
+                let mut next = current.clone();
+                if next_permutation(&mut next) { permutation = Some(next); }
+                Some(current)
+            }))
+        }
+    }
+}
+
+// Returns (observed statistic, p-value, whether the null distribution was exact)
+#[allow(clippy::too_many_arguments)]
+pub fn permutation_test_rs(x: &Array1<f64>, y: &Array1<f64>, statistic: PairStatistic,
+                           design: PermutationDesign, alternative: Alternative, method: PermutationMethod,
+                           n_resamples: usize, seed: u64) -> Result<(f64, f64, bool), StatsError> {
+    if x.is_empty() || y.is_empty() { return Err(StatsError::EmptyDataSet); }
+    if design != PermutationDesign::Independent && x.len() != y.len() { return Err(StatsError::InconsistentLength); }
+    if n_resamples == 0 { return Err(StatsError::InvalidInputValue); }
+
+    let total = design.count(x.len(), y.len());
+    let exact = match method {
+        PermutationMethod::Auto => total <= n_resamples as f64 && total <= MAX_EXACT,
+        PermutationMethod::Exact => {
+            if total > MAX_EXACT { return Err(StatsError::InvalidInputValue); }
+            true
+        }
+        PermutationMethod::MonteCarlo => false,
+    };
+
+    let observed = statistic(x, y);
+    // ties within rounding error of the observed value count as at least as extreme
+    let tol = 1e-12 * observed.abs().max(1.0);
+    // (at least as large, at least as small, arrangements) counted without keeping the null values
+    let tally = |arrangement: &[usize]| {
+        let (x_new, y_new) = rearrange(design, x, y, arrangement);
+        let t = statistic(&x_new, &y_new);
+        (usize::from(t >= observed - tol), usize::from(t <= observed + tol), 1usize)
+    };
+    let add = |a: (usize, usize, usize), b: (usize, usize, usize)| (a.0 + b.0, a.1 + b.1, a.2 + b.2);
+    let (greater, less, size) = if exact {
+        all_arrangements(design, x.len(), y.len()).par_bridge()
+            .map(|arrangement| tally(&arrangement))
+            .reduce(|| (0, 0, 0), add)
+    } else {
+        (0..n_resamples).into_par_iter()
+            .map(|b| tally(&random_arrangement(design, x.len(), y.len(), &mut replicate_rng(seed, b))))
+            .reduce(|| (0, 0, 0), add)
+    };
+    let (greater, less, size) = (greater as f64, less as f64, size as f64);
+
+    // Monte Carlo p-values include the observed arrangement (Phipson & Smyth) so they are never 0
+    let p_of = |count: f64| if exact { count / size } else { (count + 1.0) / (size + 1.0) };
+    let p = match alternative {
+        Alternative::Greater => p_of(greater),
+        Alternative::Less => p_of(less),
+        Alternative::TwoSided => (2.0 * p_of(greater).min(p_of(less))).min(1.0),
+    };
+    Ok((observed, p, exact))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, y, statistic = "mean_difference", design = "independent", alternative = "two-sided",
+                    method = "auto", n_resamples = 9999, seed = None))]
+#[allow(clippy::too_many_arguments)]
+pub fn permutation_test(x: &PyAny, y: &PyAny, statistic: &str, design: &str, alternative: &str,
+                        method: &str, n_resamples: usize, seed: Option<u64>) -> PyResult<TestResult> {
+    // statistic: mean_difference, median_difference or correlation
+    // design: independent, paired or correlation
+    // method: auto (exact when there are at most n_resamples and at most 1e7 rearrangements), exact or monte-carlo
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+
+    let alternative = Alternative::parse(alternative)?;
+    let (observed, p, exact) = permutation_test_rs(&x_data, &y_data, pair_statistic_by_name(statistic)?,
+                                                   PermutationDesign::parse(design)?, alternative,
+                                                   PermutationMethod::parse(method)?, n_resamples,
+                                                   seed.unwrap_or_else(rand::random))?;
+    let name = if exact { "Exact permutation test" } else { "Monte Carlo permutation test" };
+    Ok(TestResult::new(name, observed, p).with_alternative(alternative).with_estimate(observed))
+}
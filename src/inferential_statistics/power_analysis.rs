@@ -0,0 +1,247 @@
+// Power analysis and sample size calculators
+// power_analysis -- given three of effect size, nobs, alpha and power, solve for the fourth
+// power_curve -- power over a grid of effect sizes and sample sizes for planning
+//
+// Effect sizes follow Cohen: d for t-tests, h for proportions, f for anova, w for chi-square.
+// nobs is the size of the first group for two sample designs (the second is ratio * nobs)
+// and the total sample size for anova and chi-square.
+
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor, Normal, StudentsT};
+
+use crate::distributions::noncentral::{noncentral_chi_squared_cdf, noncentral_f_cdf, noncentral_t_cdf};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::Alternative;
+use crate::utils::{bisect_rs, from_pyarray1};
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerTest {
+    OneSample,
+    Paired,
+    TwoSample,
+    OneProportion,
+    TwoProportion,
+    Anova,
+    ChiSquare,
+}
+
+impl PowerTest {
+    pub fn parse(test: &str) -> Result<PowerTest, StatsError> {
+        match test {
+            "one-sample" => Ok(PowerTest::OneSample),
+            "paired" => Ok(PowerTest::Paired),
+            "two-sample" => Ok(PowerTest::TwoSample),
+            "one-proportion" => Ok(PowerTest::OneProportion),
+            "two-proportion" => Ok(PowerTest::TwoProportion),
+            "anova" => Ok(PowerTest::Anova),
+            "chi-square" => Ok(PowerTest::ChiSquare),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PowerDesign {
+    pub test: PowerTest,
+    pub alternative: Alternative,
+    // second group size relative to the first, two sample designs only
+    pub ratio: f64,
+    // number of groups, anova only
+    pub k_groups: usize,
+    // degrees of freedom, chi-square only
+    pub df: f64,
+}
+
+impl PowerDesign {
+    // Smallest sample size the design is defined for
+    pub fn min_nobs(&self) -> f64 {
+        match self.test {
+            PowerTest::OneSample | PowerTest::Paired => 2.0,
+            PowerTest::TwoSample => 2.0_f64.max(2.0 / self.ratio),
+            PowerTest::OneProportion | PowerTest::TwoProportion | PowerTest::ChiSquare => 1.0,
+            PowerTest::Anova => self.k_groups as f64 + 1.0,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), StatsError> {
+        if self.ratio <= 0.0 || !self.ratio.is_finite() { return Err(StatsError::InvalidInputValue); }
+        if self.test == PowerTest::Anova && self.k_groups < 2 { return Err(StatsError::InsufficientGroups); }
+        if self.test == PowerTest::ChiSquare && self.df < 1.0 { return Err(StatsError::InvalidInputValue); }
+        if matches!(self.test, PowerTest::Anova | PowerTest::ChiSquare) && self.alternative != Alternative::TwoSided {
+            // F and chi-square tests are one tailed by construction
+            return Err(StatsError::InvalidInputValue);
+        }
+        Ok(())
+    }
+
+    pub fn power(&self, effect_size: f64, nobs: f64, alpha: f64) -> f64 {
+        match self.test {
+            PowerTest::OneSample | PowerTest::Paired => {
+                t_power(effect_size * nobs.sqrt(), nobs - 1.0, alpha, self.alternative)
+            }
+            PowerTest::TwoSample => {
+                let n2 = nobs * self.ratio;
+                let ncp = effect_size / (1.0 / nobs + 1.0 / n2).sqrt();
+                t_power(ncp, nobs + n2 - 2.0, alpha, self.alternative)
+            }
+            PowerTest::OneProportion => normal_power(effect_size * nobs.sqrt(), alpha, self.alternative),
+            PowerTest::TwoProportion => {
+                let n2 = nobs * self.ratio;
+                normal_power(effect_size / (1.0 / nobs + 1.0 / n2).sqrt(), alpha, self.alternative)
+            }
+            PowerTest::Anova => {
+                let df1 = self.k_groups as f64 - 1.0;
+                let df2 = nobs - self.k_groups as f64;
+                let critical = FisherSnedecor::new(df1, df2).unwrap().inverse_cdf(1.0 - alpha);
+                1.0 - noncentral_f_cdf(critical, df1, df2, effect_size * effect_size * nobs)
+            }
+            PowerTest::ChiSquare => {
+                let critical = ChiSquared::new(self.df).unwrap().inverse_cdf(1.0 - alpha);
+                1.0 - noncentral_chi_squared_cdf(critical, self.df, effect_size * effect_size * nobs)
+            }
+        }
+    }
+}
+
+fn t_power(ncp: f64, df: f64, alpha: f64, alternative: Alternative) -> f64 {
+    let t = StudentsT::new(0.0, 1.0, df).unwrap();
+    match alternative {
+        Alternative::TwoSided => {
+            let critical = t.inverse_cdf(1.0 - alpha / 2.0);
+            (1.0 - noncentral_t_cdf(critical, df, ncp)) + noncentral_t_cdf(-critical, df, ncp)
+        }
+        Alternative::Greater => 1.0 - noncentral_t_cdf(t.inverse_cdf(1.0 - alpha), df, ncp),
+        Alternative::Less => noncentral_t_cdf(-t.inverse_cdf(1.0 - alpha), df, ncp),
+    }
+}
+
+fn normal_power(ncp: f64, alpha: f64, alternative: Alternative) -> f64 {
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    match alternative {
+        Alternative::TwoSided => {
+            let critical = normal.inverse_cdf(1.0 - alpha / 2.0);
+            normal.cdf(ncp - critical) + normal.cdf(-ncp - critical)
+        }
+        Alternative::Greater => normal.cdf(ncp - normal.inverse_cdf(1.0 - alpha)),
+        Alternative::Less => normal.cdf(-ncp - normal.inverse_cdf(1.0 - alpha)),
+    }
+}
+
+// Widens [lo, hi] upwards until f(hi) >= target, for f increasing in its argument
+fn expand_upper<F: Fn(f64) -> f64>(f: &F, lo: f64, target: f64, limit: f64) -> Result<f64, StatsError> {
+    let mut hi = (2.0 * lo).max(lo + 1.0);
+    while f(hi) < target {
+        if hi >= limit { return Err(StatsError::NoSolution); }
+        hi *= 2.0;
+    }
+    Ok(hi)
+}
+
+// Solves for whichever one of effect size, nobs, alpha or power is None
+pub fn solve_power_rs(design: &PowerDesign, effect_size: Option<f64>, nobs: Option<f64>,
+                      alpha: Option<f64>, power: Option<f64>) -> Result<f64, StatsError> {
+    design.validate()?;
+    let missing = [effect_size.is_none(), nobs.is_none(), alpha.is_none(), power.is_none()]
+        .iter().filter(|&&m| m).count();
+    if missing != 1 { return Err(StatsError::InvalidInputValue); }
+
+    if let Some(alpha) = alpha {
+        if alpha <= 0.0 || alpha >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    }
+    if let Some(power) = power {
+        if power <= 0.0 || power >= 1.0 { return Err(StatsError::InvalidInputValue); }
+        // power can never fall below the size of the test
+        if alpha.is_some_and(|alpha| power <= alpha) { return Err(StatsError::NoSolution); }
+    }
+    if let Some(nobs) = nobs {
+        if nobs < design.min_nobs() { return Err(StatsError::InvalidInputValue); }
+    }
+
+    let tol = 1e-10;
+    match (effect_size, nobs, alpha, power) {
+        (Some(es), Some(n), Some(a), None) => Ok(design.power(es, n, a)),
+        (Some(es), None, Some(a), Some(target)) => {
+            let f = |n: f64| design.power(es, n, a);
+            let lo = design.min_nobs();
+            if f(lo) >= target { return Ok(lo); }
+            let hi = expand_upper(&f, lo, target, 1e9)?;
+            Ok(bisect_rs(|n| f(n) - target, lo, hi, tol))
+        }
+        (None, Some(n), Some(a), Some(target)) => {
+            // effects pointing the wrong way never reach the target, search the side that can
+            let sign = if design.alternative == Alternative::Less { -1.0 } else { 1.0 };
+            let f = |es: f64| design.power(sign * es, n, a);
+            let hi = expand_upper(&f, 0.0, target, 1e6)?;
+            Ok(sign * bisect_rs(|es| f(es) - target, 0.0, hi, tol))
+        }
+        (Some(es), Some(n), None, Some(target)) => {
+            let f = |a: f64| design.power(es, n, a);
+            let (lo, hi) = (1e-12, 1.0 - 1e-12);
+            if f(lo) > target || f(hi) < target { return Err(StatsError::NoSolution); }
+            Ok(bisect_rs(|a| f(a) - target, lo, hi, tol))
+        }
+        _ => Err(StatsError::InvalidInputValue),
+    }
+}
+
+// Power for every (effect size, nobs) pair, rows follow effect sizes and columns nobs
+pub fn power_curve_rs(design: &PowerDesign, effect_sizes: &[f64], nobs: &[f64], alpha: f64) -> Result<Array2<f64>, StatsError> {
+    design.validate()?;
+    if alpha <= 0.0 || alpha >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    if nobs.iter().any(|&n| n < design.min_nobs()) { return Err(StatsError::InvalidInputValue); }
+    Ok(Array2::from_shape_fn((effect_sizes.len(), nobs.len()), |(i, j)| {
+        design.power(effect_sizes[i], nobs[j], alpha)
+    }))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (test, effect_size = None, nobs = None, alpha = None, power = None,
+                    alternative = "two-sided", ratio = 1.0, k_groups = 2, df = 1.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn power_analysis(test: &str, effect_size: Option<f64>, nobs: Option<f64>, alpha: Option<f64>,
+                      power: Option<f64>, alternative: &str, ratio: f64, k_groups: usize, df: f64) -> PyResult<f64> {
+    // Leave exactly one of effect_size, nobs, alpha, power as None and it is solved for.
+    // tests: one-sample, paired, two-sample, one-proportion, two-proportion, anova, chi-square
+    let design = PowerDesign {
+        test: PowerTest::parse(test)?,
+        alternative: Alternative::parse(alternative)?,
+        ratio,
+        k_groups,
+        df,
+    };
+    Ok(solve_power_rs(&design, effect_size, nobs, alpha, power)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (test, effect_sizes, nobs, alpha = 0.05, alternative = "two-sided",
+                    ratio = 1.0, k_groups = 2, df = 1.0))]
+#[allow(clippy::too_many_arguments)]
+pub fn power_curve<'py>(py: Python<'py>, test: &str, effect_sizes: &PyAny, nobs: &PyAny, alpha: f64,
+                        alternative: &str, ratio: f64, k_groups: usize, df: f64) -> PyResult<&'py PyArray2<f64>> {
+    // Returns a len(effect_sizes) x len(nobs) array of power
+    let effect_data = match from_pyarray1(effect_sizes) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let nobs_data = match from_pyarray1(nobs) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let design = PowerDesign {
+        test: PowerTest::parse(test)?,
+        alternative: Alternative::parse(alternative)?,
+        ratio,
+        k_groups,
+        df,
+    };
+    let curve = power_curve_rs(&design, &effect_data.to_vec(), &nobs_data.to_vec(), alpha)?;
+    Ok(curve.into_pyarray(py))
+}
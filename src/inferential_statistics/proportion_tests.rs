@@ -0,0 +1,362 @@
+// Tests for binary outcomes
+// binomial_test -- exact test of a single proportion, Clopper-Pearson interval
+// proportion_ztest -- normal approximation test of a single proportion
+// two_proportion_ztest -- pooled z test for the difference of two independent proportions
+// proportion_interval -- wald, wilson, agresti-coull or clopper-pearson interval for a proportion
+// mcnemar_test -- paired binary outcomes, table [[a, b], [c, d]] with rows the first
+//                 measurement and columns the second
+// cochrans_q_test -- k related binary outcomes, subjects in rows and treatments in columns
+// cochran_mantel_haenszel_test -- association in K stratified 2x2 tables, laid out like
+//                                 fisher_exact_test, with the Mantel-Haenszel common odds ratio
+
+use ndarray::Array2;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+use statrs::function::beta::beta_reg;
+use statrs::function::factorial::ln_binomial;
+
+use crate::inferential_statistics::contingency_tests::Table2x2;
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::utils::{bisect_rs, from_pyarray2};
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProportionInterval {
+    Wald,
+    Wilson,
+    AgrestiCoull,
+    ClopperPearson,
+}
+
+impl ProportionInterval {
+    pub fn parse(method: &str) -> Result<ProportionInterval, StatsError> {
+        match method {
+            "wald" => Ok(ProportionInterval::Wald),
+            "wilson" => Ok(ProportionInterval::Wilson),
+            "agresti-coull" => Ok(ProportionInterval::AgrestiCoull),
+            "clopper-pearson" => Ok(ProportionInterval::ClopperPearson),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DifferenceInterval {
+    Wald,
+    // Newcombe's hybrid score interval built from the two Wilson intervals
+    Newcombe,
+}
+
+impl DifferenceInterval {
+    pub fn parse(method: &str) -> Result<DifferenceInterval, StatsError> {
+        match method {
+            "wald" => Ok(DifferenceInterval::Wald),
+            "newcombe" => Ok(DifferenceInterval::Newcombe),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+fn check_counts(k: u64, n: u64) -> Result<(), StatsError> {
+    if n == 0 { return Err(StatsError::EmptyDataSet); }
+    if k > n { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+fn check_probability(p: f64) -> Result<(), StatsError> {
+    if !(p > 0.0 && p < 1.0) { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+// Tail probability left outside each bound: half of 1 - confidence for two sided intervals,
+// all of it on the bounded side for one sided ones
+fn tail_probabilities(confidence_level: f64, alternative: Alternative) -> Result<(f64, f64), StatsError> {
+    if confidence_level <= 0.0 || confidence_level >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    let alpha = 1.0 - confidence_level;
+    Ok(match alternative {
+        Alternative::TwoSided => (alpha / 2.0, alpha / 2.0),
+        Alternative::Greater => (alpha, 0.0),
+        Alternative::Less => (0.0, alpha),
+    })
+}
+
+fn z_quantile(tail: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - tail)
+}
+
+fn binomial_ln_pmf(i: u64, n: u64, p: f64) -> f64 {
+    ln_binomial(n, i) + i as f64 * p.ln() + (n - i) as f64 * (1.0 - p).ln()
+}
+
+// One bound of the interval, `upper` picking the side, with `tail` probability beyond it
+fn proportion_bound(k: u64, n: u64, tail: f64, upper: bool, method: ProportionInterval) -> f64 {
+    if tail == 0.0 { return if upper { 1.0 } else { 0.0 }; }
+    let (kf, nf) = (k as f64, n as f64);
+    let p_hat = kf / nf;
+    let sign = if upper { 1.0 } else { -1.0 };
+    let z = z_quantile(tail);
+    let bound = match method {
+        ProportionInterval::Wald => p_hat + sign * z * (p_hat * (1.0 - p_hat) / nf).sqrt(),
+        ProportionInterval::Wilson => {
+            let z2 = z * z;
+            let center = (p_hat + z2 / (2.0 * nf)) / (1.0 + z2 / nf);
+            let half = z / (1.0 + z2 / nf) * (p_hat * (1.0 - p_hat) / nf + z2 / (4.0 * nf * nf)).sqrt();
+            center + sign * half
+        }
+        ProportionInterval::AgrestiCoull => {
+            let n_tilde = nf + z * z;
+            let p_tilde = (kf + z * z / 2.0) / n_tilde;
+            p_tilde + sign * z * (p_tilde * (1.0 - p_tilde) / n_tilde).sqrt()
+        }
+        ProportionInterval::ClopperPearson => {
+            // P(X >= k | lower) = tail and P(X <= k | upper) = tail, through the beta cdf
+            if upper {
+                if k == n { return 1.0; }
+                bisect_rs(|p| beta_reg(kf + 1.0, nf - kf, p) - (1.0 - tail), 0.0, 1.0, 1e-14)
+            } else {
+                if k == 0 { return 0.0; }
+                bisect_rs(|p| beta_reg(kf, nf - kf + 1.0, p) - tail, 0.0, 1.0, 1e-14)
+            }
+        }
+    };
+    bound.clamp(0.0, 1.0)
+}
+
+pub fn proportion_interval_rs(k: u64, n: u64, confidence_level: f64, alternative: Alternative,
+                              method: ProportionInterval) -> Result<(f64, f64), StatsError> {
+    check_counts(k, n)?;
+    let (lower_tail, upper_tail) = tail_probabilities(confidence_level, alternative)?;
+    Ok((proportion_bound(k, n, lower_tail, false, method), proportion_bound(k, n, upper_tail, true, method)))
+}
+
+// Interval for p1 - p2
+pub fn difference_interval_rs(k1: u64, n1: u64, k2: u64, n2: u64, confidence_level: f64, alternative: Alternative,
+                              method: DifferenceInterval) -> Result<(f64, f64), StatsError> {
+    check_counts(k1, n1)?;
+    check_counts(k2, n2)?;
+    let (lower_tail, upper_tail) = tail_probabilities(confidence_level, alternative)?;
+    let (p1, p2) = (k1 as f64 / n1 as f64, k2 as f64 / n2 as f64);
+    let difference = p1 - p2;
+    let bound = |tail: f64, upper: bool| -> f64 {
+        if tail == 0.0 { return if upper { 1.0 } else { -1.0 }; }
+        match method {
+            DifferenceInterval::Wald => {
+                let se = (p1 * (1.0 - p1) / n1 as f64 + p2 * (1.0 - p2) / n2 as f64).sqrt();
+                let sign = if upper { 1.0 } else { -1.0 };
+                (difference + sign * z_quantile(tail) * se).clamp(-1.0, 1.0)
+            }
+            DifferenceInterval::Newcombe => {
+                let (l1, u1) = (proportion_bound(k1, n1, tail, false, ProportionInterval::Wilson),
+                                proportion_bound(k1, n1, tail, true, ProportionInterval::Wilson));
+                let (l2, u2) = (proportion_bound(k2, n2, tail, false, ProportionInterval::Wilson),
+                                proportion_bound(k2, n2, tail, true, ProportionInterval::Wilson));
+                if upper {
+                    difference + ((u1 - p1).powi(2) + (p2 - l2).powi(2)).sqrt()
+                } else {
+                    difference - ((p1 - l1).powi(2) + (u2 - p2).powi(2)).sqrt()
+                }
+            }
+        }
+    };
+    Ok((bound(lower_tail, false), bound(upper_tail, true)))
+}
+
+// Exact p-value, two sided sums every outcome no more likely than k (with scipy's tolerance)
+pub fn binomial_p_value_rs(k: u64, n: u64, p: f64, alternative: Alternative) -> f64 {
+    let pmf: Vec<f64> = (0..=n).map(|i| binomial_ln_pmf(i, n, p).exp()).collect();
+    let value: f64 = match alternative {
+        Alternative::Greater => pmf[k as usize..].iter().sum(),
+        Alternative::Less => pmf[..=k as usize].iter().sum(),
+        Alternative::TwoSided => {
+            let cutoff = pmf[k as usize] * (1.0 + 1e-7);
+            pmf.iter().filter(|&&d| d <= cutoff).sum()
+        }
+    };
+    value.min(1.0)
+}
+
+pub fn binomial_test_rs(k: u64, n: u64, p: f64, alternative: Alternative, confidence_level: f64) -> Result<TestResult, StatsError> {
+    check_counts(k, n)?;
+    check_probability(p)?;
+    let interval = proportion_interval_rs(k, n, confidence_level, alternative, ProportionInterval::ClopperPearson)?;
+    Ok(TestResult::new("Exact binomial test", k as f64, binomial_p_value_rs(k, n, p, alternative))
+        .with_alternative(alternative)
+        .with_estimate(k as f64 / n as f64)
+        .with_confidence_interval(interval))
+}
+
+pub fn proportion_ztest_rs(k: u64, n: u64, p: f64, alternative: Alternative, confidence_level: f64,
+                           interval: ProportionInterval) -> Result<TestResult, StatsError> {
+    check_counts(k, n)?;
+    check_probability(p)?;
+    let p_hat = k as f64 / n as f64;
+    // standard error under the null
+    let z = (p_hat - p) / (p * (1.0 - p) / n as f64).sqrt();
+    Ok(TestResult::new("One-sample z test for a proportion", z, alternative.normal_p_value(z))
+        .with_alternative(alternative)
+        .with_estimate(p_hat)
+        .with_confidence_interval(proportion_interval_rs(k, n, confidence_level, alternative, interval)?))
+}
+
+pub fn two_proportion_ztest_rs(k1: u64, n1: u64, k2: u64, n2: u64, alternative: Alternative,
+                               confidence_level: f64, interval: DifferenceInterval) -> Result<TestResult, StatsError> {
+    check_counts(k1, n1)?;
+    check_counts(k2, n2)?;
+    let (p1, p2) = (k1 as f64 / n1 as f64, k2 as f64 / n2 as f64);
+    let pooled = (k1 + k2) as f64 / (n1 + n2) as f64;
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 as f64 + 1.0 / n2 as f64)).sqrt();
+    if se == 0.0 { return Err(StatsError::ZeroVariance); }
+    let z = (p1 - p2) / se;
+    Ok(TestResult::new("Two-sample z test for proportions", z, alternative.normal_p_value(z))
+        .with_alternative(alternative)
+        .with_estimate(p1 - p2)
+        .with_confidence_interval(difference_interval_rs(k1, n1, k2, n2, confidence_level, alternative, interval)?))
+}
+
+// Exact version is the binomial test of b against b + c discordant pairs at p = 0.5
+pub fn mcnemar_test_rs(table: &Table2x2, exact: bool, correction: bool, confidence_level: f64) -> Result<TestResult, StatsError> {
+    let (b, c) = (table.b as f64, table.c as f64);
+    let n = (table.a + table.b + table.c + table.d) as f64;
+    if table.b + table.c == 0 { return Err(StatsError::ZeroVariance); }
+
+    // difference of the marginal proportions, (a + b) / n - (a + c) / n, with a wald interval
+    let difference = (b - c) / n;
+    let se = (b + c - (b - c).powi(2) / n).sqrt() / n;
+    let (tail, _) = tail_probabilities(confidence_level, Alternative::TwoSided)?;
+    let z = z_quantile(tail);
+    let interval = ((difference - z * se).max(-1.0), (difference + z * se).min(1.0));
+
+    let result = if exact {
+        TestResult::new("Exact McNemar test", b, binomial_p_value_rs(table.b, table.b + table.c, 0.5, Alternative::TwoSided))
+    } else {
+        let shift = if correction { 1.0 } else { 0.0 };
+        let statistic = ((b - c).abs() - shift).max(0.0).powi(2) / (b + c);
+        TestResult::new("McNemar's chi-squared test", statistic, ChiSquared::new(1.0).unwrap().sf(statistic)).with_df(1.0)
+    };
+    Ok(result.with_estimate(difference).with_confidence_interval(interval))
+}
+
+// data holds 0/1 outcomes, one row per subject and one column per treatment
+pub fn cochrans_q_test_rs(data: &Array2<f64>) -> Result<TestResult, StatsError> {
+    let k = data.ncols();
+    if k < 2 { return Err(StatsError::InsufficientGroups); }
+    if data.nrows() == 0 { return Err(StatsError::EmptyDataSet); }
+    if data.iter().any(|&v| v != 0.0 && v != 1.0) { return Err(StatsError::InvalidInputValue); }
+
+    let kf = k as f64;
+    let column_totals = data.sum_axis(ndarray::Axis(0));
+    let row_totals = data.sum_axis(ndarray::Axis(1));
+    let total = row_totals.sum();
+    let denominator = kf * total - row_totals.iter().map(|r| r * r).sum::<f64>();
+    if denominator == 0.0 { return Err(StatsError::ZeroVariance); }
+    let q = (kf - 1.0) * (kf * column_totals.iter().map(|c| c * c).sum::<f64>() - total * total) / denominator;
+    Ok(TestResult::new("Cochran's Q test", q, ChiSquared::new(kf - 1.0).unwrap().sf(q)).with_df(kf - 1.0))
+}
+
+pub fn cochran_mantel_haenszel_rs(tables: &[Table2x2], correction: bool, confidence_level: f64) -> Result<TestResult, StatsError> {
+    if tables.is_empty() { return Err(StatsError::EmptyDataSet); }
+    let (mut observed, mut expected, mut variance) = (0.0, 0.0, 0.0);
+    // Robins-Breslow-Greenland sums for the variance of the log common odds ratio
+    let (mut sum_r, mut sum_s, mut sum_pr, mut sum_ps_qr, mut sum_qs) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for table in tables {
+        let (a, b, c, d) = (table.a as f64, table.b as f64, table.c as f64, table.d as f64);
+        let n = a + b + c + d;
+        // strata of a single observation carry no information
+        if n < 2.0 { continue; }
+        observed += a;
+        expected += (a + b) * (a + c) / n;
+        variance += (a + b) * (c + d) * (a + c) * (b + d) / (n * n * (n - 1.0));
+
+        let (r, s) = (a * d / n, b * c / n);
+        let (p, q) = ((a + d) / n, (b + c) / n);
+        sum_r += r;
+        sum_s += s;
+        sum_pr += p * r;
+        sum_ps_qr += p * s + q * r;
+        sum_qs += q * s;
+    }
+    if variance == 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let shift = if correction { 0.5 } else { 0.0 };
+    let statistic = ((observed - expected).abs() - shift).max(0.0).powi(2) / variance;
+    let mut result = TestResult::new("Cochran-Mantel-Haenszel test", statistic, ChiSquared::new(1.0).unwrap().sf(statistic))
+        .with_df(1.0);
+
+    if sum_r > 0.0 && sum_s > 0.0 {
+        let common_or = sum_r / sum_s;
+        let se = (sum_pr / (2.0 * sum_r * sum_r) + sum_ps_qr / (2.0 * sum_r * sum_s) + sum_qs / (2.0 * sum_s * sum_s)).sqrt();
+        let (tail, _) = tail_probabilities(confidence_level, Alternative::TwoSided)?;
+        let z = z_quantile(tail);
+        result = result.with_estimate(common_or)
+            .with_confidence_interval(((common_or.ln() - z * se).exp(), (common_or.ln() + z * se).exp()));
+    }
+    Ok(result)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (k, n, p = 0.5, alternative = "two-sided", confidence_level = 0.95))]
+pub fn binomial_test(k: u64, n: u64, p: f64, alternative: &str, confidence_level: f64) -> PyResult<TestResult> {
+    Ok(binomial_test_rs(k, n, p, Alternative::parse(alternative)?, confidence_level)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (k, n, p = 0.5, alternative = "two-sided", confidence_level = 0.95, interval = "wilson"))]
+pub fn proportion_ztest(k: u64, n: u64, p: f64, alternative: &str, confidence_level: f64, interval: &str) -> PyResult<TestResult> {
+    Ok(proportion_ztest_rs(k, n, p, Alternative::parse(alternative)?, confidence_level, ProportionInterval::parse(interval)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (k1, n1, k2, n2, alternative = "two-sided", confidence_level = 0.95, interval = "newcombe"))]
+pub fn two_proportion_ztest(k1: u64, n1: u64, k2: u64, n2: u64, alternative: &str, confidence_level: f64,
+                            interval: &str) -> PyResult<TestResult> {
+    // interval is for p1 - p2: wald or newcombe
+    Ok(two_proportion_ztest_rs(k1, n1, k2, n2, Alternative::parse(alternative)?, confidence_level,
+                               DifferenceInterval::parse(interval)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (k, n, confidence_level = 0.95, method = "wilson"))]
+pub fn proportion_interval(k: u64, n: u64, confidence_level: f64, method: &str) -> PyResult<(f64, f64)> {
+    // method: wald, wilson, agresti-coull or clopper-pearson
+    Ok(proportion_interval_rs(k, n, confidence_level, Alternative::TwoSided, ProportionInterval::parse(method)?)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (table, exact = false, correction = true, confidence_level = 0.95))]
+pub fn mcnemar_test(table: &PyAny, exact: bool, correction: bool, confidence_level: f64) -> PyResult<TestResult> {
+    let table_data = match from_pyarray2(table) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    Ok(mcnemar_test_rs(&Table2x2::from_array(&table_data)?, exact, correction, confidence_level)?)
+}
+
+#[pyfunction]
+pub fn cochrans_q_test(data: &PyAny) -> PyResult<TestResult> {
+    let data = match from_pyarray2(data) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    Ok(cochrans_q_test_rs(&data)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (*tables, correction = true, confidence_level = 0.95))]
+pub fn cochran_mantel_haenszel_test(tables: &PyTuple, correction: bool, confidence_level: f64) -> PyResult<TestResult> {
+    let mut strata = Vec::with_capacity(tables.len());
+    for table in tables.iter() {
+        let table_data = match from_pyarray2(table) {
+            Ok(data) => data,
+            Err(_e) => return Err(StatsError::Conversion.into()),
+        };
+        strata.push(Table2x2::from_array(&table_data)?);
+    }
+    Ok(cochran_mantel_haenszel_rs(&strata, correction, confidence_level)?)
+}
@@ -0,0 +1,398 @@
+// Nonparametric rank tests
+// mann_whitney_u_test -- two independent samples, shift in location
+// wilcoxon_signed_rank_test -- paired samples / one sample about zero
+// kruskal_wallis_test -- k independent samples, rank based one way anova
+// friedman_test -- k related samples measured over the same blocks
+// dunn_test -- pairwise post-hoc comparisons after a significant kruskal wallis
+
+use ndarray::{Array1, Array2};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::multiple_comparisons::{adjust_p_values_rs, Adjustment};
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::validate_statistical_input;
+use crate::utils::{from_pyarray1, from_pygroups, rank_rs, tie_sum_rs};
+
+
+/// Rust Native Computations
+
+// Which null distribution to use, "auto" picks exact only when it is valid and cheap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Auto,
+    Exact,
+    Asymptotic,
+}
+
+impl Method {
+    pub fn parse(method: &str) -> Result<Method, StatsError> {
+        match method {
+            "auto" => Ok(Method::Auto),
+            "exact" => Ok(Method::Exact),
+            "asymptotic" => Ok(Method::Asymptotic),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+// Number of ways to obtain every U in 0..=m*n for samples of size m and n (no ties).
+// The counts are the coefficients of the gaussian binomial [m + n choose m], built as the product
+// over k = 1..=m of (1 - q^(n + k)) / (1 - q^k), so only one row of m*n + 1 values is kept.
+pub fn mann_whitney_counts_rs(m: usize, n: usize) -> Vec<f64> {
+    let max_u = m * n;
+    let mut counts = vec![0.0; max_u + 1];
+    counts[0] = 1.0;
+    for k in 1..=m {
+        // multiply by 1 - q^(n + k), then divide by 1 - q^k
+        for u in (n + k..=max_u).rev() {
+            counts[u] -= counts[u - n - k];
+        }
+        for u in k..=max_u {
+            counts[u] += counts[u - k];
+        }
+    }
+    counts
+}
+
+// Number of subsets of {1..n} summing to every t in 0..=n(n+1)/2
+pub fn signed_rank_counts_rs(n: usize) -> Vec<f64> {
+    let max_t = n * (n + 1) / 2;
+    let mut counts = vec![0.0; max_t + 1];
+    counts[0] = 1.0;
+    for k in 1..=n {
+        for t in (k..=max_t).rev() {
+            counts[t] += counts[t - k];
+        }
+    }
+    counts
+}
+
+// p-value of an observed statistic under a discrete exact null given by `counts`
+pub fn exact_p_value_rs(counts: &[f64], statistic: f64, alternative: Alternative) -> f64 {
+    let total: f64 = counts.iter().sum();
+    // statistics on the exact path are integers, rounding protects against 3.9999
+    let s = statistic.round() as usize;
+    let lower: f64 = counts[..=s.min(counts.len() - 1)].iter().sum::<f64>() / total;
+    let upper: f64 = counts[s.min(counts.len())..].iter().sum::<f64>() / total;
+    match alternative {
+        Alternative::Less => lower,
+        Alternative::Greater => upper,
+        Alternative::TwoSided => (2.0 * lower.min(upper)).min(1.0),
+    }
+}
+
+// Normal approximation with a 0.5 continuity correction towards the mean
+pub fn continuity_z_rs(statistic: f64, mean: f64, sd: f64, alternative: Alternative) -> f64 {
+    let diff = statistic - mean;
+    let corrected = match alternative {
+        Alternative::Greater => diff - 0.5,
+        Alternative::Less => diff + 0.5,
+        Alternative::TwoSided => diff.signum() * (diff.abs() - 0.5).max(0.0),
+    };
+    corrected / sd
+}
+
+pub fn mann_whitney_u_rs(x: &Array1<f64>, y: &Array1<f64>,
+                         alternative: Alternative, method: Method) -> Result<(f64, f64), StatsError> {
+    let (n1, n2) = (x.len(), y.len());
+    let combined: Vec<f64> = x.iter().chain(y.iter()).cloned().collect();
+    let (ranks, ties) = rank_rs(&combined);
+    let r1: f64 = ranks.iter().take(n1).sum();
+    let u1 = r1 - (n1 * (n1 + 1)) as f64 / 2.0;
+
+    let use_exact = match method {
+        Method::Exact => {
+            if !ties.is_empty() { return Err(StatsError::InvalidInputValue); }
+            true
+        }
+        Method::Asymptotic => false,
+        Method::Auto => ties.is_empty() && n1 <= 8 && n2 <= 8,
+    };
+
+    if use_exact {
+        let counts = mann_whitney_counts_rs(n1, n2);
+        return Ok((u1, exact_p_value_rs(&counts, u1, alternative)));
+    }
+
+    let n = (n1 + n2) as f64;
+    let (n1f, n2f) = (n1 as f64, n2 as f64);
+    let mean_u = n1f * n2f / 2.0;
+    let var_u = n1f * n2f / 12.0 * ((n + 1.0) - tie_sum_rs(&ties) / (n * (n - 1.0)));
+    if var_u <= 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let z = continuity_z_rs(u1, mean_u, var_u.sqrt(), alternative);
+    Ok((u1, alternative.normal_p_value(z)))
+}
+
+// Returns (T+, p) where T+ is the sum of ranks of the positive differences
+pub fn wilcoxon_signed_rank_rs(d: &Array1<f64>, zero_method: &str,
+                               alternative: Alternative, method: Method) -> Result<(f64, f64), StatsError> {
+    let n_zero = d.iter().filter(|&&v| v == 0.0).count();
+    let diffs: Vec<f64> = match zero_method {
+        "wilcox" => d.iter().cloned().filter(|&v| v != 0.0).collect(),
+        "pratt" | "zsplit" => d.to_vec(),
+        _ => return Err(StatsError::InvalidInputValue),
+    };
+    if diffs.is_empty() { return Err(StatsError::EmptyDataSet); }
+
+    let abs_diffs: Vec<f64> = diffs.iter().map(|v| v.abs()).collect();
+    let (ranks, ties) = rank_rs(&abs_diffs);
+
+    let mut t_plus: f64 = diffs.iter().zip(ranks.iter())
+        .filter(|(&v, _)| v > 0.0)
+        .map(|(_, &r)| r)
+        .sum();
+    let zero_rank_sum: f64 = diffs.iter().zip(ranks.iter())
+        .filter(|(&v, _)| v == 0.0)
+        .map(|(_, &r)| r)
+        .sum();
+    if zero_method == "zsplit" {
+        t_plus += zero_rank_sum / 2.0;
+    }
+
+    let n = diffs.len();
+    let has_zeros = zero_method != "wilcox" && n_zero > 0;
+    let use_exact = match method {
+        Method::Exact => {
+            if !ties.is_empty() || has_zeros { return Err(StatsError::InvalidInputValue); }
+            true
+        }
+        Method::Asymptotic => false,
+        Method::Auto => ties.is_empty() && !has_zeros && n <= 50,
+    };
+
+    if use_exact {
+        let counts = signed_rank_counts_rs(n);
+        return Ok((t_plus, exact_p_value_rs(&counts, t_plus, alternative)));
+    }
+
+    let nf = n as f64;
+    let (mut mean_t, mut var_t) = (nf * (nf + 1.0) / 4.0, nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0);
+    if zero_method == "pratt" {
+        // zero differences are ranked but contribute to neither sum
+        let z = n_zero as f64;
+        mean_t -= z * (z + 1.0) / 4.0;
+        var_t -= z * (z + 1.0) * (2.0 * z + 1.0) / 24.0;
+    }
+    var_t -= tie_sum_rs(&ties) / 48.0;
+    if var_t <= 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let z = continuity_z_rs(t_plus, mean_t, var_t.sqrt(), alternative);
+    Ok((t_plus, alternative.normal_p_value(z)))
+}
+
+// Returns (H, p), H is tie corrected and referred to chi-square with k - 1 df
+pub fn kruskal_wallis_rs(groups: &[Array1<f64>]) -> Result<(f64, f64), StatsError> {
+    if groups.len() < 2 { return Err(StatsError::InsufficientGroups); }
+    let combined: Vec<f64> = groups.iter().flat_map(|g| g.iter().cloned()).collect();
+    let n = combined.len() as f64;
+    let (ranks, ties) = rank_rs(&combined);
+
+    let mut offset = 0;
+    let mut ssr = 0.0;
+    for group in groups {
+        let rank_sum: f64 = ranks.slice(ndarray::s![offset..offset + group.len()]).sum();
+        ssr += rank_sum * rank_sum / group.len() as f64;
+        offset += group.len();
+    }
+
+    let h = 12.0 / (n * (n + 1.0)) * ssr - 3.0 * (n + 1.0);
+    let correction = 1.0 - tie_sum_rs(&ties) / (n * n * n - n);
+    if correction <= 0.0 { return Err(StatsError::ZeroVariance); }
+    let h = h / correction;
+
+    let df = (groups.len() - 1) as f64;
+    let chi2 = ChiSquared::new(df).map_err(|_| StatsError::UnderlyingError)?;
+    Ok((h, chi2.sf(h)))
+}
+
+// Returns (Q, p). Each group is one treatment, element i of every group belongs to block i.
+pub fn friedman_rs(groups: &[Array1<f64>]) -> Result<(f64, f64), StatsError> {
+    let k = groups.len();
+    if k < 3 { return Err(StatsError::InsufficientGroups); }
+    let n = groups[0].len();
+    if groups.iter().any(|g| g.len() != n) { return Err(StatsError::InconsistentLength); }
+
+    let mut rank_sums = vec![0.0; k];
+    let mut tie_total = 0.0;
+    for block in 0..n {
+        let row: Vec<f64> = groups.iter().map(|g| g[block]).collect();
+        let (ranks, ties) = rank_rs(&row);
+        for (sum, r) in rank_sums.iter_mut().zip(ranks.iter()) {
+            *sum += r;
+        }
+        tie_total += tie_sum_rs(&ties);
+    }
+
+    let (nf, kf) = (n as f64, k as f64);
+    let ssr: f64 = rank_sums.iter().map(|r| r * r).sum();
+    let q = 12.0 / (nf * kf * (kf + 1.0)) * ssr - 3.0 * nf * (kf + 1.0);
+    let correction = 1.0 - tie_total / (nf * kf * (kf * kf - 1.0));
+    if correction <= 0.0 { return Err(StatsError::ZeroVariance); }
+    let q = q / correction;
+
+    let chi2 = ChiSquared::new(kf - 1.0).map_err(|_| StatsError::UnderlyingError)?;
+    Ok((q, chi2.sf(q)))
+}
+
+// Pairwise z statistics and two sided p-values on the pooled ranks, k x k matrices
+pub fn dunn_rs(groups: &[Array1<f64>], adjust: Adjustment) -> Result<(Array2<f64>, Array2<f64>), StatsError> {
+    let k = groups.len();
+    if k < 2 { return Err(StatsError::InsufficientGroups); }
+    let combined: Vec<f64> = groups.iter().flat_map(|g| g.iter().cloned()).collect();
+    let n = combined.len() as f64;
+    let (ranks, ties) = rank_rs(&combined);
+
+    let mut mean_ranks = Vec::with_capacity(k);
+    let mut offset = 0;
+    for group in groups {
+        mean_ranks.push(ranks.slice(ndarray::s![offset..offset + group.len()]).sum() / group.len() as f64);
+        offset += group.len();
+    }
+
+    let base_var = n * (n + 1.0) / 12.0 - tie_sum_rs(&ties) / (12.0 * (n - 1.0));
+    if base_var <= 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let mut z_scores = Array2::<f64>::zeros((k, k));
+    let mut pairs = Vec::with_capacity(k * (k - 1) / 2);
+    for i in 0..k {
+        for j in (i + 1)..k {
+            let se = (base_var * (1.0 / groups[i].len() as f64 + 1.0 / groups[j].len() as f64)).sqrt();
+            let z = (mean_ranks[i] - mean_ranks[j]) / se;
+            z_scores[[i, j]] = z;
+            z_scores[[j, i]] = -z;
+            pairs.push((i, j));
+        }
+    }
+
+    // the family being adjusted is the k(k-1)/2 pairwise comparisons
+    let raw: Array1<f64> = pairs.iter().map(|&(i, j)| Alternative::TwoSided.normal_p_value(z_scores[[i, j]])).collect();
+    let adjusted = adjust_p_values_rs(&raw, adjust)?;
+    let mut p_values = Array2::<f64>::ones((k, k));
+    for (&(i, j), &p) in pairs.iter().zip(adjusted.iter()) {
+        p_values[[i, j]] = p;
+        p_values[[j, i]] = p;
+    }
+    Ok((z_scores, p_values))
+}
+
+// One result per pair i < j, in row order, group_a and group_b are the positions of the two groups
+pub fn dunn_results_rs(groups: &[Array1<f64>], adjust: Adjustment) -> Result<Vec<TestResult>, StatsError> {
+    let (z_scores, p_values) = dunn_rs(groups, adjust)?;
+    let k = groups.len();
+    let mut results = Vec::with_capacity(k * (k - 1) / 2);
+    for i in 0..k {
+        for j in (i + 1)..k {
+            results.push(TestResult::new("Dunn's test", z_scores[[i, j]], p_values[[i, j]])
+                .with_extra("group_a", i as f64)
+                .with_extra("group_b", j as f64));
+        }
+    }
+    Ok(results)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alternative = "two-sided", method = "auto"))]
+pub fn mann_whitney_u_test(x: &PyAny, y: &PyAny, alternative: &str, method: &str) -> PyResult<TestResult> {
+    // The statistic U counts the pairs with x_i > y_j, ties counted as a half,
+    // the effect size is the rank-biserial correlation 2U / (n1 n2) - 1
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+
+    let alternative = Alternative::parse(alternative)?;
+    let method = Method::parse(method)?;
+    let (u, p) = mann_whitney_u_rs(&x_data, &y_data, alternative, method)?;
+    let pairs = (x_data.len() * y_data.len()) as f64;
+    Ok(TestResult::new("Mann-Whitney U test", u, p)
+        .with_alternative(alternative)
+        .with_effect_size(2.0 * u / pairs - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y = None, zero_method = "wilcox", alternative = "two-sided", method = "auto"))]
+pub fn wilcoxon_signed_rank_test(x: &PyAny, y: Option<&PyAny>, zero_method: &str,
+                                 alternative: &str, method: &str) -> PyResult<TestResult> {
+    // One sample about zero, or paired when y is given (tests x - y)
+    // The statistic T+ is the sum of ranks of the positive differences
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+
+    let diffs = match y {
+        Some(y) => {
+            let y_data = match from_pyarray1(y) {
+                Ok(data) => data,
+                Err(_e) => return Err(StatsError::Conversion.into()),
+            };
+            validate_statistical_input!(basic, y_data);
+            if x_data.len() != y_data.len() { return Err(StatsError::InconsistentLength.into()); }
+            &x_data - &y_data
+        }
+        None => x_data,
+    };
+
+    let alternative = Alternative::parse(alternative)?;
+    let method = Method::parse(method)?;
+    let (t, p) = wilcoxon_signed_rank_rs(&diffs, zero_method, alternative, method)?;
+    Ok(TestResult::new("Wilcoxon signed-rank test", t, p).with_alternative(alternative))
+}
+
+#[pyfunction]
+#[pyo3(signature = (*groups))]
+pub fn kruskal_wallis_test(groups: &PyTuple) -> PyResult<TestResult> {
+    let groups_data = match from_pygroups(groups) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    for group in &groups_data {
+        validate_statistical_input!(basic, group);
+    }
+    let (h, p) = kruskal_wallis_rs(&groups_data)?;
+    Ok(TestResult::new("Kruskal-Wallis H test", h, p).with_df(groups_data.len() as f64 - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (*groups))]
+pub fn friedman_test(groups: &PyTuple) -> PyResult<TestResult> {
+    // Each array is one treatment, the i-th entries of all arrays form block i
+    let groups_data = match from_pygroups(groups) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    for group in &groups_data {
+        validate_statistical_input!(basic, group);
+    }
+    let (q, p) = friedman_rs(&groups_data)?;
+    Ok(TestResult::new("Friedman test", q, p).with_df(groups_data.len() as f64 - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (*groups, adjust = "none"))]
+pub fn dunn_test(groups: &PyTuple, adjust: &str) -> PyResult<Vec<TestResult>> {
+    // One TestResult per pair of groups, group_a < group_b index the arguments
+    // adjust takes any of the adjust_p_values methods, the p-values are already adjusted
+    let groups_data = match from_pygroups(groups) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    for group in &groups_data {
+        validate_statistical_input!(basic, group);
+    }
+    Ok(dunn_results_rs(&groups_data, Adjustment::parse(adjust, 0.5)?)?)
+}
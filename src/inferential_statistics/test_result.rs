@@ -0,0 +1,185 @@
+// Common result type returned by the hypothesis tests
+// Only statistic, p_value, alternative and method are always set, everything else depends on the test.
+// Test specific values (critical values, one-sided statistics, ...) go in extras and read as attributes.
+
+// pyo3 0.20 expands __getattr__ into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyAttributeError;
+use pyo3::types::PyDict;
+
+use crate::inferential_statistics::Alternative;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Extra {
+    Scalar(f64),
+    Values(Vec<f64>),
+    Flag(bool),
+}
+
+impl From<f64> for Extra {
+    fn from(value: f64) -> Extra {
+        Extra::Scalar(value)
+    }
+}
+
+impl From<Vec<f64>> for Extra {
+    fn from(values: Vec<f64>) -> Extra {
+        Extra::Values(values)
+    }
+}
+
+impl From<bool> for Extra {
+    fn from(flag: bool) -> Extra {
+        Extra::Flag(flag)
+    }
+}
+
+impl ToPyObject for Extra {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        match self {
+            Extra::Scalar(value) => value.to_object(py),
+            Extra::Values(values) => values.to_object(py),
+            Extra::Flag(flag) => flag.to_object(py),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    #[pyo3(get)]
+    pub statistic: f64,
+    #[pyo3(get)]
+    pub p_value: f64,
+    #[pyo3(get)]
+    pub df: Option<f64>,
+    #[pyo3(get)]
+    pub alternative: String,
+    #[pyo3(get)]
+    pub method: String,
+    #[pyo3(get)]
+    pub estimate: Option<f64>,
+    #[pyo3(get)]
+    pub confidence_interval: Option<(f64, f64)>,
+    #[pyo3(get)]
+    pub effect_size: Option<f64>,
+    pub extras: Vec<(String, Extra)>,
+}
+
+impl TestResult {
+    pub fn new(method: &str, statistic: f64, p_value: f64) -> TestResult {
+        TestResult {
+            statistic,
+            p_value,
+            df: None,
+            alternative: Alternative::TwoSided.as_str().to_string(),
+            method: method.to_string(),
+            estimate: None,
+            confidence_interval: None,
+            effect_size: None,
+            extras: vec![],
+        }
+    }
+
+    pub fn with_df(mut self, df: f64) -> TestResult {
+        self.df = Some(df);
+        self
+    }
+
+    pub fn with_alternative(mut self, alternative: Alternative) -> TestResult {
+        self.alternative = alternative.as_str().to_string();
+        self
+    }
+
+    pub fn with_estimate(mut self, estimate: f64) -> TestResult {
+        self.estimate = Some(estimate);
+        self
+    }
+
+    pub fn with_confidence_interval(mut self, interval: (f64, f64)) -> TestResult {
+        self.confidence_interval = Some(interval);
+        self
+    }
+
+    pub fn with_effect_size(mut self, effect_size: f64) -> TestResult {
+        self.effect_size = Some(effect_size);
+        self
+    }
+
+    pub fn with_extra(mut self, name: &str, value: impl Into<Extra>) -> TestResult {
+        self.extras.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn extra(&self, name: &str) -> Option<&Extra> {
+        self.extras.iter().find(|(key, _)| key == name).map(|(_, value)| value)
+    }
+}
+
+
+/// Pymethods
+
+#[pymethods]
+impl TestResult {
+    fn __repr__(&self) -> String {
+        let mut fields = vec![
+            format!("method='{}'", self.method),
+            format!("statistic={}", self.statistic),
+            format!("p_value={}", self.p_value),
+        ];
+        if let Some(df) = self.df { fields.push(format!("df={}", df)); }
+        fields.push(format!("alternative='{}'", self.alternative));
+        if let Some(estimate) = self.estimate { fields.push(format!("estimate={}", estimate)); }
+        if let Some((lo, hi)) = self.confidence_interval {
+            fields.push(format!("confidence_interval=({}, {})", lo, hi));
+        }
+        if let Some(effect_size) = self.effect_size { fields.push(format!("effect_size={}", effect_size)); }
+        for (name, value) in &self.extras {
+            match value {
+                Extra::Scalar(v) => fields.push(format!("{}={}", name, v)),
+                Extra::Values(values) => fields.push(format!("{}={:?}", name, values)),
+                Extra::Flag(flag) => fields.push(format!("{}={}", name, if *flag { "True" } else { "False" })),
+            }
+        }
+        format!("TestResult({})", fields.join(", "))
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        // Every field is present, unset ones as None
+        let dict = PyDict::new(py);
+        dict.set_item("statistic", self.statistic)?;
+        dict.set_item("p_value", self.p_value)?;
+        dict.set_item("df", self.df)?;
+        dict.set_item("alternative", &self.alternative)?;
+        dict.set_item("method", &self.method)?;
+        dict.set_item("estimate", self.estimate)?;
+        dict.set_item("confidence_interval", self.confidence_interval)?;
+        dict.set_item("effect_size", self.effect_size)?;
+        for (name, value) in &self.extras {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+
+    #[getter]
+    fn extras<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        for (name, value) in &self.extras {
+            dict.set_item(name, value)?;
+        }
+        Ok(dict)
+    }
+
+    fn __getattr__(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        // only reached when name is not a regular field
+        match self.extra(name) {
+            Some(value) => Ok(value.to_object(py)),
+            None => Err(PyAttributeError::new_err(format!("'TestResult' object has no attribute '{}'", name))),
+        }
+    }
+}
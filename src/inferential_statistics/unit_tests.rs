@@ -0,0 +1,943 @@
+// Unit Tests
+use crate::inferential_statistics::*;
+use crate::utils::rank_rs;
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{array, Array1};
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "expected {}, got {}", expected, actual);
+    }
+
+    mod rank_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::rank_tests::*;
+        use crate::inferential_statistics::multiple_comparisons::Adjustment;
+        use crate::inferential_statistics::test_result::Extra;
+
+        #[test]
+        fn test_rank_with_ties() {
+            let (ranks, ties) = rank_rs(&[3.0, 1.0, 4.0, 1.0, 5.0]);
+            assert_eq!(ranks, array![3.0, 1.5, 4.0, 1.5, 5.0]);
+            assert_eq!(ties, vec![2]);
+        }
+
+        #[test]
+        fn test_mann_whitney_counts() {
+            assert_eq!(mann_whitney_counts_rs(2, 2), vec![1.0, 1.0, 2.0, 1.0, 1.0]);
+            let counts = mann_whitney_counts_rs(3, 4);
+            assert_eq!(counts.iter().sum::<f64>(), 35.0);
+            assert_eq!(counts, mann_whitney_counts_rs(4, 3));
+            assert_eq!(counts[..4].to_vec(), vec![1.0, 1.0, 2.0, 3.0]);
+        }
+
+        #[test]
+        fn test_continuity_z_at_mean() {
+            assert_eq!(continuity_z_rs(5.0, 5.0, 2.0, Alternative::TwoSided), 0.0);
+            assert_eq!(continuity_z_rs(5.25, 5.0, 2.0, Alternative::TwoSided), 0.0);
+            assert_close(continuity_z_rs(4.0, 5.0, 2.0, Alternative::TwoSided), -0.25, 1e-12);
+        }
+
+        #[test]
+        fn test_mann_whitney_auto_needs_both_samples_small() {
+            let x = array![1.5, 7.5, 12.5];
+            let y: Array1<f64> = (0..40).map(f64::from).collect();
+            let auto = mann_whitney_u_rs(&x, &y, Alternative::TwoSided, Method::Auto).unwrap();
+            let asymptotic = mann_whitney_u_rs(&x, &y, Alternative::TwoSided, Method::Asymptotic).unwrap();
+            assert_eq!(auto, asymptotic);
+        }
+
+        #[test]
+        fn test_signed_rank_counts() {
+            assert_eq!(signed_rank_counts_rs(3), vec![1.0, 1.0, 1.0, 2.0, 1.0, 1.0, 1.0]);
+        }
+
+        #[test]
+        fn test_mann_whitney_exact_separated_samples() {
+            let x = array![1.0, 2.0, 3.0];
+            let y = array![4.0, 5.0, 6.0];
+            let (u, p) = mann_whitney_u_rs(&x, &y, Alternative::TwoSided, Method::Auto).unwrap();
+            assert_eq!(u, 0.0);
+            assert_close(p, 0.1, 1e-12);
+            let (_, p_less) = mann_whitney_u_rs(&x, &y, Alternative::Less, Method::Auto).unwrap();
+            assert_close(p_less, 0.05, 1e-12);
+        }
+
+        #[test]
+        fn test_mann_whitney_exact_rejects_ties() {
+            let x = array![1.0, 2.0, 2.0];
+            let y = array![2.0, 5.0, 6.0];
+            assert_eq!(mann_whitney_u_rs(&x, &y, Alternative::TwoSided, Method::Exact),
+                       Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_wilcoxon_exact_all_positive() {
+            let d = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let (t, p) = wilcoxon_signed_rank_rs(&d, "wilcox", Alternative::TwoSided, Method::Auto).unwrap();
+            assert_eq!(t, 15.0);
+            assert_close(p, 0.0625, 1e-12);
+        }
+
+        #[test]
+        fn test_wilcoxon_invalid_zero_method() {
+            let d = array![1.0, 2.0];
+            assert_eq!(wilcoxon_signed_rank_rs(&d, "nope", Alternative::TwoSided, Method::Auto),
+                       Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_kruskal_wallis_two_groups() {
+            let groups = vec![array![1.0, 2.0, 3.0], array![4.0, 5.0, 6.0]];
+            let (h, p) = kruskal_wallis_rs(&groups).unwrap();
+            assert_close(h, 27.0 / 7.0, 1e-12);
+            assert_close(p, 0.049535, 1e-5);
+        }
+
+        #[test]
+        fn test_friedman_consistent_blocks() {
+            let groups = vec![array![1.0, 1.0], array![2.0, 2.0], array![3.0, 3.0]];
+            let (q, p) = friedman_rs(&groups).unwrap();
+            assert_close(q, 4.0, 1e-12);
+            assert_close(p, (-2.0f64).exp(), 1e-10);
+        }
+
+        #[test]
+        fn test_friedman_needs_three_groups() {
+            let groups = vec![array![1.0, 2.0], array![2.0, 3.0]];
+            assert_eq!(friedman_rs(&groups), Err(StatsError::InsufficientGroups));
+        }
+
+        #[test]
+        fn test_dunn_symmetric() {
+            let groups = vec![array![1.0, 2.0, 3.0], array![4.0, 5.0, 6.0], array![7.0, 8.0, 9.0]];
+            let (z, p) = dunn_rs(&groups, Adjustment::Bonferroni).unwrap();
+            assert_close(z[[0, 1]], -z[[1, 0]], 1e-12);
+            assert_eq!(p[[0, 2]], p[[2, 0]]);
+            assert!(p[[0, 2]] < p[[0, 1]]);
+        }
+
+        #[test]
+        fn test_dunn_results_per_pair() {
+            let groups = vec![array![1.0, 2.0, 3.0], array![4.0, 5.0, 6.0], array![7.0, 8.0, 9.0]];
+            let (z, p) = dunn_rs(&groups, Adjustment::Holm).unwrap();
+            let results = dunn_results_rs(&groups, Adjustment::Holm).unwrap();
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[1].extra("group_a"), Some(&Extra::Scalar(0.0)));
+            assert_eq!(results[1].extra("group_b"), Some(&Extra::Scalar(2.0)));
+            assert_eq!((results[1].statistic, results[1].p_value), (z[[0, 2]], p[[0, 2]]));
+        }
+    }
+
+    mod contingency_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::contingency_tests::*;
+
+        // fisher's lady tasting tea
+        const TEA: Table2x2 = Table2x2 { a: 3, b: 1, c: 1, d: 3 };
+
+        #[test]
+        fn test_table_rejects_wrong_shape() {
+            let table = ndarray::Array2::<f64>::zeros((2, 3));
+            assert_eq!(Table2x2::from_array(&table), Err(StatsError::InvalidShape));
+        }
+
+        #[test]
+        fn test_table_rejects_fractional_counts() {
+            let table = array![[1.5, 2.0], [3.0, 4.0]];
+            assert_eq!(Table2x2::from_array(&table), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_fisher_tea_tasting() {
+            let (odds_ratio, p, (lower, upper)) = fisher_exact_rs(&TEA, Alternative::TwoSided, 0.95).unwrap();
+            assert_close(p, 0.4857143, 1e-6);
+            // R reports 6.408309 and 621.93 because of uniroot's loose tolerance, these are the exact roots
+            assert_close(odds_ratio, 6.4083197, 1e-6);
+            assert_close(lower, 0.2117329, 1e-5);
+            assert_close(upper, 626.2435306, 1e-6);
+        }
+
+        #[test]
+        fn test_fisher_one_sided() {
+            let (_, p, (lower, upper)) = fisher_exact_rs(&TEA, Alternative::Greater, 0.95).unwrap();
+            assert_close(p, 0.2428571, 1e-6);
+            assert_close(lower, 0.3135693, 1e-5);
+            assert_eq!(upper, f64::INFINITY);
+        }
+
+        #[test]
+        fn test_fisher_large_counts_do_not_overflow() {
+            let table = Table2x2 { a: 5000, b: 4000, c: 4000, d: 5000 };
+            let (odds_ratio, p, _) = fisher_exact_rs(&table, Alternative::TwoSided, 0.95).unwrap();
+            assert!(p.is_finite() && p < 1e-10);
+            assert_close(odds_ratio, 1.5625, 1e-2);
+        }
+
+        #[test]
+        fn test_fisher_boundary_odds_ratio() {
+            let table = Table2x2 { a: 0, b: 5, c: 5, d: 0 };
+            let (odds_ratio, _, (lower, _)) = fisher_exact_rs(&table, Alternative::TwoSided, 0.95).unwrap();
+            assert_eq!(odds_ratio, 0.0);
+            assert_eq!(lower, 0.0);
+            // an empty row leaves a single possible table
+            for table in [Table2x2 { a: 0, b: 0, c: 3, d: 4 }, Table2x2 { a: 2, b: 0, c: 3, d: 0 }] {
+                assert!(fisher_exact_rs(&table, Alternative::TwoSided, 0.95).unwrap().0.is_nan());
+            }
+        }
+
+        #[test]
+        fn test_barnard_more_powerful_than_fisher() {
+            let (statistic, p) = barnard_exact_rs(&TEA, Alternative::TwoSided, true, 32).unwrap();
+            assert_close(statistic, std::f64::consts::SQRT_2, 1e-12);
+            assert!(p > 0.0 && p < 0.4857143);
+        }
+
+        #[test]
+        fn test_boschloo_one_sided() {
+            let table = Table2x2 { a: 74, b: 31, c: 43, d: 32 };
+            let (statistic, p) = boschloo_exact_rs(&table, Alternative::Greater, 32).unwrap();
+            assert_close(statistic, 0.0483, 1e-4);
+            assert_close(p, 0.0355, 1e-4);
+        }
+    }
+
+    mod power_analysis_tests {
+        use super::*;
+        use crate::distributions::noncentral::*;
+        use crate::inferential_statistics::power_analysis::*;
+
+        fn design(test: PowerTest) -> PowerDesign {
+            PowerDesign { test, alternative: Alternative::TwoSided, ratio: 1.0, k_groups: 3, df: 1.0 }
+        }
+
+        #[test]
+        fn test_noncentral_reduces_to_central() {
+            assert_close(noncentral_t_cdf(0.0, 10.0, 0.0), 0.5, 1e-12);
+            assert_close(noncentral_chi_squared_cdf(3.841459, 1.0, 0.0), 0.95, 1e-6);
+            assert_close(noncentral_f_cdf(1.0, 5.0, 5.0, 0.0), 0.5, 1e-12);
+        }
+
+        #[test]
+        fn test_noncentral_t_symmetry() {
+            let lower = noncentral_t_cdf(-1.5, 12.0, 0.7);
+            let upper = noncentral_t_cdf(1.5, 12.0, -0.7);
+            assert_close(lower, 1.0 - upper, 1e-12);
+        }
+
+        #[test]
+        fn test_two_sample_power() {
+            let power = solve_power_rs(&design(PowerTest::TwoSample), Some(0.5), Some(64.0), Some(0.05), None).unwrap();
+            assert_close(power, 0.8014596, 1e-6);
+        }
+
+        #[test]
+        fn test_two_sample_sample_size() {
+            let n = solve_power_rs(&design(PowerTest::TwoSample), Some(0.5), None, Some(0.05), Some(0.8)).unwrap();
+            assert_close(n, 63.76561, 1e-4);
+        }
+
+        #[test]
+        fn test_one_sample_sample_size() {
+            let n = solve_power_rs(&design(PowerTest::OneSample), Some(0.5), None, Some(0.05), Some(0.8)).unwrap();
+            assert_close(n, 33.36713, 1e-4);
+        }
+
+        #[test]
+        fn test_anova_sample_size() {
+            let n = solve_power_rs(&design(PowerTest::Anova), Some(0.25), None, Some(0.05), Some(0.8)).unwrap();
+            assert_close(n, 157.1897, 1e-3);
+        }
+
+        #[test]
+        fn test_chi_square_sample_size() {
+            let n = solve_power_rs(&design(PowerTest::ChiSquare), Some(0.3), None, Some(0.05), Some(0.8)).unwrap();
+            assert_close(n, 87.20954, 1e-3);
+        }
+
+        #[test]
+        fn test_effect_size_round_trip() {
+            let d = design(PowerTest::TwoProportion);
+            let es = solve_power_rs(&d, None, Some(100.0), Some(0.05), Some(0.9)).unwrap();
+            let power = solve_power_rs(&d, Some(es), Some(100.0), Some(0.05), None).unwrap();
+            assert_close(power, 0.9, 1e-8);
+        }
+
+        #[test]
+        fn test_alpha_round_trip() {
+            let d = design(PowerTest::Paired);
+            let alpha = solve_power_rs(&d, Some(0.4), Some(40.0), None, Some(0.7)).unwrap();
+            let power = solve_power_rs(&d, Some(0.4), Some(40.0), Some(alpha), None).unwrap();
+            assert_close(power, 0.7, 1e-8);
+        }
+
+        #[test]
+        fn test_requires_exactly_one_unknown() {
+            let d = design(PowerTest::OneSample);
+            assert_eq!(solve_power_rs(&d, None, None, Some(0.05), Some(0.8)), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_power_curve_shape_and_monotone() {
+            let curve = power_curve_rs(&design(PowerTest::TwoSample), &[0.2, 0.5], &[10.0, 50.0, 100.0], 0.05).unwrap();
+            assert_eq!(curve.shape(), &[2, 3]);
+            assert!(curve[[0, 0]] < curve[[0, 2]]);
+            assert!(curve[[0, 1]] < curve[[1, 1]]);
+        }
+    }
+
+    mod effect_size_tests {
+        use super::*;
+        use crate::distributions::noncentral::noncentral_t_cdf;
+        use crate::inferential_statistics::contingency_tests::Table2x2;
+        use crate::inferential_statistics::effect_size::*;
+
+        #[test]
+        fn test_cohens_d_pooled() {
+            let x = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let y = array![3.0, 4.0, 5.0, 6.0, 7.0];
+            let (d, (lo, hi)) = cohens_d_rs(&x, &y, CohensD::Pooled, 0.95).unwrap();
+            assert_close(d, -2.0 / 2.5f64.sqrt(), 1e-12);
+            // the bounds are the noncentralities placing t at the 97.5% and 2.5% quantiles
+            let scale = (0.4f64).sqrt();
+            assert_close(noncentral_t_cdf(d / scale, 8.0, lo / scale), 0.975, 1e-8);
+            assert_close(noncentral_t_cdf(d / scale, 8.0, hi / scale), 0.025, 1e-8);
+        }
+
+        #[test]
+        fn test_cohens_d_paired_needs_equal_lengths() {
+            let x = array![1.0, 2.0, 3.0];
+            let y = array![1.0, 2.0];
+            assert_eq!(cohens_d_rs(&x, &y, CohensD::Rm, 0.95), Err(StatsError::InconsistentLength));
+        }
+
+        #[test]
+        fn test_hedges_correction() {
+            assert_close(hedges_correction_rs(8.0), 0.9027033, 1e-6);
+        }
+
+        #[test]
+        fn test_anova_effect_sizes() {
+            let sizes = anova_effect_sizes_rs(20.0, 80.0, 2.0, 27.0, None, 0.95).unwrap();
+            assert_close(sizes.eta_squared, 0.2, 1e-12);
+            assert_close(sizes.partial_eta_squared, 0.2, 1e-12);
+            assert_close(sizes.omega_squared, (20.0 - 160.0 / 27.0) / (100.0 + 80.0 / 27.0), 1e-12);
+            assert!(sizes.interval.0 < sizes.partial_eta_squared && sizes.partial_eta_squared < sizes.interval.1);
+        }
+
+        #[test]
+        fn test_cliffs_delta_matches_rank_biserial() {
+            let x = array![1.0, 4.0, 2.0, 8.0, 5.0];
+            let y = array![3.0, 6.0, 7.0, 9.0];
+            let (delta, (lo, hi)) = cliffs_delta_rs(&x, &y, 0.95, EffectInterval::Asymptotic).unwrap();
+            let (r, _) = rank_biserial_rs(&x, Some(&y), false, 0.95, EffectInterval::Asymptotic).unwrap();
+            assert_close(delta, r, 1e-12);
+            assert!(lo < delta && delta < hi);
+        }
+
+        #[test]
+        fn test_rank_biserial_paired_all_positive() {
+            let x = array![2.0, 3.0, 5.0, 9.0];
+            let y = array![1.0, 1.0, 1.0, 1.0];
+            let (r, _) = rank_biserial_rs(&x, Some(&y), true, 0.95, EffectInterval::Asymptotic).unwrap();
+            assert_close(r, 1.0, 1e-12);
+        }
+
+        #[test]
+        fn test_odds_and_risk_ratio() {
+            let table = Table2x2 { a: 3, b: 1, c: 1, d: 3 };
+            let (or, (lo, hi)) = odds_ratio_rs(&table, 0.95, EffectInterval::Asymptotic).unwrap();
+            assert_close(or, 9.0, 1e-12);
+            let se = (8.0f64 / 3.0).sqrt();
+            assert_close(lo, 9.0 * (-1.959964 * se).exp(), 1e-5);
+            assert_close(hi, 9.0 * (1.959964 * se).exp(), 1e-3);
+            let (rr, _) = risk_ratio_rs(&table, 0.95, EffectInterval::Asymptotic).unwrap();
+            assert_close(rr, 3.0, 1e-12);
+        }
+
+        #[test]
+        fn test_glass_delta_noncentral_interval() {
+            let x = array![4.0, 6.0, 5.0, 7.0, 8.0];
+            let y = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let (delta, (lo, hi)) = glass_delta_rs(&x, &y, 0.95).unwrap();
+            assert_close(delta, 3.0 / 2.5f64.sqrt(), 1e-12);
+            // on the control group's 4 df
+            let scale = (0.4f64).sqrt();
+            assert_close(noncentral_t_cdf(delta / scale, 4.0, lo / scale), 0.975, 1e-8);
+            assert_close(noncentral_t_cdf(delta / scale, 4.0, hi / scale), 0.025, 1e-8);
+        }
+
+        #[test]
+        fn test_bootstrap_effect_intervals() {
+            let x = array![1.0, 4.0, 2.0, 8.0, 5.0, 7.5, 6.0, 3.5];
+            let y = array![3.0, 6.0, 7.0, 9.0, 10.0, 8.5, 4.5];
+            let bootstrap = EffectInterval::Bootstrap { n_resamples: 2000, seed: 11 };
+            let (delta, (lo, hi)) = cliffs_delta_rs(&x, &y, 0.95, bootstrap).unwrap();
+            assert_eq!(delta, cliffs_delta_rs(&x, &y, 0.95, EffectInterval::Asymptotic).unwrap().0);
+            assert!(lo < delta && delta < hi && lo >= -1.0 && hi <= 1.0);
+            assert_eq!(cliffs_delta_rs(&x, &y, 0.95, bootstrap).unwrap(), (delta, (lo, hi)));
+
+            let (r, (lo, hi)) = rank_biserial_rs(&x, Some(&y), false, 0.9, bootstrap).unwrap();
+            assert_close(r, delta, 1e-12);
+            assert!(lo < r && r < hi);
+            let paired = rank_biserial_rs(&x.slice(ndarray::s![..7]).to_owned(), Some(&y), true, 0.9, bootstrap).unwrap();
+            assert!(paired.1 .0 <= paired.0 && paired.0 <= paired.1 .1);
+
+            let table = Table2x2 { a: 12, b: 5, c: 8, d: 15 };
+            let (or, (lo, hi)) = odds_ratio_rs(&table, 0.95, bootstrap).unwrap();
+            assert_close(or, 4.5, 1e-12);
+            assert!(lo < or && or < hi);
+            let (rr, (lo, hi)) = risk_ratio_rs(&table, 0.95, bootstrap).unwrap();
+            assert_close(rr, 0.6 / 0.25, 1e-12);
+            assert!(lo < rr && rr < hi);
+            assert_eq!(EffectInterval::parse("bootstrap", 0, None), Err(StatsError::InvalidInputValue));
+        }
+    }
+
+    mod normality_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::normality_tests::*;
+        use ndarray::Array1;
+        use statrs::distribution::{ContinuousCDF, Normal};
+
+        // expected normal order statistics, as close to normal as a sample gets
+        fn normal_quantiles(n: usize) -> Array1<f64> {
+            let normal = Normal::new(10.0, 2.0).unwrap();
+            (1..=n).map(|i| normal.inverse_cdf((i as f64 - 0.5) / n as f64)).collect()
+        }
+
+        fn doubling(n: usize) -> Array1<f64> {
+            (0..n).map(|i| 2f64.powi(i as i32)).collect()
+        }
+
+        #[test]
+        fn test_shapiro_wilk_three_points() {
+            let (w, p) = shapiro_wilk_rs(&array![1.0, 2.0, 4.0]).unwrap();
+            assert_close(w, 4.5 / (42.0 / 9.0), 1e-12);
+            let expected_p = 6.0 / std::f64::consts::PI * (w.sqrt().asin() - std::f64::consts::FRAC_PI_3);
+            assert_close(p, expected_p, 1e-12);
+        }
+
+        #[test]
+        fn test_shapiro_wilk_normal_and_skewed() {
+            let (w, p) = shapiro_wilk_rs(&normal_quantiles(30)).unwrap();
+            assert!(w > 0.98 && p > 0.5);
+            let (_, p_small) = shapiro_wilk_rs(&normal_quantiles(8)).unwrap();
+            assert!(p_small > 0.5);
+            let (_, p_skewed) = shapiro_wilk_rs(&doubling(15)).unwrap();
+            assert!(p_skewed < 0.001);
+        }
+
+        #[test]
+        fn test_jarque_bera() {
+            let (jb, p) = jarque_bera_rs(&array![1.0, 2.0, 3.0, 4.0, 10.0]).unwrap();
+            assert_close(jb, 1.0893633, 1e-6);
+            assert_close(p, 0.5800264, 1e-6);
+        }
+
+        #[test]
+        fn test_dagostino_pearson_symmetric() {
+            let data = normal_quantiles(25);
+            assert_close(skewness_z_rs(&data), 0.0, 1e-8);
+            let (_, p) = dagostino_pearson_rs(&data).unwrap();
+            assert!(p > 0.5);
+            assert_eq!(dagostino_pearson_rs(&array![1.0, 2.0, 3.0]), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_anderson_darling_normal() {
+            let (a2, critical, significance, p) = anderson_darling_rs(&normal_quantiles(20), AndersonDistribution::Normal).unwrap();
+            assert_eq!(critical[0], 0.506);
+            assert_eq!(significance, vec![15.0, 10.0, 5.0, 2.5, 1.0]);
+            assert!(a2 < critical[0]);
+            assert!(p.unwrap() > 0.5);
+        }
+
+        #[test]
+        fn test_anderson_darling_other_distributions() {
+            let data = doubling(12);
+            for dist in [AndersonDistribution::Exponential, AndersonDistribution::Logistic, AndersonDistribution::Gumbel] {
+                let (a2, critical, significance, p) = anderson_darling_rs(&data, dist).unwrap();
+                assert!(a2.is_finite() && a2 > 0.0);
+                assert_eq!(critical.len(), significance.len());
+                assert!(p.is_none());
+            }
+        }
+
+        #[test]
+        fn test_lilliefors_and_cramer_von_mises() {
+            let (_, p) = lilliefors_rs(&normal_quantiles(40)).unwrap();
+            assert!(p > 0.2);
+            let (_, p) = cramer_von_mises_rs(&normal_quantiles(40)).unwrap();
+            assert!(p > 0.2);
+            let (_, p) = lilliefors_rs(&doubling(20)).unwrap();
+            assert!(p < 0.01);
+            let (_, p) = cramer_von_mises_rs(&doubling(20)).unwrap();
+            assert!(p < 0.01);
+        }
+    }
+
+    mod variance_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::variance_tests::*;
+        use crate::descriptive_statistics::sample_variance_rs;
+
+        fn groups() -> Vec<Array1<f64>> {
+            vec![
+                array![8.88, 9.12, 9.04, 8.98, 9.00, 9.08, 9.01, 8.85, 9.06, 8.99],
+                array![8.88, 8.95, 9.29, 9.44, 9.15, 9.58, 8.36, 9.18, 8.67, 9.05],
+                array![8.95, 9.12, 8.95, 8.85, 9.03, 8.84, 9.07, 8.98, 8.86, 8.98],
+            ]
+        }
+
+        #[test]
+        fn test_levene_brown_forsythe() {
+            let (w, p) = levene_rs(&groups(), Center::Median).unwrap();
+            assert_close(w, 7.584952754501659, 1e-9);
+            assert_close(p, 0.002431505967249681, 1e-9);
+            assert!(levene_rs(&groups(), Center::Mean).unwrap().0 > 0.0);
+            assert_eq!(Center::parse("trimmed", 0.6), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_levene_trimmed_centres_on_trimmed_groups() {
+            // trimming once, then the mean-centred test on the trimmed samples
+            let trimmed: Vec<Array1<f64>> = groups().iter().map(|g| crate::descriptive_statistics::trim_rs(g, 0.2)).collect();
+            let expected = levene_rs(&trimmed, Center::Mean).unwrap();
+            let (w, p) = levene_rs(&groups(), Center::Trimmed(0.2)).unwrap();
+            assert_close(w, expected.0, 1e-12);
+            assert_close(p, expected.1, 1e-12);
+        }
+
+        #[test]
+        fn test_bartlett() {
+            let (t, p) = bartlett_rs(&groups()).unwrap();
+            assert_close(t, 22.789434813726768, 1e-9);
+            assert_close(p, 1.1254782518834628e-05, 1e-12);
+            assert_eq!(bartlett_rs(&groups()[..1]), Err(StatsError::InsufficientGroups));
+        }
+
+        #[test]
+        fn test_fligner_killeen() {
+            let (x2, p) = fligner_killeen_rs(&groups(), Center::Median).unwrap();
+            assert_close(x2, 10.80, 1e-2);
+            assert_close(p, 0.0045, 1e-4);
+        }
+
+        #[test]
+        fn test_fligner_killeen_trimmed_ranks_trimmed_groups() {
+            let trimmed: Vec<Array1<f64>> = groups().iter().map(|g| crate::descriptive_statistics::trim_rs(g, 0.2)).collect();
+            let expected = fligner_killeen_rs(&trimmed, Center::Mean).unwrap();
+            let (x2, p) = fligner_killeen_rs(&groups(), Center::Trimmed(0.2)).unwrap();
+            assert_close(x2, expected.0, 1e-12);
+            assert_close(p, expected.1, 1e-12);
+        }
+
+        #[test]
+        fn test_f_test() {
+            let g = groups();
+            let (f, p_two) = f_test_rs(&g[0], &g[1], Alternative::TwoSided).unwrap();
+            let (_, p_less) = f_test_rs(&g[0], &g[1], Alternative::Less).unwrap();
+            assert_close(f, sample_variance_rs(&g[0]) / sample_variance_rs(&g[1]), 1e-12);
+            assert!(f < 1.0);
+            assert_close(p_two, 2.0 * p_less, 1e-12);
+        }
+    }
+
+    mod test_result_tests {
+        use super::*;
+
+        #[test]
+        fn test_builder_fills_optional_fields() {
+            let result = TestResult::new("F test", 2.5, 0.04)
+                .with_df(3.0)
+                .with_alternative(Alternative::Greater)
+                .with_estimate(2.5)
+                .with_confidence_interval((1.1, 4.0));
+            assert_eq!(result.alternative, "greater");
+            assert_eq!(result.df, Some(3.0));
+            assert_eq!(result.confidence_interval, Some((1.1, 4.0)));
+            assert_eq!(result.effect_size, None);
+            assert_eq!(TestResult::new("t", 0.0, 1.0).alternative, "two-sided");
+        }
+
+        #[test]
+        fn test_extras_are_kept_by_name() {
+            use crate::inferential_statistics::test_result::Extra;
+            let result = TestResult::new("Anderson-Darling test", 0.3, f64::NAN)
+                .with_extra("critical_values", vec![0.5, 0.6])
+                .with_extra("lower_p_value", 0.01);
+            assert_eq!(result.extra("critical_values"), Some(&Extra::Values(vec![0.5, 0.6])));
+            assert_eq!(result.extra("lower_p_value"), Some(&Extra::Scalar(0.01)));
+            assert_eq!(result.extra("missing"), None);
+        }
+
+        #[test]
+        fn test_kolmogorov_smirnov_p_value() {
+            // scipy.stats.kstwobign.sf(1.0) = 0.26999967
+            let p = kolmogorov_smirnov_p_value_rs(1.0 / (50.0_f64.sqrt() + 0.12 + 0.11 / 50.0_f64.sqrt()), 100, 100);
+            assert_close(p, 0.26999967, 1e-7);
+            assert_eq!(kolmogorov_smirnov_p_value_rs(0.0, 10, 10), 1.0);
+        }
+    }
+
+    mod multiple_comparisons_tests {
+        use super::*;
+        use crate::inferential_statistics::multiple_comparisons::*;
+
+        fn p_values() -> Array1<f64> {
+            array![0.01, 0.04, 0.03, 0.005, 0.2]
+        }
+
+        fn assert_all_close(actual: &Array1<f64>, expected: &[f64], tol: f64) {
+            for (a, e) in actual.iter().zip(expected.iter()) {
+                assert_close(*a, *e, tol);
+            }
+        }
+
+        #[test]
+        fn test_family_wise_adjustments() {
+            let p = p_values();
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::Bonferroni).unwrap(), &[0.05, 0.2, 0.15, 0.025, 1.0], 1e-12);
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::Holm).unwrap(), &[0.04, 0.09, 0.09, 0.025, 0.2], 1e-12);
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::Hochberg).unwrap(), &[0.04, 0.08, 0.08, 0.025, 0.2], 1e-12);
+            // closed testing with Simes' test, computed by brute force over all intersections
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::Hommel).unwrap(), &[0.04, 0.08, 0.06, 0.025, 0.2], 1e-12);
+            let equal = array![0.01, 0.02, 0.03, 0.04, 0.05];
+            assert_all_close(&adjust_p_values_rs(&equal, Adjustment::Hommel).unwrap(), &[0.05; 5], 1e-12);
+        }
+
+        #[test]
+        fn test_false_discovery_rate_adjustments() {
+            let p = p_values();
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::BenjaminiHochberg).unwrap(), &[0.025, 0.05, 0.05, 0.025, 0.2], 1e-12);
+            let c = 1.0 + 1.0 / 2.0 + 1.0 / 3.0 + 1.0 / 4.0 + 1.0 / 5.0;
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::BenjaminiYekutieli).unwrap(),
+                             &[0.025 * c, 0.05 * c, 0.05 * c, 0.025 * c, 0.2 * c], 1e-12);
+            // one of five p-values above 0.1, so pi0 = 1 / (5 * 0.9)
+            let pi0 = 1.0 / 4.5;
+            assert_all_close(&adjust_p_values_rs(&p, Adjustment::Storey(0.1)).unwrap(),
+                             &[0.025 * pi0, 0.05 * pi0, 0.05 * pi0, 0.025 * pi0, 0.2 * pi0], 1e-12);
+            // nothing above lambda leaves no estimate of the null proportion
+            assert_eq!(adjust_p_values_rs(&p, Adjustment::Storey(0.5)), Err(StatsError::NoSolution));
+        }
+
+        #[test]
+        fn test_invalid_p_values() {
+            assert_eq!(adjust_p_values_rs(&array![0.5, 1.5], Adjustment::Holm), Err(StatsError::InvalidInputValue));
+            assert_eq!(Adjustment::parse("storey", 1.0), Err(StatsError::InvalidInputValue));
+            assert_eq!(Adjustment::parse("sidak", 0.5), Err(StatsError::InvalidInputValue));
+        }
+    }
+
+    mod permutation_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::permutation_tests::*;
+
+        fn run(x: &Array1<f64>, y: &Array1<f64>, statistic: &str, design: PermutationDesign,
+               alternative: Alternative, method: PermutationMethod) -> (f64, f64, bool) {
+            permutation_test_rs(x, y, pair_statistic_by_name(statistic).unwrap(), design, alternative, method, 9999, 11).unwrap()
+        }
+
+        #[test]
+        fn test_exact_independent() {
+            // the observed split is the most extreme of the C(6, 3) = 20
+            let (x, y) = (array![1.0, 2.0, 3.0], array![4.0, 5.0, 6.0]);
+            let (d, p, exact) = run(&x, &y, "mean_difference", PermutationDesign::Independent, Alternative::Less, PermutationMethod::Auto);
+            assert!(exact);
+            assert_eq!(d, -3.0);
+            assert_close(p, 1.0 / 20.0, 1e-12);
+            let (_, p, _) = run(&x, &y, "mean_difference", PermutationDesign::Independent, Alternative::TwoSided, PermutationMethod::Auto);
+            assert_close(p, 0.1, 1e-12);
+            let (_, p, _) = run(&x, &y, "median_difference", PermutationDesign::Independent, Alternative::Greater, PermutationMethod::Auto);
+            assert_close(p, 1.0, 1e-12);
+        }
+
+        #[test]
+        fn test_exact_paired_and_correlation() {
+            let x = array![1.2, 2.3, 3.1, 4.8, 5.5];
+            let y = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let (_, p, _) = run(&x, &y, "mean_difference", PermutationDesign::Paired, Alternative::Greater, PermutationMethod::Exact);
+            assert_close(p, 1.0 / 32.0, 1e-12);
+            // only the identity permutation reaches the observed correlation
+            let (r, p, _) = run(&x, &y, "correlation", PermutationDesign::Correlation, Alternative::Greater, PermutationMethod::Exact);
+            assert!(r > 0.99);
+            assert_close(p, 1.0 / 120.0, 1e-12);
+        }
+
+        #[test]
+        fn test_monte_carlo_is_reproducible_and_close_to_exact() {
+            let x = array![3.1, 4.2, 2.8, 5.0, 3.9, 4.4, 3.3];
+            let y = array![2.2, 3.0, 2.9, 3.6, 2.5, 3.1];
+            let (_, exact_p, _) = run(&x, &y, "mean_difference", PermutationDesign::Independent, Alternative::TwoSided, PermutationMethod::Exact);
+            let (_, mc_p, exact) = run(&x, &y, "mean_difference", PermutationDesign::Independent, Alternative::TwoSided, PermutationMethod::MonteCarlo);
+            assert!(!exact);
+            assert_close(mc_p, exact_p, 0.01);
+            let (_, again, _) = run(&x, &y, "mean_difference", PermutationDesign::Independent, Alternative::TwoSided, PermutationMethod::MonteCarlo);
+            assert_eq!(mc_p, again);
+        }
+
+        #[test]
+        fn test_count_and_errors() {
+            assert_eq!(PermutationDesign::Independent.count(7, 6), 1716.0);
+            assert_eq!(PermutationDesign::Correlation.count(5, 5), 120.0);
+            let long: Array1<f64> = (0..30).map(|v| v as f64).collect();
+            assert_eq!(permutation_test_rs(&long, &long, pair_statistic_by_name("correlation").unwrap(), PermutationDesign::Correlation,
+                                           Alternative::TwoSided, PermutationMethod::Exact, 100, 1), Err(StatsError::InvalidInputValue));
+            assert_eq!(permutation_test_rs(&array![1.0, 2.0], &array![1.0], pair_statistic_by_name("mean_difference").unwrap(),
+                                           PermutationDesign::Paired, Alternative::TwoSided, PermutationMethod::Auto, 100, 1),
+                       Err(StatsError::InconsistentLength));
+        }
+    }
+
+    mod correlation_tests_tests {
+        use super::*;
+        use crate::descriptive_statistics::correlation_rs;
+        use crate::inferential_statistics::correlation_tests::*;
+        use crate::inferential_statistics::rank_tests::Method;
+        use ndarray::Array2;
+
+        #[test]
+        fn test_pearson() {
+            let x = array![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+            let y = array![2.0, 1.0, 4.0, 3.0, 7.0, 5.0, 6.0, 9.0, 10.0, 8.0];
+            let result = pearson_test_rs(&x, &y, Alternative::TwoSided, 0.95).unwrap();
+            let r = correlation_rs(&x, &y);
+            assert_close(result.estimate.unwrap(), r, 1e-12);
+            assert_close(result.statistic, r * (8.0 / (1.0 - r * r)).sqrt(), 1e-12);
+            assert_eq!(result.df, Some(8.0));
+            let (lo, hi) = result.confidence_interval.unwrap();
+            assert!(lo < r && r < hi && hi < 1.0);
+            // one sided p halves the two sided one in the direction of the effect
+            let greater = pearson_test_rs(&x, &y, Alternative::Greater, 0.95).unwrap();
+            assert_close(greater.p_value, result.p_value / 2.0, 1e-12);
+            assert_eq!(greater.confidence_interval.unwrap().1, 1.0);
+        }
+
+        #[test]
+        fn test_spearman_monotone() {
+            let x = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let y = array![1.0, 8.0, 27.0, 64.0, 125.0];
+            let result = spearman_test_rs(&x, &y, Alternative::TwoSided).unwrap();
+            assert_eq!(result.estimate, Some(1.0));
+            assert_eq!(result.p_value, 0.0);
+        }
+
+        #[test]
+        fn test_kendall_exact_and_asymptotic() {
+            // scipy.stats.kendalltau([1, 2, 3, 4], [1, 3, 2, 4]) -> tau 0.6667, p 0.3333
+            let x = array![1.0, 2.0, 3.0, 4.0];
+            let y = array![1.0, 3.0, 2.0, 4.0];
+            let result = kendall_tau_test_rs(&x, &y, Alternative::TwoSided, Method::Auto).unwrap();
+            assert_eq!(result.statistic, 4.0);
+            assert_close(result.estimate.unwrap(), 2.0 / 3.0, 1e-12);
+            assert_close(result.p_value, 1.0 / 3.0, 1e-12);
+            // without ties var(C - D) = n (n - 1) (2n + 5) / 18
+            let asymptotic = kendall_tau_test_rs(&x, &y, Alternative::TwoSided, Method::Asymptotic).unwrap();
+            assert_close(asymptotic.statistic, 4.0 / (12.0_f64 * 13.0 / 18.0).sqrt(), 1e-12);
+            assert_close(asymptotic.estimate.unwrap(), 2.0 / 3.0, 1e-12);
+            let greater = kendall_tau_test_rs(&x, &y, Alternative::Greater, Method::Exact).unwrap();
+            assert_close(greater.p_value, 1.0 / 6.0, 1e-12);
+            // ties force the normal approximation
+            let tied = array![1.0, 1.0, 2.0, 3.0];
+            assert_eq!(kendall_tau_test_rs(&tied, &y, Alternative::TwoSided, Method::Exact), Err(StatsError::InvalidInputValue));
+            let result = kendall_tau_test_rs(&tied, &y, Alternative::TwoSided, Method::Auto).unwrap();
+            assert!(result.p_value > 0.0 && result.p_value < 1.0);
+        }
+
+        #[test]
+        fn test_compare_independent_correlations() {
+            let result = compare_correlations_rs(0.5, 103, 0.3, 103, Alternative::TwoSided).unwrap();
+            assert_close(result.statistic, (0.5_f64.atanh() - 0.3_f64.atanh()) / (2.0_f64 / 100.0).sqrt(), 1e-12);
+            assert_close(result.estimate.unwrap(), 0.2, 1e-12);
+        }
+
+        #[test]
+        fn test_steiger() {
+            let overlapping = steiger_overlapping_rs(0.6, 0.4, 0.5, 50, Alternative::TwoSided).unwrap();
+            assert_close(overlapping.statistic, 1.6712052493517717, 1e-12);
+            assert_eq!(steiger_overlapping_rs(0.4, 0.4, 0.5, 50, Alternative::TwoSided).unwrap().statistic, 0.0);
+            let nonoverlapping = steiger_nonoverlapping_rs(0.5, 0.3, 0.4, 0.2, 0.35, 0.25, 100, Alternative::TwoSided).unwrap();
+            assert_close(nonoverlapping.statistic, 1.7428278243061401, 1e-12);
+            assert_eq!(steiger_overlapping_rs(1.0, 0.4, 0.5, 50, Alternative::TwoSided), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_partial_correlation_matches_formula() {
+            let x = array![2.0, 4.0, 5.0, 4.0, 5.0, 7.0, 8.0, 9.0, 10.0, 12.0];
+            let y = array![1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0, 7.0, 11.0, 10.0];
+            let z = array![1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 6.0, 7.0, 7.0, 9.0];
+            let (r_xy, r_xz, r_yz) = (correlation_rs(&x, &y), correlation_rs(&x, &z), correlation_rs(&y, &z));
+            let covariates: Array2<f64> = z.clone().insert_axis(ndarray::Axis(1));
+
+            let partial = partial_correlation_rs(&x, &y, &covariates, PartialKind::Partial, Alternative::TwoSided, 0.95).unwrap();
+            let expected = (r_xy - r_xz * r_yz) / ((1.0 - r_xz * r_xz) * (1.0 - r_yz * r_yz)).sqrt();
+            assert_close(partial.estimate.unwrap(), expected, 1e-10);
+            assert_eq!(partial.df, Some(7.0));
+
+            let semi = partial_correlation_rs(&x, &y, &covariates, PartialKind::Semipartial, Alternative::TwoSided, 0.95).unwrap();
+            assert_close(semi.estimate.unwrap(), (r_xy - r_xz * r_yz) / (1.0 - r_xz * r_xz).sqrt(), 1e-10);
+        }
+    }
+
+    mod proportion_tests_tests {
+        use super::*;
+        use crate::inferential_statistics::contingency_tests::Table2x2;
+        use crate::inferential_statistics::proportion_tests::*;
+        use ndarray::Array2;
+
+        #[test]
+        fn test_binomial_test() {
+            let result = binomial_test_rs(3, 15, 0.1, Alternative::Greater, 0.95).unwrap();
+            assert_close(result.p_value, 0.18406106910639106, 1e-12);
+            assert_eq!(result.confidence_interval.unwrap().1, 1.0);
+            let two_sided = binomial_test_rs(5, 10, 0.5, Alternative::TwoSided, 0.95).unwrap();
+            assert_close(two_sided.p_value, 1.0, 1e-12);
+            assert_eq!(binomial_test_rs(11, 10, 0.5, Alternative::TwoSided, 0.95), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_clopper_pearson_bounds_invert_the_binomial_test() {
+            let (lo, hi) = proportion_interval_rs(3, 15, 0.95, Alternative::TwoSided, ProportionInterval::ClopperPearson).unwrap();
+            assert_close(binomial_p_value_rs(3, 15, lo, Alternative::Greater), 0.025, 1e-9);
+            assert_close(binomial_p_value_rs(3, 15, hi, Alternative::Less), 0.025, 1e-9);
+            let (lo, _) = proportion_interval_rs(0, 15, 0.95, Alternative::TwoSided, ProportionInterval::ClopperPearson).unwrap();
+            assert_eq!(lo, 0.0);
+        }
+
+        #[test]
+        fn test_wilson_interval() {
+            let (lo, hi) = proportion_interval_rs(7, 20, 0.95, Alternative::TwoSided, ProportionInterval::Wilson).unwrap();
+            assert_close(lo, 0.18119182410108206, 1e-8);
+            assert_close(hi, 0.5671457233147638, 1e-8);
+        }
+
+        #[test]
+        fn test_two_proportion_ztest() {
+            let result = two_proportion_ztest_rs(45, 100, 30, 100, Alternative::TwoSided, 0.95, DifferenceInterval::Newcombe).unwrap();
+            let pooled: f64 = 0.375;
+            assert_close(result.statistic, 0.15 / (pooled * (1.0 - pooled) * 0.02).sqrt(), 1e-12);
+            let (lo, hi) = result.confidence_interval.unwrap();
+            assert!(lo < 0.15 && 0.15 < hi && lo > 0.0);
+        }
+
+        #[test]
+        fn test_mcnemar() {
+            let table = Table2x2 { a: 794, b: 86, c: 150, d: 570 };
+            assert_close(mcnemar_test_rs(&table, false, true, 0.95).unwrap().statistic, 16.81779661016949, 1e-12);
+            assert_close(mcnemar_test_rs(&table, false, false, 0.95).unwrap().statistic, 17.35593220338983, 1e-12);
+            let exact = mcnemar_test_rs(&table, true, true, 0.95).unwrap();
+            assert!(exact.p_value < 1e-4);
+            assert_close(exact.estimate.unwrap(), -64.0 / 1600.0, 1e-12);
+        }
+
+        #[test]
+        fn test_cochrans_q() {
+            let data: Array2<f64> = array![[1.0, 1.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 1.0],
+                                           [0.0, 0.0, 0.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]];
+            let result = cochrans_q_test_rs(&data).unwrap();
+            assert_close(result.statistic, 3.5, 1e-12);
+            assert_close(result.p_value, (-1.75_f64).exp(), 1e-12);
+            assert_eq!(cochrans_q_test_rs(&data.mapv(|v| v * 2.0)), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_cmh_single_stratum_odds_ratio() {
+            let table = Table2x2 { a: 10, b: 5, c: 4, d: 12 };
+            let result = cochran_mantel_haenszel_rs(&[table], true, 0.95).unwrap();
+            assert_close(result.estimate.unwrap(), 120.0 / 20.0, 1e-12);
+            // one stratum reduces the RBG variance to Woolf's
+            let se = (0.1_f64 + 0.2 + 0.25 + 1.0 / 12.0).sqrt();
+            let (lo, _) = result.confidence_interval.unwrap();
+            assert_close(lo, (6.0_f64.ln() - 1.959963984540054 * se).exp(), 1e-8);
+        }
+    }
+
+    mod equivalence_tests_tests {
+        use super::*;
+        use crate::descriptive_statistics::{mean_rs, sample_variance_rs};
+        use crate::inferential_statistics::equivalence_tests::*;
+        use statrs::distribution::{ContinuousCDF, StudentsT};
+
+        fn extra(result: &TestResult, name: &str) -> f64 {
+            match result.extra(name) {
+                Some(crate::inferential_statistics::test_result::Extra::Scalar(value)) => *value,
+                other => panic!("{} is {:?}", name, other),
+            }
+        }
+
+        fn equivalent(result: &TestResult) -> bool {
+            result.extra("equivalent") == Some(&crate::inferential_statistics::test_result::Extra::Flag(true))
+        }
+
+        fn sample() -> Array1<f64> {
+            array![0.3, -0.2, 0.5, 0.1, -0.4, 0.2, 0.0, 0.35, -0.1, 0.15]
+        }
+
+        #[test]
+        fn test_one_sample_tost() {
+            let x = sample();
+            let result = tost_one_sample_rs(&x, 0.0, -0.5, 0.5, false, 0.05).unwrap();
+            let (m, se) = (mean_rs(&x), (sample_variance_rs(&x) / 10.0).sqrt());
+            let t = StudentsT::new(0.0, 1.0, 9.0).unwrap();
+            assert_close(extra(&result, "lower_p_value"), t.sf((m + 0.5) / se), 1e-12);
+            assert_close(extra(&result, "upper_p_value"), t.cdf((m - 0.5) / se), 1e-12);
+            assert_close(result.confidence_interval.unwrap().0, m - t.inverse_cdf(0.95) * se, 1e-8);
+            assert!(equivalent(&result));
+            assert_eq!(result.p_value, extra(&result, "lower_p_value").max(extra(&result, "upper_p_value")));
+            // the 90% interval lies inside the bounds exactly when both tests reject
+            assert!(result.confidence_interval.unwrap().0 > -0.5 && result.confidence_interval.unwrap().1 < 0.5);
+
+            let narrow = tost_one_sample_rs(&x, 0.0, -0.05, 0.05, false, 0.05).unwrap();
+            assert!(!equivalent(&narrow));
+        }
+
+        #[test]
+        fn test_standardized_bounds() {
+            let x = sample();
+            let result = tost_one_sample_rs(&x, 0.0, -0.5, 0.5, true, 0.05).unwrap();
+            let sd = sample_variance_rs(&x).sqrt();
+            assert_close(extra(&result, "upper_bound"), 0.5 * sd, 1e-12);
+        }
+
+        #[test]
+        fn test_paired_matches_one_sample_of_differences() {
+            let x = array![5.1, 4.8, 6.0, 5.5, 5.2, 4.9];
+            let y = array![5.0, 4.9, 5.8, 5.6, 5.0, 5.0];
+            let paired = tost_paired_rs(&x, &y, -0.3, 0.3, false, 0.05).unwrap();
+            let one_sample = tost_one_sample_rs(&(&x - &y), 0.0, -0.3, 0.3, false, 0.05).unwrap();
+            assert_eq!(paired.p_value, one_sample.p_value);
+            assert_eq!(paired.method, "Paired TOST");
+        }
+
+        #[test]
+        fn test_independent_tost_symmetry() {
+            let x = array![1.0, 2.0, 3.0, 4.0, 5.0];
+            let y = array![1.5, 2.5, 3.5, 4.5, 5.5, 3.0];
+            let welch = tost_independent_rs(&x, &y, -2.0, 2.0, false, false, 0.05).unwrap();
+            let pooled = tost_independent_rs(&x, &y, -2.0, 2.0, false, true, 0.05).unwrap();
+            assert_close(welch.estimate.unwrap(), -5.0 / 12.0, 1e-12);
+            assert_eq!(pooled.df, Some(9.0));
+            assert!(welch.df.unwrap() < 9.0);
+            // an estimate centred in the bounds gives equal one-sided p-values
+            let centred = tost_independent_rs(&x, &y, pooled.estimate.unwrap() - 2.0, pooled.estimate.unwrap() + 2.0, false, true, 0.05).unwrap();
+            assert_close(extra(&centred, "lower_p_value"), extra(&centred, "upper_p_value"), 1e-12);
+            // one observation leaves no variance to estimate
+            assert_eq!(tost_independent_rs(&x, &array![2.0], -2.0, 2.0, false, false, 0.05), Err(StatsError::InvalidInputValue));
+            assert_eq!(tost_one_sample_rs(&array![2.0], 0.0, -2.0, 2.0, false, 0.05), Err(StatsError::InvalidInputValue));
+        }
+
+        #[test]
+        fn test_proportions_tost() {
+            let result = tost_proportions_rs(45, 100, 48, 100, -0.15, 0.15, 0.05).unwrap();
+            let se = (0.45_f64 * 0.55 / 100.0 + 0.48 * 0.52 / 100.0).sqrt();
+            assert_close(extra(&result, "lower_statistic"), (-0.03 + 0.15) / se, 1e-12);
+            assert_eq!(result.df, None);
+            assert_eq!(tost_proportions_rs(45, 100, 48, 100, 0.15, -0.15, 0.05), Err(StatsError::InvalidInputValue));
+        }
+    }
+}
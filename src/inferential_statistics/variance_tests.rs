@@ -0,0 +1,222 @@
+// Homogeneity of variance tests
+// levene_test -- anova on absolute deviations from a group centre (mean, median or trimmed mean);
+//                the median centre is the Brown-Forsythe test
+// bartlett_test -- likelihood ratio test, powerful but sensitive to non-normality
+// fligner_killeen_test -- rank based test on absolute deviations from a group centre (median by default)
+// f_test -- ratio of two sample variances
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor, Normal};
+
+use crate::descriptive_statistics::{mean_rs, median_rs, sample_variance_rs, trim_rs, trimmed_mean_rs};
+use crate::inferential_statistics::errors::*;
+use crate::inferential_statistics::{Alternative, TestResult};
+use crate::utils::{from_pyarray1, from_pygroups, rank_rs};
+use crate::validate_statistical_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Center {
+    Mean,
+    Median,
+    // trimmed mean, the fraction cut from each end
+    Trimmed(f64),
+}
+
+impl Center {
+    pub fn parse(center: &str, proportion_to_cut: f64) -> Result<Center, StatsError> {
+        match center {
+            "mean" => Ok(Center::Mean),
+            "median" => Ok(Center::Median),
+            "trimmed" => {
+                if !(0.0..0.5).contains(&proportion_to_cut) { return Err(StatsError::InvalidInputValue); }
+                Ok(Center::Trimmed(proportion_to_cut))
+            }
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+
+    pub fn of(&self, data: &Array1<f64>) -> f64 {
+        match self {
+            Center::Mean => mean_rs(data),
+            Center::Median => median_rs(data),
+            Center::Trimmed(cut) => trimmed_mean_rs(data, *cut),
+        }
+    }
+}
+
+fn validate_groups(groups: &[Array1<f64>], min_size: usize) -> Result<(), StatsError> {
+    if groups.len() < 2 { return Err(StatsError::InsufficientGroups); }
+    if groups.iter().any(|g| g.len() < min_size) { return Err(StatsError::InvalidInputValue); }
+    Ok(())
+}
+
+// Absolute deviations of each group from its centre
+// the trimmed variant works on the trimmed samples throughout, not just for the centre
+fn center_deviations_rs(groups: &[Array1<f64>], center: Center) -> Result<Vec<Array1<f64>>, StatsError> {
+    let samples: Vec<Array1<f64>> = match center {
+        Center::Trimmed(cut) => groups.iter().map(|g| trim_rs(g, cut)).collect(),
+        _ => groups.to_vec(),
+    };
+    if samples.iter().any(|g| g.is_empty()) { return Err(StatsError::InvalidInputValue); }
+
+    Ok(samples.iter().map(|g| {
+        // already trimmed, so the centre is their plain mean as in scipy
+        let c = match center {
+            Center::Trimmed(_) => mean_rs(g),
+            _ => center.of(g),
+        };
+        g.mapv(|v| (v - c).abs())
+    }).collect())
+}
+
+// Returns (W, p)
+pub fn levene_rs(groups: &[Array1<f64>], center: Center) -> Result<(f64, f64), StatsError> {
+    validate_groups(groups, 2)?;
+    let deviations = center_deviations_rs(groups, center)?;
+
+    let k = deviations.len() as f64;
+    let n: f64 = deviations.iter().map(|d| d.len() as f64).sum();
+    let group_means: Vec<f64> = deviations.iter().map(mean_rs).collect();
+    let grand_mean = deviations.iter().map(|d| d.sum()).sum::<f64>() / n;
+
+    let between: f64 = deviations.iter().zip(group_means.iter())
+        .map(|(d, m)| d.len() as f64 * (m - grand_mean).powi(2))
+        .sum();
+    let within: f64 = deviations.iter().zip(group_means.iter())
+        .map(|(d, m)| d.iter().map(|v| (v - m).powi(2)).sum::<f64>())
+        .sum();
+    if within == 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let w = (n - k) / (k - 1.0) * between / within;
+    let f = FisherSnedecor::new(k - 1.0, n - k).map_err(|_| StatsError::UnderlyingError)?;
+    Ok((w, f.sf(w)))
+}
+
+// Returns (T, p)
+pub fn bartlett_rs(groups: &[Array1<f64>]) -> Result<(f64, f64), StatsError> {
+    validate_groups(groups, 2)?;
+    let k = groups.len() as f64;
+    let sizes: Vec<f64> = groups.iter().map(|g| g.len() as f64).collect();
+    let variances: Vec<f64> = groups.iter().map(sample_variance_rs).collect();
+    if variances.contains(&0.0) { return Err(StatsError::ZeroVariance); }
+    let n: f64 = sizes.iter().sum();
+
+    let pooled = sizes.iter().zip(variances.iter()).map(|(ni, vi)| (ni - 1.0) * vi).sum::<f64>() / (n - k);
+    let numerator = (n - k) * pooled.ln()
+        - sizes.iter().zip(variances.iter()).map(|(ni, vi)| (ni - 1.0) * vi.ln()).sum::<f64>();
+    let denominator = 1.0 + (sizes.iter().map(|ni| 1.0 / (ni - 1.0)).sum::<f64>() - 1.0 / (n - k))
+        / (3.0 * (k - 1.0));
+
+    let t = numerator / denominator;
+    let chi2 = ChiSquared::new(k - 1.0).map_err(|_| StatsError::UnderlyingError)?;
+    Ok((t, chi2.sf(t)))
+}
+
+// Returns (X^2, p)
+pub fn fligner_killeen_rs(groups: &[Array1<f64>], center: Center) -> Result<(f64, f64), StatsError> {
+    validate_groups(groups, 1)?;
+    let group_deviations = center_deviations_rs(groups, center)?;
+    let deviations: Vec<f64> = group_deviations.iter().flat_map(|d| d.iter().cloned()).collect();
+    let n = deviations.len() as f64;
+
+    // normal scores of the pooled ranks
+    let normal = Normal::new(0.0, 1.0).unwrap();
+    let (ranks, _) = rank_rs(&deviations);
+    let scores: Array1<f64> = ranks.mapv(|r| normal.inverse_cdf(0.5 + r / (2.0 * (n + 1.0))));
+    let score_mean = mean_rs(&scores);
+    let score_var = sample_variance_rs(&scores);
+    if score_var == 0.0 { return Err(StatsError::ZeroVariance); }
+
+    let mut offset = 0;
+    let mut between = 0.0;
+    for group in &group_deviations {
+        let group_scores = scores.slice(ndarray::s![offset..offset + group.len()]);
+        let group_mean = group_scores.sum() / group.len() as f64;
+        between += group.len() as f64 * (group_mean - score_mean).powi(2);
+        offset += group.len();
+    }
+
+    let statistic = between / score_var;
+    let chi2 = ChiSquared::new(groups.len() as f64 - 1.0).map_err(|_| StatsError::UnderlyingError)?;
+    Ok((statistic, chi2.sf(statistic)))
+}
+
+// Returns (F, p) with F = var(x) / var(y)
+pub fn f_test_rs(x: &Array1<f64>, y: &Array1<f64>, alternative: Alternative) -> Result<(f64, f64), StatsError> {
+    if x.len() < 2 || y.len() < 2 { return Err(StatsError::InvalidInputValue); }
+    let (vx, vy) = (sample_variance_rs(x), sample_variance_rs(y));
+    if vy == 0.0 { return Err(StatsError::ZeroVariance); }
+    let f = vx / vy;
+    let dist = FisherSnedecor::new(x.len() as f64 - 1.0, y.len() as f64 - 1.0)
+        .map_err(|_| StatsError::UnderlyingError)?;
+    let p = match alternative {
+        Alternative::Less => dist.cdf(f),
+        Alternative::Greater => dist.sf(f),
+        Alternative::TwoSided => (2.0 * dist.cdf(f).min(dist.sf(f))).min(1.0),
+    };
+    Ok((f, p))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (*groups, center = "median", proportion_to_cut = 0.05))]
+pub fn levene_test(groups: &PyTuple, center: &str, proportion_to_cut: f64) -> PyResult<TestResult> {
+    // center: "mean" (Levene), "median" (Brown-Forsythe) or "trimmed"
+    let groups_data = validated_groups(groups)?;
+    let (w, p) = levene_rs(&groups_data, Center::parse(center, proportion_to_cut)?)?;
+    Ok(TestResult::new("Levene's test", w, p).with_df(groups_data.len() as f64 - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (*groups))]
+pub fn bartlett_test(groups: &PyTuple) -> PyResult<TestResult> {
+    let groups_data = validated_groups(groups)?;
+    let (t, p) = bartlett_rs(&groups_data)?;
+    Ok(TestResult::new("Bartlett's test", t, p).with_df(groups_data.len() as f64 - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (*groups, center = "median", proportion_to_cut = 0.05))]
+pub fn fligner_killeen_test(groups: &PyTuple, center: &str, proportion_to_cut: f64) -> PyResult<TestResult> {
+    let groups_data = validated_groups(groups)?;
+    let (x2, p) = fligner_killeen_rs(&groups_data, Center::parse(center, proportion_to_cut)?)?;
+    Ok(TestResult::new("Fligner-Killeen test", x2, p).with_df(groups_data.len() as f64 - 1.0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alternative = "two-sided"))]
+pub fn f_test(x: &PyAny, y: &PyAny, alternative: &str) -> PyResult<TestResult> {
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    let y_data = match from_pyarray1(y) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    validate_statistical_input!(basic, y_data);
+    let alternative = Alternative::parse(alternative)?;
+    let (f, p) = f_test_rs(&x_data, &y_data, alternative)?;
+    Ok(TestResult::new("F test for equal variances", f, p)
+        .with_alternative(alternative)
+        .with_estimate(f))
+}
+
+fn validated_groups(groups: &PyTuple) -> PyResult<Vec<Array1<f64>>> {
+    let groups_data = match from_pygroups(groups) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    for group in &groups_data {
+        validate_statistical_input!(basic, group);
+    }
+    Ok(groups_data)
+}
@@ -1,6 +1,8 @@
 pub mod descriptive_statistics;
 pub mod inferential_statistics;
 pub mod linear_models;
+pub mod distributions;
+pub mod resampling;
 pub mod utils;
 mod dtype_conversion;
 
@@ -10,14 +12,48 @@ pub use descriptive_statistics::{mean, median, trimmed_mean, weighted_mean, vari
                                  trimmed_variance, median_absolute_deviation, iqr, range,
                                  covariance, correlation, skewness, kurtosis, summary_statistics};
 
-pub use inferential_statistics::{confidence_interval, kolmogorov_smirnov_test};
+pub use inferential_statistics::{confidence_interval, kolmogorov_smirnov_test, TestResult};
+pub use inferential_statistics::rank_tests::{mann_whitney_u_test, wilcoxon_signed_rank_test,
+                                             kruskal_wallis_test, friedman_test, dunn_test};
+pub use inferential_statistics::contingency_tests::{fisher_exact_test, barnard_exact_test,
+                                                    boschloo_exact_test};
+pub use inferential_statistics::power_analysis::{power_analysis, power_curve};
+pub use inferential_statistics::effect_size::{effect_size, cohens_d, hedges_g, glass_delta, anova_effect_sizes,
+                                              cliffs_delta, rank_biserial, odds_ratio, risk_ratio};
+pub use inferential_statistics::normality_tests::{shapiro_wilk_test, dagostino_pearson_test, jarque_bera_test,
+                                                  anderson_darling_test, lilliefors_test, cramer_von_mises_test};
+pub use inferential_statistics::variance_tests::{levene_test, bartlett_test, fligner_killeen_test, f_test};
+pub use inferential_statistics::multiple_comparisons::adjust_p_values;
+pub use inferential_statistics::permutation_tests::permutation_test;
+pub use inferential_statistics::correlation_tests::{pearson_test, spearman_test, kendall_tau_test, compare_correlations,
+                                                    steiger_test, steiger_test_nonoverlapping, partial_correlation};
+pub use inferential_statistics::proportion_tests::{binomial_test, proportion_ztest, two_proportion_ztest,
+                                                   proportion_interval, mcnemar_test, cochrans_q_test,
+                                                   cochran_mantel_haenszel_test};
+pub use inferential_statistics::equivalence_tests::{tost_one_sample, tost_paired, tost_independent, tost_proportions};
+pub use resampling::{bootstrap, jackknife};
+pub use distributions::continuous::{Normal, StudentsT, ChiSquared, FDistribution, Beta, Gamma, Exponential, LogNormal,
+                                    Weibull, Uniform, Cauchy};
+pub use distributions::discrete::{Poisson, Binomial, NegativeBinomial, Geometric};
+pub use distributions::fitting::{fit, FitResult};
+pub use linear_models::errors::{SingularMatrixError, RankDeficiencyError, ConvergenceError, ShapeMismatchError,
+                                NotFittedError};
+pub use linear_models::ols::LinearRegression;
+pub use linear_models::diagnostics::{residuals, leverage, cooks_distance, dffits, dfbetas, variance_inflation_factors,
+                                     breusch_pagan_test, white_test, durbin_watson, reset_test};
+pub use linear_models::glm::Glm;
+pub use linear_models::logistic::LogisticRegression;
+pub use linear_models::regularized::{Ridge, Lasso, ElasticNet, RegularizationPath, ridge_path, enet_path};
+pub use linear_models::formula::Formula;
+pub use linear_models::robust::{RobustLinearModel, RANSACRegressor, TheilSenRegressor, QuantileRegression};
+pub use linear_models::scoring::{score, scorers};
 
 // Or common, if you prefer that name
 // pub use utils::pyarray_conversion::PyArrayConversion;
 
 //
 #[pymodule]
-fn wemburs(_py: Python, m: &PyModule) -> PyResult<()> {
+fn wemburs(py: Python, m: &PyModule) -> PyResult<()> {
     // descriptive statistics - yee yoo yaa
     m.add_function(wrap_pyfunction!(mean, m)?)?;
     m.add_function(wrap_pyfunction!(trimmed_mean, m)?)?;
@@ -35,19 +71,118 @@ fn wemburs(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(summary_statistics, m)?)?;
 
     // inferential statistics - wee woo waa
+    m.add_class::<TestResult>()?;
     m.add_function(wrap_pyfunction!(confidence_interval, m)?)?;
-    //m.add_function(wrap_pyfunction!(effect_size, m)?)?;
+    m.add_function(wrap_pyfunction!(effect_size, m)?)?;
+    m.add_function(wrap_pyfunction!(cohens_d, m)?)?;
+    m.add_function(wrap_pyfunction!(hedges_g, m)?)?;
+    m.add_function(wrap_pyfunction!(glass_delta, m)?)?;
+    m.add_function(wrap_pyfunction!(anova_effect_sizes, m)?)?;
+    m.add_function(wrap_pyfunction!(cliffs_delta, m)?)?;
+    m.add_function(wrap_pyfunction!(rank_biserial, m)?)?;
+    m.add_function(wrap_pyfunction!(odds_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(risk_ratio, m)?)?;
     m.add_function(wrap_pyfunction!(kolmogorov_smirnov_test, m)?)?;
     // m.add_function(wrap_pyfunction!(t_test, m)?)?;
     // m.add_function(wrap_pyfunction!(chi_squared_test, m)?)?;
     // m.add_function(wrap_pyfunction!(anova, m)?)?;
-    // m.add_function(wrap_pyfunction!(mann_whitney_u_test, m)?)?;
+    m.add_function(wrap_pyfunction!(mann_whitney_u_test, m)?)?;
     // m.add_function(wrap_pyfunction!(spearman_rank_correlation_test, m)?)?;
-    // m.add_function(wrap_pyfunction!(fisher_exact_test, m)?)?;
-    // m.add_function(wrap_pyfunction!(kruskal_wallis_test, m)?)?;
-    // m.add_function(wrap_pyfunction!(wilcoxon_signed_rank_test, m)?)?;
-    // m.add_function(wrap_pyfunction!(fisher_exact_test, m)?)?;
-    // m.add_function(wrap_pyfunction!(power_analysis, m)?)?;
+    m.add_function(wrap_pyfunction!(fisher_exact_test, m)?)?;
+    m.add_function(wrap_pyfunction!(kruskal_wallis_test, m)?)?;
+    m.add_function(wrap_pyfunction!(wilcoxon_signed_rank_test, m)?)?;
+    m.add_function(wrap_pyfunction!(friedman_test, m)?)?;
+    m.add_function(wrap_pyfunction!(dunn_test, m)?)?;
+    m.add_function(wrap_pyfunction!(barnard_exact_test, m)?)?;
+    m.add_function(wrap_pyfunction!(boschloo_exact_test, m)?)?;
+    m.add_function(wrap_pyfunction!(power_analysis, m)?)?;
+    m.add_function(wrap_pyfunction!(power_curve, m)?)?;
+    m.add_function(wrap_pyfunction!(shapiro_wilk_test, m)?)?;
+    m.add_function(wrap_pyfunction!(dagostino_pearson_test, m)?)?;
+    m.add_function(wrap_pyfunction!(jarque_bera_test, m)?)?;
+    m.add_function(wrap_pyfunction!(anderson_darling_test, m)?)?;
+    m.add_function(wrap_pyfunction!(lilliefors_test, m)?)?;
+    m.add_function(wrap_pyfunction!(cramer_von_mises_test, m)?)?;
+    m.add_function(wrap_pyfunction!(levene_test, m)?)?;
+    m.add_function(wrap_pyfunction!(bartlett_test, m)?)?;
+    m.add_function(wrap_pyfunction!(fligner_killeen_test, m)?)?;
+    m.add_function(wrap_pyfunction!(f_test, m)?)?;
+    m.add_function(wrap_pyfunction!(adjust_p_values, m)?)?;
+    m.add_function(wrap_pyfunction!(permutation_test, m)?)?;
+    m.add_function(wrap_pyfunction!(pearson_test, m)?)?;
+    m.add_function(wrap_pyfunction!(spearman_test, m)?)?;
+    m.add_function(wrap_pyfunction!(kendall_tau_test, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_correlations, m)?)?;
+    m.add_function(wrap_pyfunction!(steiger_test, m)?)?;
+    m.add_function(wrap_pyfunction!(steiger_test_nonoverlapping, m)?)?;
+    m.add_function(wrap_pyfunction!(partial_correlation, m)?)?;
+    m.add_function(wrap_pyfunction!(binomial_test, m)?)?;
+    m.add_function(wrap_pyfunction!(proportion_ztest, m)?)?;
+    m.add_function(wrap_pyfunction!(two_proportion_ztest, m)?)?;
+    m.add_function(wrap_pyfunction!(proportion_interval, m)?)?;
+    m.add_function(wrap_pyfunction!(mcnemar_test, m)?)?;
+    m.add_function(wrap_pyfunction!(cochrans_q_test, m)?)?;
+    m.add_function(wrap_pyfunction!(cochran_mantel_haenszel_test, m)?)?;
+    m.add_function(wrap_pyfunction!(tost_one_sample, m)?)?;
+    m.add_function(wrap_pyfunction!(tost_paired, m)?)?;
+    m.add_function(wrap_pyfunction!(tost_independent, m)?)?;
+    m.add_function(wrap_pyfunction!(tost_proportions, m)?)?;
+
+    // resampling
+    m.add_function(wrap_pyfunction!(bootstrap, m)?)?;
+    m.add_function(wrap_pyfunction!(jackknife, m)?)?;
+
+    // distributions
+    m.add_class::<Normal>()?;
+    m.add_class::<StudentsT>()?;
+    m.add_class::<ChiSquared>()?;
+    m.add_class::<FDistribution>()?;
+    m.add_class::<Beta>()?;
+    m.add_class::<Gamma>()?;
+    m.add_class::<Exponential>()?;
+    m.add_class::<LogNormal>()?;
+    m.add_class::<Weibull>()?;
+    m.add_class::<Uniform>()?;
+    m.add_class::<Cauchy>()?;
+    m.add_class::<Poisson>()?;
+    m.add_class::<Binomial>()?;
+    m.add_class::<NegativeBinomial>()?;
+    m.add_class::<Geometric>()?;
+    m.add_class::<FitResult>()?;
+    m.add_function(wrap_pyfunction!(fit, m)?)?;
+
+    // linear models
+    m.add("SingularMatrixError", py.get_type::<SingularMatrixError>())?;
+    m.add("RankDeficiencyError", py.get_type::<RankDeficiencyError>())?;
+    m.add("ConvergenceError", py.get_type::<ConvergenceError>())?;
+    m.add("ShapeMismatchError", py.get_type::<ShapeMismatchError>())?;
+    m.add("NotFittedError", py.get_type::<NotFittedError>())?;
+    m.add_class::<LinearRegression>()?;
+    m.add_class::<Ridge>()?;
+    m.add_class::<Lasso>()?;
+    m.add_class::<ElasticNet>()?;
+    m.add_class::<LogisticRegression>()?;
+    m.add_class::<Glm>()?;
+    m.add_class::<RobustLinearModel>()?;
+    m.add_class::<RANSACRegressor>()?;
+    m.add_class::<TheilSenRegressor>()?;
+    m.add_class::<QuantileRegression>()?;
+    m.add_class::<Formula>()?;
+    m.add_class::<RegularizationPath>()?;
+    m.add_function(wrap_pyfunction!(ridge_path, m)?)?;
+    m.add_function(wrap_pyfunction!(enet_path, m)?)?;
+    m.add_function(wrap_pyfunction!(score, m)?)?;
+    m.add_function(wrap_pyfunction!(scorers, m)?)?;
+    m.add_function(wrap_pyfunction!(residuals, m)?)?;
+    m.add_function(wrap_pyfunction!(leverage, m)?)?;
+    m.add_function(wrap_pyfunction!(cooks_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(dffits, m)?)?;
+    m.add_function(wrap_pyfunction!(dfbetas, m)?)?;
+    m.add_function(wrap_pyfunction!(variance_inflation_factors, m)?)?;
+    m.add_function(wrap_pyfunction!(breusch_pagan_test, m)?)?;
+    m.add_function(wrap_pyfunction!(white_test, m)?)?;
+    m.add_function(wrap_pyfunction!(durbin_watson, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_test, m)?)?;
     Ok(())
 }
     // Implement November 22nd
@@ -0,0 +1,155 @@
+// Sandwich covariance estimators for OLS
+// hc0 .. hc3 -- White's heteroskedasticity-consistent estimators with MacKinnon-White corrections
+// cluster -- one-way, or two-way (Cameron-Gelbach-Miller) when two group columns are given,
+//            with the G / (G - 1) (n - 1) / (n - k) small sample correction
+// hac -- Newey-West with Bartlett weights, maxlags defaulting to floor(4 (n / 100)^(2/9))
+//
+// Every estimator is (X'X)^-1 M (X'X)^-1 for a different meat M. Inference with a robust
+// covariance follows Stata: t with n - k degrees of freedom, or G - 1 for clustered errors with G
+// the smallest number of clusters.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+
+use crate::linear_models::errors::LinearModelError;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CovType {
+    Nonrobust,
+    Hc0,
+    Hc1,
+    Hc2,
+    Hc3,
+    // cluster index of every observation
+    Cluster(Vec<usize>),
+    TwoWayCluster(Vec<usize>, Vec<usize>),
+    NeweyWest(usize),
+}
+
+impl CovType {
+    // groups is (n, 1) for one-way and (n, 2) for two-way clustering
+    pub fn parse(cov_type: &str, groups: Option<&Array2<f64>>, maxlags: Option<usize>, nobs: usize)
+                 -> Result<CovType, LinearModelError> {
+        let cov_type = cov_type.to_lowercase();
+        if cov_type != "cluster" && groups.is_some() { return Err(LinearModelError::InvalidInputValue); }
+        if cov_type != "hac" && maxlags.is_some() { return Err(LinearModelError::InvalidInputValue); }
+        match cov_type.as_str() {
+            "nonrobust" => Ok(CovType::Nonrobust),
+            "hc0" => Ok(CovType::Hc0),
+            "hc1" => Ok(CovType::Hc1),
+            "hc2" => Ok(CovType::Hc2),
+            "hc3" => Ok(CovType::Hc3),
+            "cluster" => {
+                let groups = groups.ok_or(LinearModelError::InvalidInputValue)?;
+                if groups.nrows() != nobs { return Err(LinearModelError::ShapeMismatch); }
+                if groups.iter().any(|v| !v.is_finite()) { return Err(LinearModelError::InvalidInputValue); }
+                match groups.ncols() {
+                    1 => Ok(CovType::Cluster(group_indices_rs(groups.column(0)))),
+                    2 => Ok(CovType::TwoWayCluster(group_indices_rs(groups.column(0)), group_indices_rs(groups.column(1)))),
+                    _ => Err(LinearModelError::ShapeMismatch),
+                }
+            }
+            "hac" => Ok(CovType::NeweyWest(maxlags.unwrap_or((4.0 * (nobs as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize))),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            CovType::Nonrobust => "nonrobust".to_string(),
+            CovType::Hc0 => "HC0".to_string(),
+            CovType::Hc1 => "HC1".to_string(),
+            CovType::Hc2 => "HC2".to_string(),
+            CovType::Hc3 => "HC3".to_string(),
+            CovType::Cluster(_) => "cluster".to_string(),
+            CovType::TwoWayCluster(_, _) => "cluster (two-way)".to_string(),
+            CovType::NeweyWest(maxlags) => format!("HAC (maxlags={})", maxlags),
+        }
+    }
+
+    // Residual degrees of freedom of the reference t and F distributions
+    pub fn inference_df(&self, df_resid: f64) -> f64 {
+        let clusters = |groups: &[usize]| groups.iter().max().map_or(0, |g| g + 1) as f64;
+        match self {
+            CovType::Cluster(groups) => clusters(groups) - 1.0,
+            CovType::TwoWayCluster(first, second) => clusters(first).min(clusters(second)) - 1.0,
+            _ => df_resid,
+        }
+    }
+}
+
+// Dense indices 0..G for the distinct keys, in order of first appearance
+fn dense_indices_rs<K: Hash + Eq>(keys: impl Iterator<Item = K>) -> Vec<usize> {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    keys.map(|key| {
+        let next = seen.len();
+        *seen.entry(key).or_insert(next)
+    }).collect()
+}
+
+// Group labels keyed by their bits, with -0.0 folded into 0.0
+fn group_indices_rs(labels: ArrayView1<f64>) -> Vec<usize> {
+    dense_indices_rs(labels.iter().map(|label| (label + 0.0).to_bits()))
+}
+
+// Sum over clusters of (X_g' e_g)(X_g' e_g)', times the small sample correction
+fn cluster_meat_rs(design: &Array2<f64>, residuals: &Array1<f64>, groups: &[usize]) -> Result<Array2<f64>, LinearModelError> {
+    let (n, k) = design.dim();
+    let clusters = groups.iter().max().map_or(0, |g| g + 1);
+    if clusters < 2 { return Err(LinearModelError::InsufficientGroups); }
+    let mut scores = Array2::<f64>::zeros((clusters, k));
+    for (i, row) in design.axis_iter(Axis(0)).enumerate() {
+        scores.row_mut(groups[i]).scaled_add(residuals[i], &row);
+    }
+    let g = clusters as f64;
+    let correction = g / (g - 1.0) * (n as f64 - 1.0) / (n - k) as f64;
+    Ok(scores.t().dot(&scores) * correction)
+}
+
+// (X'X)^-1 M (X'X)^-1 for the requested estimator, None for the classical s^2 (X'X)^-1
+pub fn sandwich_rs(cov_type: &CovType, design: &Array2<f64>, residuals: &Array1<f64>, xtx_inverse: &Array2<f64>)
+                   -> Result<Option<Array2<f64>>, LinearModelError> {
+    let (n, k) = design.dim();
+    let leverage = || -> Array1<f64> {
+        design.axis_iter(Axis(0)).map(|row| row.dot(&xtx_inverse.dot(&row))).collect()
+    };
+    // meat from per-observation weights on the squared residuals
+    let weighted_meat = |weights: Array1<f64>| -> Array2<f64> {
+        let mut scaled = design.clone();
+        for (i, mut row) in scaled.axis_iter_mut(Axis(0)).enumerate() {
+            row *= residuals[i] * weights[i].sqrt();
+        }
+        scaled.t().dot(&scaled)
+    };
+    let meat = match cov_type {
+        CovType::Nonrobust => return Ok(None),
+        CovType::Hc0 => weighted_meat(Array1::ones(n)),
+        CovType::Hc1 => weighted_meat(Array1::from_elem(n, n as f64 / (n - k) as f64)),
+        CovType::Hc2 => weighted_meat(leverage().mapv(|h| 1.0 / (1.0 - h))),
+        CovType::Hc3 => weighted_meat(leverage().mapv(|h| 1.0 / (1.0 - h).powi(2))),
+        CovType::Cluster(groups) => cluster_meat_rs(design, residuals, groups)?,
+        CovType::TwoWayCluster(first, second) => {
+            // the intersection clusters are counted by both dimensions and removed once
+            let intersection = dense_indices_rs(first.iter().zip(second.iter()));
+            cluster_meat_rs(design, residuals, first)? + cluster_meat_rs(design, residuals, second)?
+                - cluster_meat_rs(design, residuals, &intersection)?
+        }
+        CovType::NeweyWest(maxlags) => {
+            let mut scores = design.clone();
+            for (i, mut row) in scores.axis_iter_mut(Axis(0)).enumerate() {
+                row *= residuals[i];
+            }
+            let mut meat = scores.t().dot(&scores);
+            for lag in 1..=(*maxlags).min(n - 1) {
+                let weight = 1.0 - lag as f64 / (*maxlags as f64 + 1.0);
+                let gamma = scores.slice(ndarray::s![lag.., ..]).t().dot(&scores.slice(ndarray::s![..n - lag, ..]));
+                meat = meat + (&gamma + &gamma.t()) * weight;
+            }
+            meat
+        }
+    };
+    Ok(Some(xtx_inverse.dot(&meat).dot(xtx_inverse)))
+}
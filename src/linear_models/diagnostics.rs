@@ -0,0 +1,275 @@
+// Regression diagnostics for fitted LinearRegression models
+// residuals -- raw, standardized (internally studentized) or studentized (externally, leave-one-out)
+// leverage -- diagonal of the hat matrix
+// influence -- cooks_distance, dffits and dfbetas, all from the closed leave-one-out formulas
+// variance_inflation_factors -- VIF of each feature against the others
+// breusch_pagan_test / white_test -- Koenker's n R^2 tests for heteroskedasticity
+// durbin_watson -- first order residual autocorrelation statistic
+// reset_test -- Ramsey's RESET F test on powers of the fitted values
+//
+// Everything is computed from the OlsFit of the model and follows statsmodels' definitions.
+
+use ndarray::{Array1, Array2, Axis};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor};
+
+use crate::inferential_statistics::TestResult;
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::linalg::QrDecomposition;
+use crate::linear_models::ols::{ols_rs, LinearRegression, OlsFit, Solver};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResidualKind {
+    Raw,
+    Standardized,
+    Studentized,
+}
+
+impl ResidualKind {
+    pub fn parse(kind: &str) -> Result<ResidualKind, LinearModelError> {
+        match kind {
+            "raw" => Ok(ResidualKind::Raw),
+            "standardized" => Ok(ResidualKind::Standardized),
+            "studentized" => Ok(ResidualKind::Studentized),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+}
+
+// h_ii = x_i' (X'X)^-1 x_i
+pub fn leverage_rs(fit: &OlsFit) -> Array1<f64> {
+    fit.design.axis_iter(Axis(0)).map(|row| row.dot(&fit.xtx_inverse.dot(&row))).collect()
+}
+
+// Residual standard deviation with each observation left out in turn
+fn leave_one_out_scale_rs(fit: &OlsFit, leverage: &Array1<f64>) -> Array1<f64> {
+    let df = fit.df_resid - 1.0;
+    fit.residuals.iter().zip(leverage.iter())
+        .map(|(e, h)| ((fit.ssr - e * e / (1.0 - h)) / df).sqrt())
+        .collect()
+}
+
+pub fn residuals_rs(fit: &OlsFit, kind: ResidualKind) -> Array1<f64> {
+    let leverage = leverage_rs(fit);
+    match kind {
+        ResidualKind::Raw => fit.residuals.clone(),
+        ResidualKind::Standardized => {
+            let s = fit.scale().sqrt();
+            fit.residuals.iter().zip(leverage.iter()).map(|(e, h)| e / (s * (1.0 - h).sqrt())).collect()
+        }
+        ResidualKind::Studentized => {
+            let scales = leave_one_out_scale_rs(fit, &leverage);
+            (0..fit.nobs).map(|i| fit.residuals[i] / (scales[i] * (1.0 - leverage[i]).sqrt())).collect()
+        }
+    }
+}
+
+// D_i = r_i^2 h_i / (k (1 - h_i)) with r_i the standardized residual
+pub fn cooks_distance_rs(fit: &OlsFit) -> Array1<f64> {
+    let leverage = leverage_rs(fit);
+    let k = fit.params.len() as f64;
+    residuals_rs(fit, ResidualKind::Standardized).iter().zip(leverage.iter())
+        .map(|(r, h)| r * r * h / (k * (1.0 - h)))
+        .collect()
+}
+
+// Studentized residual times sqrt(h_i / (1 - h_i))
+pub fn dffits_rs(fit: &OlsFit) -> Array1<f64> {
+    let leverage = leverage_rs(fit);
+    residuals_rs(fit, ResidualKind::Studentized).iter().zip(leverage.iter())
+        .map(|(t, h)| t * (h / (1.0 - h)).sqrt())
+        .collect()
+}
+
+// (beta - beta_(i)) / (s_(i) sqrt((X'X)^-1_jj)), one row per observation and one column per parameter.
+// The leave-one-out change is (X'X)^-1 x_i e_i / (1 - h_i).
+pub fn dfbetas_rs(fit: &OlsFit) -> Array2<f64> {
+    let leverage = leverage_rs(fit);
+    let scales = leave_one_out_scale_rs(fit, &leverage);
+    let root_diagonal = fit.xtx_inverse.diag().mapv(f64::sqrt);
+    let mut dfbetas = Array2::<f64>::zeros((fit.nobs, fit.params.len()));
+    for (i, row) in fit.design.axis_iter(Axis(0)).enumerate() {
+        let change = fit.xtx_inverse.dot(&row) * (fit.residuals[i] / (1.0 - leverage[i]));
+        dfbetas.row_mut(i).assign(&(&change / &root_diagonal / scales[i]));
+    }
+    dfbetas
+}
+
+// Columns of the design other than the intercept
+fn feature_columns(fit: &OlsFit) -> Array2<f64> {
+    fit.design.slice(ndarray::s![.., usize::from(fit.fit_intercept)..]).to_owned()
+}
+
+// 1 / (1 - R^2) of each feature regressed on the others, with the model's intercept setting
+pub fn variance_inflation_factors_rs(fit: &OlsFit) -> Result<Array1<f64>, LinearModelError> {
+    let features = feature_columns(fit);
+    let p = features.ncols();
+    let mut factors = Array1::<f64>::ones(p);
+    if p < 2 { return Ok(factors); }
+    for j in 0..p {
+        let others: Vec<usize> = (0..p).filter(|&c| c != j).collect();
+        let auxiliary = ols_rs(&features.select(Axis(1), &others), &features.column(j).to_owned(),
+                               fit.fit_intercept, Solver::Qr)?;
+        factors[j] = 1.0 / (1.0 - auxiliary.r_squared());
+    }
+    Ok(factors)
+}
+
+// Keeps the columns that add to the rank of those kept before them, so squares of binary features
+// and similar duplicates drop out of the auxiliary regressions
+fn independent_columns_rs(columns: Vec<Array1<f64>>, n: usize) -> Array2<f64> {
+    let mut kept: Vec<Array1<f64>> = vec![Array1::<f64>::ones(n)];
+    for column in columns {
+        let mut candidate = Array2::<f64>::zeros((n, kept.len() + 1));
+        for (j, existing) in kept.iter().chain(std::iter::once(&column)).enumerate() {
+            candidate.column_mut(j).assign(existing);
+        }
+        if QrDecomposition::new(&candidate).rank() == kept.len() + 1 { kept.push(column); }
+    }
+    let mut matrix = Array2::<f64>::zeros((n, kept.len() - 1));
+    for (j, column) in kept.iter().skip(1).enumerate() {
+        matrix.column_mut(j).assign(column);
+    }
+    matrix
+}
+
+// Koenker's studentized LM statistic: n R^2 of the squared residuals on the regressors, chi^2 with
+// one degree of freedom per regressor
+fn auxiliary_lm_test_rs(fit: &OlsFit, regressors: Array2<f64>) -> Result<(f64, f64, f64), LinearModelError> {
+    let df = regressors.ncols() as f64;
+    if df == 0.0 { return Err(LinearModelError::InsufficientObservations); }
+    let squared = fit.residuals.mapv(|e| e * e);
+    let auxiliary = ols_rs(&regressors, &squared, true, Solver::Qr)?;
+    let lm = fit.nobs as f64 * auxiliary.r_squared();
+    Ok((lm, ChiSquared::new(df).unwrap().sf(lm), df))
+}
+
+// The non-constant columns of the model's design. The auxiliary regressions always carry a constant,
+// so the model must have one too, its intercept or a constant column of a no-intercept design;
+// without it the two regressions differ and n R^2 has no chi^2 null distribution.
+fn auxiliary_features(fit: &OlsFit) -> Result<Vec<Array1<f64>>, LinearModelError> {
+    if fit.fit_intercept {
+        return Ok(feature_columns(fit).axis_iter(Axis(1)).map(|c| c.to_owned()).collect());
+    }
+    let is_constant = |c: &ndarray::ArrayView1<f64>| c[0] != 0.0 && c.iter().all(|&v| v == c[0]);
+    if !fit.design.axis_iter(Axis(1)).any(|c| is_constant(&c)) { return Err(LinearModelError::InvalidInputValue); }
+    Ok(fit.design.axis_iter(Axis(1)).filter(|c| !is_constant(c)).map(|c| c.to_owned()).collect())
+}
+
+// Returns (LM, p, df)
+pub fn breusch_pagan_rs(fit: &OlsFit) -> Result<(f64, f64, f64), LinearModelError> {
+    let columns = auxiliary_features(fit)?;
+    auxiliary_lm_test_rs(fit, independent_columns_rs(columns, fit.nobs))
+}
+
+// Returns (LM, p, df), the auxiliary regression adding squares and cross products of the features
+pub fn white_rs(fit: &OlsFit) -> Result<(f64, f64, f64), LinearModelError> {
+    let features = auxiliary_features(fit)?;
+    let p = features.len();
+    let mut columns = features.clone();
+    for i in 0..p {
+        for j in i..p {
+            columns.push(&features[i] * &features[j]);
+        }
+    }
+    auxiliary_lm_test_rs(fit, independent_columns_rs(columns, fit.nobs))
+}
+
+// sum (e_t - e_t-1)^2 / sum e_t^2, near 2 without autocorrelation
+pub fn durbin_watson_rs(fit: &OlsFit) -> f64 {
+    let e = &fit.residuals;
+    let differences: f64 = (1..e.len()).map(|t| (e[t] - e[t - 1]).powi(2)).sum();
+    differences / fit.ssr
+}
+
+// Returns (F, p, q): adds fitted^2 .. fitted^power to the design and F tests the q = power - 1
+// added terms
+pub fn reset_rs(fit: &OlsFit, power: usize) -> Result<(f64, f64, f64), LinearModelError> {
+    if power < 2 { return Err(LinearModelError::InvalidInputValue); }
+    // rescaling the fitted values keeps high powers well conditioned and does not change the test
+    let largest = fit.fitted_values.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    if largest == 0.0 { return Err(LinearModelError::ZeroVariance); }
+    let scaled = &fit.fitted_values / largest;
+    let k = fit.design.ncols();
+    let mut augmented = Array2::<f64>::zeros((fit.nobs, k + power - 1));
+    augmented.slice_mut(ndarray::s![.., ..k]).assign(&fit.design);
+    for (j, exponent) in (2..=power).enumerate() {
+        augmented.column_mut(k + j).assign(&scaled.mapv(|v| v.powi(exponent as i32)));
+    }
+    let y = &fit.fitted_values + &fit.residuals;
+    let unrestricted = ols_rs(&augmented, &y, false, Solver::Qr)?;
+    let q = (power - 1) as f64;
+    let f = ((fit.ssr - unrestricted.ssr) / q) / (unrestricted.ssr / unrestricted.df_resid);
+    Ok((f, FisherSnedecor::new(q, unrestricted.df_resid).unwrap().sf(f), q))
+}
+
+
+fn fitted_model(model: &LinearRegression) -> Result<&OlsFit, LinearModelError> {
+    model.fit.as_ref().ok_or(LinearModelError::NotFitted)
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (model, kind = "raw"))]
+pub fn residuals<'py>(py: Python<'py>, model: PyRef<LinearRegression>, kind: &str) -> PyResult<&'py PyArray1<f64>> {
+    // kind: raw, standardized (internally studentized) or studentized (externally, leave-one-out)
+    Ok(residuals_rs(fitted_model(&model)?, ResidualKind::parse(kind)?).into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn leverage<'py>(py: Python<'py>, model: PyRef<LinearRegression>) -> PyResult<&'py PyArray1<f64>> {
+    Ok(leverage_rs(fitted_model(&model)?).into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn cooks_distance<'py>(py: Python<'py>, model: PyRef<LinearRegression>) -> PyResult<&'py PyArray1<f64>> {
+    Ok(cooks_distance_rs(fitted_model(&model)?).into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn dffits<'py>(py: Python<'py>, model: PyRef<LinearRegression>) -> PyResult<&'py PyArray1<f64>> {
+    Ok(dffits_rs(fitted_model(&model)?).into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn dfbetas<'py>(py: Python<'py>, model: PyRef<LinearRegression>) -> PyResult<&'py PyArray2<f64>> {
+    // (n, k) array, columns in the order of model.params
+    Ok(dfbetas_rs(fitted_model(&model)?).into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn variance_inflation_factors<'py>(py: Python<'py>, model: PyRef<LinearRegression>) -> PyResult<&'py PyArray1<f64>> {
+    // one value per feature, the intercept excluded
+    Ok(variance_inflation_factors_rs(fitted_model(&model)?)?.into_pyarray(py))
+}
+
+#[pyfunction]
+pub fn breusch_pagan_test(model: PyRef<LinearRegression>) -> PyResult<TestResult> {
+    // the model needs an intercept, or a constant column when fit without one
+    let (lm, p, df) = breusch_pagan_rs(fitted_model(&model)?)?;
+    Ok(TestResult::new("Breusch-Pagan test", lm, p).with_df(df))
+}
+
+#[pyfunction]
+pub fn white_test(model: PyRef<LinearRegression>) -> PyResult<TestResult> {
+    // the model needs an intercept, or a constant column when fit without one
+    let (lm, p, df) = white_rs(fitted_model(&model)?)?;
+    Ok(TestResult::new("White's test", lm, p).with_df(df))
+}
+
+#[pyfunction]
+pub fn durbin_watson(model: PyRef<LinearRegression>) -> PyResult<f64> {
+    Ok(durbin_watson_rs(fitted_model(&model)?))
+}
+
+#[pyfunction]
+#[pyo3(signature = (model, power = 3))]
+pub fn reset_test(model: PyRef<LinearRegression>, power: usize) -> PyResult<TestResult> {
+    // df is the numerator degrees of freedom, power - 1
+    let (f, p, q) = reset_rs(fitted_model(&model)?, power)?;
+    Ok(TestResult::new("Ramsey's RESET test", f, p).with_df(q))
+}
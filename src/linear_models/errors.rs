@@ -1,55 +1,75 @@
-// // Errors for Statistics files: *_statics.rs
-// // This needs to be completly remand for linear models
-//
-// use std::fmt::{Debug, Formatter, Result};
-// use pyo3::PyErr;
-// use pyo3::exceptions::PyValueError;
-//
-// #[derive(Debug, PartialEq)]
-// pub enum LinearModelError{
-//     EmptyDataSet,
-//     InvalidInputValue,
-//     InconsistentLength,
-//     InvalidFeatures,
-//     ZeroVariance
-// }
-//
-// impl std::fmt::Display for StatsError {
-//     fn fmt(&self, f: &mut Formatter) -> Result {
-//         write!(
-//             f,
-//             "{}",
-//             match self {
-//                 LinearModelError::EmptyDataSet => "Input data array is empty, cannot perform operation.",
-//                 LinearModelError::InvalidInputValue => "Input contains invalid values (e.g., NaN or Infinity).",
-//                 LinearModelError::InconsistentLength => "Input arrays length do not match",
-//                 LinearModelError::InvalidFeatures => "Input matrix has too many or too few features",
-//                 LinearModelError::ZeroVariance => "Cannot compute: one or both variables have zero variance."
-//             }
-//         )
-//     }
-// }
-//
-//
-// impl From<LinearModelError> for PyErr {
-//     fn from(err: LinearModelError) -> PyErr {
-//         match err {
-//             LinearModelError::EmptyDataSet => PyValueError::new_err(err.to_string()),
-//             LinearModelError::InvalidInputValue => PyValueError::new_err(err.to_string()),
-//             LinearModelError::InconsistentLength => PyValueError::new_err(err.to_string()),
-//             LinearModelError::InvalidFeatures => PyValueError::new_err(err.to_string()),
-//             LinearModelError::ZeroVariance => PyValueError::new_err(err.to_string()),
-//         }
-//     }
-// }
-//
-//
-// // impl Debug for StatsError {
-// //     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-// //         match self {
-// //             StatsError::EmptyDataSet => write!(f, "StatsError::EmptyDataSet"),
-// //             StatsError::InvalidInputValue => write!(f, "StatsError::InvalidInputValue"),
-// //             StatsError::InconsistentLength => write!(f, "StatsError::InconsistentLength")
-// //         }
-// //     }
-// // }
+// Errors for the linear models
+// Numerical failures get their own python exception classes so callers can catch a singular
+// design or a non-converged fit without parsing messages; input problems stay ValueErrors.
+
+use std::fmt::{Formatter, Result};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::PyErr;
+
+create_exception!(wemburs, SingularMatrixError, PyValueError);
+create_exception!(wemburs, RankDeficiencyError, PyValueError);
+create_exception!(wemburs, ConvergenceError, PyRuntimeError);
+create_exception!(wemburs, ShapeMismatchError, PyValueError);
+create_exception!(wemburs, NotFittedError, PyRuntimeError);
+
+#[derive(Debug, PartialEq)]
+pub enum LinearModelError{
+    EmptyDataSet,
+    InvalidInputValue,
+    ShapeMismatch,
+    ZeroVariance,
+    InsufficientGroups,
+    InsufficientObservations,
+    SingularMatrix,
+    RankDeficient,
+    NotConverged,
+    NotFitted,
+    PenalizedInference,
+    InvalidFormula,
+    Conversion
+}
+
+impl std::fmt::Display for LinearModelError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                LinearModelError::EmptyDataSet => "Input data array is empty, cannot perform operation.",
+                LinearModelError::InvalidInputValue => "Input contains invalid values (e.g., NaN or Infinity).",
+                LinearModelError::ShapeMismatch => "Input arrays do not have matching shapes.",
+                LinearModelError::ZeroVariance => "Cannot compute: the target has zero variance.",
+                LinearModelError::InsufficientGroups => "The target needs at least two distinct classes.",
+                LinearModelError::InsufficientObservations => "Fewer observations than parameters, the model cannot be estimated.",
+                LinearModelError::SingularMatrix => "Design matrix is singular, the normal equations have no unique solution.",
+                LinearModelError::RankDeficient => "Design matrix does not have full column rank, some features are linearly dependent.",
+                LinearModelError::NotConverged => "Estimation did not converge: the iteration limit was reached or the estimates diverge (e.g. perfectly separated classes).",
+                LinearModelError::NotFitted => "Model is not fitted yet, call fit first.",
+                LinearModelError::PenalizedInference => "Standard errors and tests are only available for unpenalized fits.",
+                LinearModelError::InvalidFormula => "Formula is malformed or refers to a column that is not in the data.",
+                LinearModelError::Conversion => "Cannot convert input array to numeric type."
+            }
+        )
+    }
+}
+
+impl From<LinearModelError> for PyErr {
+    fn from(err: LinearModelError) -> PyErr {
+        match err {
+            LinearModelError::EmptyDataSet => PyValueError::new_err(err.to_string()),
+            LinearModelError::InvalidInputValue => PyValueError::new_err(err.to_string()),
+            LinearModelError::ShapeMismatch => ShapeMismatchError::new_err(err.to_string()),
+            LinearModelError::ZeroVariance => PyValueError::new_err(err.to_string()),
+            LinearModelError::InsufficientGroups => PyValueError::new_err(err.to_string()),
+            LinearModelError::InsufficientObservations => PyValueError::new_err(err.to_string()),
+            LinearModelError::SingularMatrix => SingularMatrixError::new_err(err.to_string()),
+            LinearModelError::RankDeficient => RankDeficiencyError::new_err(err.to_string()),
+            LinearModelError::NotConverged => ConvergenceError::new_err(err.to_string()),
+            LinearModelError::NotFitted => NotFittedError::new_err(err.to_string()),
+            LinearModelError::PenalizedInference => PyValueError::new_err(err.to_string()),
+            LinearModelError::InvalidFormula => PyValueError::new_err(err.to_string()),
+            LinearModelError::Conversion => PyValueError::new_err(err.to_string())
+        }
+    }
+}
@@ -0,0 +1,842 @@
+// Wilkinson / patsy style model formulas
+// Formula -- parses "y ~ x1 + C(group) + x1:x2 + np.log(x3)", builds the response and design matrix
+//            from a polars DataFrame and fits or predicts with any linear_models estimator
+//
+// Terms: a + b adds, a - b removes, a:b interacts and a * b expands to a + b + a:b, with parentheses
+// for grouping. 1 and 0 (or - 1) switch the intercept on and off, which sets the estimator's
+// fit_intercept rather than adding a column.
+// Factors: column names, C(x), C(x, Treatment), C(x, Treatment(reference)) and C(x, Sum)
+// categoricals, the functions log, log10, log2, log1p, exp, sqrt and abs (with or without np.),
+// I(...) arithmetic with + - * / ** and Q("name") for column names that are not identifiers.
+// Non-numeric columns are treatment coded without C().
+//
+// As in patsy: rows with a missing value or NaN in any variable used are dropped, terms are ordered
+// by degree, a categorical factor is coded in full rank unless the rest of its term is already in
+// the model (so g without an intercept, or x:g without x, keeps every level), and new data is coded
+// with the levels learned by the first design_matrices() call.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{Array1, Array2};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::ols::LinearRegression;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    Log,
+    Log10,
+    Log2,
+    Log1p,
+    Exp,
+    Sqrt,
+    Abs,
+}
+
+impl Transform {
+    fn parse(name: &str) -> Result<Transform, LinearModelError> {
+        let name = name.strip_prefix("np.").or_else(|| name.strip_prefix("numpy.")).unwrap_or(name);
+        match name {
+            "log" => Ok(Transform::Log),
+            "log10" => Ok(Transform::Log10),
+            "log2" => Ok(Transform::Log2),
+            "log1p" => Ok(Transform::Log1p),
+            "exp" => Ok(Transform::Exp),
+            "sqrt" => Ok(Transform::Sqrt),
+            "abs" => Ok(Transform::Abs),
+            _ => Err(LinearModelError::InvalidFormula),
+        }
+    }
+
+    fn apply(&self, v: f64) -> f64 {
+        match self {
+            Transform::Log => v.ln(),
+            Transform::Log10 => v.log10(),
+            Transform::Log2 => v.log2(),
+            Transform::Log1p => v.ln_1p(),
+            Transform::Exp => v.exp(),
+            Transform::Sqrt => v.sqrt(),
+            Transform::Abs => v.abs(),
+        }
+    }
+}
+
+// Numeric expression over columns
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Column(String),
+    Number(f64),
+    Call(Transform, Box<Expr>),
+    // operator one of + - * / ^
+    Binary(char, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+}
+
+impl Expr {
+    fn columns(&self, names: &mut Vec<String>) {
+        match self {
+            Expr::Column(name) => if !names.contains(name) { names.push(name.clone()) },
+            Expr::Number(_) => {}
+            Expr::Call(_, inner) | Expr::Negate(inner) => inner.columns(names),
+            Expr::Binary(_, left, right) => {
+                left.columns(names);
+                right.columns(names);
+            }
+        }
+    }
+
+    // Missing values come back as NaN
+    fn evaluate(&self, data: &DataFrame) -> Result<Vec<f64>, LinearModelError> {
+        match self {
+            Expr::Column(name) => match column_rs(data, name)? {
+                Values::Numeric(values) => Ok(values),
+                Values::Labels(_) => Err(LinearModelError::InvalidInputValue),
+            },
+            Expr::Number(v) => Ok(vec![*v; data.height()]),
+            Expr::Call(transform, inner) => Ok(inner.evaluate(data)?.into_iter().map(|v| transform.apply(v)).collect()),
+            Expr::Negate(inner) => Ok(inner.evaluate(data)?.into_iter().map(|v| -v).collect()),
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (left.evaluate(data)?, right.evaluate(data)?);
+                Ok(left.iter().zip(right.iter()).map(|(a, b)| match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(*b),
+                }).collect())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coding {
+    // dummies for every level but the reference, the first level by default
+    Treatment(Option<String>),
+    // deviations from the mean of the levels, the last level coded -1
+    Sum,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Factor {
+    // numeric columns enter as they are, other columns treatment coded
+    Variable(String),
+    Numeric { label: String, expr: Expr },
+    Categorical { label: String, variable: String, coding: Coding },
+}
+
+impl Factor {
+    pub fn label(&self) -> &str {
+        match self {
+            Factor::Variable(name) => name,
+            Factor::Numeric { label, .. } | Factor::Categorical { label, .. } => label,
+        }
+    }
+}
+
+// A term is the interaction of its factors, the intercept having none
+pub type Term = Vec<Factor>;
+
+fn term_key(term: &Term) -> Vec<String> {
+    let mut labels: Vec<String> = term.iter().map(|f| f.label().to_string()).collect();
+    labels.sort();
+    labels
+}
+
+fn term_label(term: &Term) -> String {
+    term.iter().map(|f| f.label()).collect::<Vec<&str>>().join(":")
+}
+
+// Terms of a sub-formula, and whether it switches the intercept on or off
+#[derive(Debug, Clone, Default)]
+struct TermSet {
+    terms: Vec<Term>,
+    intercept: Option<bool>,
+}
+
+impl TermSet {
+    fn add(&mut self, term: Term) {
+        if !self.terms.iter().any(|t| term_key(t) == term_key(&term)) { self.terms.push(term); }
+    }
+
+    fn interact(&self, other: &TermSet) -> Result<TermSet, LinearModelError> {
+        if self.intercept.is_some() || other.intercept.is_some() { return Err(LinearModelError::InvalidFormula); }
+        let mut result = TermSet::default();
+        for left in &self.terms {
+            for right in &other.terms {
+                let mut term = left.clone();
+                for factor in right {
+                    if !term.iter().any(|f| f.label() == factor.label()) { term.push(factor.clone()); }
+                }
+                result.add(term);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Number(f64),
+    Str(String),
+    Op(&'static str),
+}
+
+// Tokens with their byte span in the formula, so factor labels can quote the source
+fn tokenize(text: &str) -> Result<Vec<(Token, usize, usize)>, LinearModelError> {
+    const OPERATORS: [&str; 12] = ["**", "~", "+", "-", "*", "/", ":", "(", ")", ",", "=", "^"];
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(text.len(), |(offset, _)| *offset);
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|(_, d)| d.is_ascii_digit())) {
+            let mut j = i;
+            while j < chars.len() && (chars[j].1.is_ascii_digit() || chars[j].1 == '.'
+                || ((chars[j].1 == 'e' || chars[j].1 == 'E') && j + 1 < chars.len())
+                || ((chars[j].1 == '+' || chars[j].1 == '-') && matches!(chars[j - 1].1, 'e' | 'E'))) {
+                j += 1;
+            }
+            let number = text[start..end_of(j)].parse::<f64>().map_err(|_| LinearModelError::InvalidFormula)?;
+            tokens.push((Token::Number(number), start, end_of(j)));
+            i = j;
+        } else if c.is_alphabetic() || c == '_' {
+            let mut j = i;
+            while j < chars.len() && (chars[j].1.is_alphanumeric() || chars[j].1 == '_' || chars[j].1 == '.') {
+                j += 1;
+            }
+            tokens.push((Token::Name(text[start..end_of(j)].to_string()), start, end_of(j)));
+            i = j;
+        } else if c == '"' || c == '\'' {
+            let close = (i + 1..chars.len()).find(|&j| chars[j].1 == c).ok_or(LinearModelError::InvalidFormula)?;
+            tokens.push((Token::Str(text[end_of(i + 1)..chars[close].0].to_string()), start, end_of(close + 1)));
+            i = close + 1;
+        } else {
+            let op = OPERATORS.iter().find(|op| text[start..].starts_with(**op)).ok_or(LinearModelError::InvalidFormula)?;
+            tokens.push((Token::Op(op), start, start + op.len()));
+            i += op.chars().count();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<(Token, usize, usize)>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _, _)| token)
+    }
+
+    fn is_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Token::Op(o)) if *o == op)
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), LinearModelError> {
+        if !self.is_op(op) { return Err(LinearModelError::InvalidFormula); }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Token, LinearModelError> {
+        let token = self.peek().cloned().ok_or(LinearModelError::InvalidFormula)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    // Source text from token `first` up to the current position, whitespace normalized
+    fn source_since(&self, first: usize) -> String {
+        let (start, end) = (self.tokens[first].1, self.tokens[self.position - 1].2);
+        self.text[start..end].split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    // sum := [+|-] product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<TermSet, LinearModelError> {
+        let mut result = TermSet::default();
+        let mut positive = true;
+        if self.is_op("+") || self.is_op("-") { positive = self.next()? == Token::Op("+"); }
+        loop {
+            let item = self.product()?;
+            if positive {
+                for term in item.terms { result.add(term); }
+            } else {
+                let removed: Vec<Vec<String>> = item.terms.iter().map(term_key).collect();
+                result.terms.retain(|t| !removed.contains(&term_key(t)));
+            }
+            if let Some(intercept) = item.intercept { result.intercept = Some(intercept == positive); }
+            if self.is_op("+") || self.is_op("-") {
+                positive = self.next()? == Token::Op("+");
+            } else {
+                return Ok(result);
+            }
+        }
+    }
+
+    // product := interaction ('*' interaction)*, a * b being a + b + a:b
+    fn product(&mut self) -> Result<TermSet, LinearModelError> {
+        let mut result = self.interaction()?;
+        while self.is_op("*") {
+            self.position += 1;
+            let right = self.interaction()?;
+            let crossed = result.interact(&right)?;
+            for term in right.terms.into_iter().chain(crossed.terms) { result.add(term); }
+        }
+        Ok(result)
+    }
+
+    // interaction := atom (':' atom)*
+    fn interaction(&mut self) -> Result<TermSet, LinearModelError> {
+        let mut result = self.atom()?;
+        while self.is_op(":") {
+            self.position += 1;
+            result = result.interact(&self.atom()?)?;
+        }
+        Ok(result)
+    }
+
+    fn atom(&mut self) -> Result<TermSet, LinearModelError> {
+        match self.peek() {
+            Some(Token::Op("(")) => {
+                self.position += 1;
+                let inner = self.sum()?;
+                self.expect_op(")")?;
+                Ok(inner)
+            }
+            Some(Token::Number(v)) if *v == 0.0 || *v == 1.0 => {
+                let intercept = *v == 1.0;
+                self.position += 1;
+                Ok(TermSet { terms: vec![], intercept: Some(intercept) })
+            }
+            _ => Ok(TermSet { terms: vec![vec![self.factor()?]], intercept: None }),
+        }
+    }
+
+    fn factor(&mut self) -> Result<Factor, LinearModelError> {
+        let first = self.position;
+        let Token::Name(name) = self.next()? else { return Err(LinearModelError::InvalidFormula) };
+        if !self.is_op("(") { return Ok(Factor::Variable(name)); }
+        self.position += 1;
+        let factor = match name.as_str() {
+            "C" => {
+                let variable = self.variable_name()?;
+                let coding = if self.is_op(",") {
+                    self.position += 1;
+                    self.coding()?
+                } else {
+                    Coding::Treatment(None)
+                };
+                self.expect_op(")")?;
+                return Ok(Factor::Categorical { label: self.source_since(first), variable, coding });
+            }
+            "Q" => {
+                let Token::Str(column) = self.next()? else { return Err(LinearModelError::InvalidFormula) };
+                self.expect_op(")")?;
+                return Ok(Factor::Variable(column));
+            }
+            "I" => self.arithmetic()?,
+            _ => Expr::Call(Transform::parse(&name)?, Box::new(self.arithmetic()?)),
+        };
+        self.expect_op(")")?;
+        Ok(Factor::Numeric { label: self.source_since(first), expr: factor })
+    }
+
+    fn variable_name(&mut self) -> Result<String, LinearModelError> {
+        match self.next()? {
+            Token::Name(name) if name == "Q" && self.is_op("(") => {
+                self.position += 1;
+                let Token::Str(column) = self.next()? else { return Err(LinearModelError::InvalidFormula) };
+                self.expect_op(")")?;
+                Ok(column)
+            }
+            Token::Name(name) => Ok(name),
+            _ => Err(LinearModelError::InvalidFormula),
+        }
+    }
+
+    // Treatment, Treatment(level), Treatment(reference=level) or Sum
+    fn coding(&mut self) -> Result<Coding, LinearModelError> {
+        let Token::Name(name) = self.next()? else { return Err(LinearModelError::InvalidFormula) };
+        match name.as_str() {
+            "Sum" => Ok(Coding::Sum),
+            "Treatment" => {
+                if !self.is_op("(") { return Ok(Coding::Treatment(None)); }
+                self.position += 1;
+                if matches!(self.tokens.get(self.position + 1), Some((Token::Op("="), _, _))) {
+                    if self.next()? != Token::Name("reference".to_string()) { return Err(LinearModelError::InvalidFormula); }
+                    self.position += 1;
+                }
+                let reference = match self.next()? {
+                    Token::Str(level) | Token::Name(level) => level,
+                    Token::Number(level) => format!("{}", level),
+                    Token::Op(_) => return Err(LinearModelError::InvalidFormula),
+                };
+                self.expect_op(")")?;
+                Ok(Coding::Treatment(Some(reference)))
+            }
+            _ => Err(LinearModelError::InvalidFormula),
+        }
+    }
+
+    // arithmetic := term (('+' | '-') term)*
+    fn arithmetic(&mut self) -> Result<Expr, LinearModelError> {
+        let mut result = self.arithmetic_term()?;
+        while self.is_op("+") || self.is_op("-") {
+            let op = if self.next()? == Token::Op("+") { '+' } else { '-' };
+            result = Expr::Binary(op, Box::new(result), Box::new(self.arithmetic_term()?));
+        }
+        Ok(result)
+    }
+
+    fn arithmetic_term(&mut self) -> Result<Expr, LinearModelError> {
+        let mut result = self.arithmetic_unary()?;
+        while self.is_op("*") || self.is_op("/") {
+            let op = if self.next()? == Token::Op("*") { '*' } else { '/' };
+            result = Expr::Binary(op, Box::new(result), Box::new(self.arithmetic_unary()?));
+        }
+        Ok(result)
+    }
+
+    fn arithmetic_unary(&mut self) -> Result<Expr, LinearModelError> {
+        if self.is_op("-") {
+            self.position += 1;
+            return Ok(Expr::Negate(Box::new(self.arithmetic_unary()?)));
+        }
+        let base = self.arithmetic_atom()?;
+        // right associative, binding tighter than a unary minus on its left
+        if self.is_op("**") || self.is_op("^") {
+            self.position += 1;
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.arithmetic_unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn arithmetic_atom(&mut self) -> Result<Expr, LinearModelError> {
+        match self.next()? {
+            Token::Number(v) => Ok(Expr::Number(v)),
+            Token::Op("(") => {
+                let inner = self.arithmetic()?;
+                self.expect_op(")")?;
+                Ok(inner)
+            }
+            Token::Name(name) if self.is_op("(") => {
+                self.position += 1;
+                let expr = if name == "Q" {
+                    let Token::Str(column) = self.next()? else { return Err(LinearModelError::InvalidFormula) };
+                    Expr::Column(column)
+                } else {
+                    Expr::Call(Transform::parse(&name)?, Box::new(self.arithmetic()?))
+                };
+                self.expect_op(")")?;
+                Ok(expr)
+            }
+            Token::Name(name) => Ok(Expr::Column(name)),
+            _ => Err(LinearModelError::InvalidFormula),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaSpec {
+    // source text and expression of the left hand side
+    pub response: Option<(String, Expr)>,
+    pub terms: Vec<Term>,
+    pub intercept: bool,
+}
+
+pub fn parse_formula_rs(formula: &str) -> Result<FormulaSpec, LinearModelError> {
+    let mut parser = Parser { text: formula, tokens: tokenize(formula)?, position: 0 };
+    let response = match parser.tokens.iter().position(|(token, _, _)| *token == Token::Op("~")) {
+        Some(0) => None,
+        Some(_) => {
+            let expr = parser.arithmetic()?;
+            let label = parser.source_since(0);
+            parser.expect_op("~")?;
+            Some((label, expr))
+        }
+        None => return Err(LinearModelError::InvalidFormula),
+    };
+    if response.is_none() { parser.expect_op("~")?; }
+    let rhs = parser.sum()?;
+    if parser.position != parser.tokens.len() { return Err(LinearModelError::InvalidFormula); }
+    let mut terms = rhs.terms;
+    // stable, so terms of equal degree keep the order they were written in
+    terms.sort_by_key(|term| term.len());
+    Ok(FormulaSpec { response, terms, intercept: rhs.intercept.unwrap_or(true) })
+}
+
+impl FormulaSpec {
+    // Every column the formula reads
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = vec![];
+        if let Some((_, expr)) = &self.response { expr.columns(&mut names); }
+        for factor in self.terms.iter().flatten() {
+            match factor {
+                Factor::Variable(name) | Factor::Categorical { variable: name, .. } => {
+                    if !names.contains(name) { names.push(name.clone()); }
+                }
+                Factor::Numeric { expr, .. } => expr.columns(&mut names),
+            }
+        }
+        names
+    }
+}
+
+// Patsy's rule for which categorical factors of a term are coded full rank. A term is split into its
+// numeric factors times every subset of its categorical factors in reduced coding, subsets already
+// spanned by earlier terms (or the intercept) are dropped, and the rest merged back: pieces S and S
+// with one more reduced factor f become S with f in full rank. Returns, per term, its pieces as
+// (position in the term, full rank) of the categorical factors they include.
+fn term_codings_rs(terms: &[Term], is_categorical: impl Fn(&Factor) -> bool, intercept: bool) -> Vec<Vec<Vec<(usize, bool)>>> {
+    let mut spanned: Vec<Vec<String>> = if intercept { vec![vec![]] } else { vec![] };
+    terms.iter().map(|term| {
+        let numeric: Vec<usize> = (0..term.len()).filter(|&i| !is_categorical(&term[i])).collect();
+        let categorical: Vec<usize> = (0..term.len()).filter(|&i| is_categorical(&term[i])).collect();
+        let mut subsets: Vec<Vec<usize>> = (0..1usize << categorical.len())
+            .map(|mask| (0..categorical.len()).filter(|&j| (mask >> j) & 1 == 1).map(|j| categorical[j]).collect())
+            .collect();
+        subsets.sort_by_key(|subset| subset.len());
+
+        let mut pieces: Vec<Vec<(usize, bool)>> = vec![];
+        for subset in subsets {
+            let mut key: Vec<String> = numeric.iter().chain(subset.iter()).map(|&i| term[i].label().to_string()).collect();
+            key.sort();
+            if spanned.contains(&key) { continue; }
+            spanned.push(key);
+            pieces.push(subset.into_iter().map(|i| (i, false)).collect());
+        }
+
+        while let Some((short, long, factor)) = (0..pieces.len())
+            .flat_map(|short| (short + 1..pieces.len()).map(move |long| (short, long)))
+            .find_map(|(short, long)| {
+                let extra: Vec<&(usize, bool)> = pieces[long].iter().filter(|p| !pieces[short].contains(p)).collect();
+                let contained = pieces[short].iter().all(|p| pieces[long].contains(p));
+                match extra.as_slice() {
+                    [(factor, false)] if contained => Some((short, long, *factor)),
+                    _ => None,
+                }
+            }) {
+            for piece in pieces[long].iter_mut().filter(|(position, _)| *position == factor) {
+                piece.1 = true;
+            }
+            pieces.remove(short);
+        }
+        pieces
+    }).collect()
+}
+
+enum Values {
+    Numeric(Vec<f64>),
+    Labels(Vec<Option<String>>),
+}
+
+fn column_rs(data: &DataFrame, name: &str) -> Result<Values, LinearModelError> {
+    let series = data.column(name).map_err(|_| LinearModelError::InvalidFormula)?;
+    if series.dtype().is_numeric() || *series.dtype() == DataType::Boolean {
+        let values = series.cast(&DataType::Float64).map_err(|_| LinearModelError::Conversion)?;
+        let values = values.f64().map_err(|_| LinearModelError::Conversion)?;
+        Ok(Values::Numeric(values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect()))
+    } else {
+        let labels = series.cast(&DataType::Utf8).map_err(|_| LinearModelError::Conversion)?;
+        let labels = labels.utf8().map_err(|_| LinearModelError::Conversion)?;
+        Ok(Values::Labels(labels.into_iter().map(|v| v.map(str::to_string)).collect()))
+    }
+}
+
+// Category labels of a column, numbers written as f64 Display does ("2", "2.5")
+fn labels_rs(data: &DataFrame, name: &str) -> Result<Vec<Option<String>>, LinearModelError> {
+    match column_rs(data, name)? {
+        Values::Labels(labels) => Ok(labels),
+        Values::Numeric(values) => Ok(values.iter().map(|v| if v.is_nan() { None } else { Some(format!("{}", v)) }).collect()),
+    }
+}
+
+// Sorted distinct labels, numerically when every label is a number
+fn sorted_levels(labels: impl Iterator<Item = String>) -> Vec<String> {
+    let mut levels: Vec<String> = vec![];
+    for label in labels {
+        if !levels.contains(&label) { levels.push(label); }
+    }
+    if levels.iter().all(|l| l.parse::<f64>().is_ok()) {
+        levels.sort_by(|a, b| a.parse::<f64>().unwrap().total_cmp(&b.parse::<f64>().unwrap()));
+    } else {
+        levels.sort();
+    }
+    levels
+}
+
+enum FactorValues {
+    Numeric(Vec<f64>),
+    Categorical(Vec<Option<String>>, Coding),
+}
+
+#[derive(Debug, Clone)]
+pub struct DesignMatrices {
+    pub y: Option<Array1<f64>>,
+    // without an intercept column, which the estimator adds when spec.intercept is set
+    pub x: Array2<f64>,
+    pub column_names: Vec<String>,
+    // levels of every categorical factor, by factor label
+    pub levels: Vec<(String, Vec<String>)>,
+    // rows of the data that survived the missing value filter
+    pub rows: Vec<usize>,
+}
+
+// Codes `data` with the given levels, or learns them from it when `levels` is None. The response
+// is only evaluated (and required) when with_response is set.
+pub fn design_matrices_rs(spec: &FormulaSpec, data: &DataFrame, levels: Option<&[(String, Vec<String>)]>,
+                          with_response: bool) -> Result<DesignMatrices, LinearModelError> {
+    let n = data.height();
+    let response = match (&spec.response, with_response) {
+        (Some((_, expr)), true) => Some(expr.evaluate(data)?),
+        (None, true) => return Err(LinearModelError::InvalidFormula),
+        (_, false) => None,
+    };
+
+    let mut factors: Vec<(String, FactorValues)> = vec![];
+    for factor in spec.terms.iter().flatten() {
+        if factors.iter().any(|(label, _)| label == factor.label()) { continue; }
+        let values = match factor {
+            Factor::Variable(name) => match column_rs(data, name)? {
+                Values::Numeric(values) => FactorValues::Numeric(values),
+                Values::Labels(labels) => FactorValues::Categorical(labels, Coding::Treatment(None)),
+            },
+            Factor::Numeric { expr, .. } => FactorValues::Numeric(expr.evaluate(data)?),
+            Factor::Categorical { variable, coding, .. } => FactorValues::Categorical(labels_rs(data, variable)?, coding.clone()),
+        };
+        factors.push((factor.label().to_string(), values));
+    }
+
+    let rows: Vec<usize> = (0..n).filter(|&i| {
+        response.as_ref().is_none_or(|y| !y[i].is_nan()) && factors.iter().all(|(_, values)| match values {
+            FactorValues::Numeric(v) => !v[i].is_nan(),
+            FactorValues::Categorical(labels, _) => labels[i].is_some(),
+        })
+    }).collect();
+    if rows.is_empty() { return Err(LinearModelError::EmptyDataSet); }
+
+    let mut learned: Vec<(String, Vec<String>)> = vec![];
+    for (label, values) in &factors {
+        let FactorValues::Categorical(labels, coding) = values else { continue };
+        let factor_levels = match levels {
+            Some(known) => {
+                let (_, known) = known.iter().find(|(l, _)| l == label).ok_or(LinearModelError::InvalidFormula)?;
+                // new data may not bring categories the model never saw
+                if rows.iter().any(|&i| !known.contains(labels[i].as_ref().unwrap())) { return Err(LinearModelError::InvalidInputValue); }
+                known.clone()
+            }
+            None => sorted_levels(rows.iter().map(|&i| labels[i].clone().unwrap())),
+        };
+        if let Coding::Treatment(Some(reference)) = coding {
+            if !factor_levels.contains(reference) { return Err(LinearModelError::InvalidInputValue); }
+        }
+        learned.push((label.clone(), factor_levels));
+    }
+
+    let is_categorical = |factor: &Factor| learned.iter().any(|(label, _)| label == factor.label());
+    let m = rows.len();
+    let mut columns: Vec<(String, Vec<f64>)> = vec![];
+    for (term, pieces) in spec.terms.iter().zip(term_codings_rs(&spec.terms, is_categorical, spec.intercept)) {
+        for piece in pieces {
+            let mut piece_columns: Vec<(String, Vec<f64>)> = vec![(String::new(), vec![1.0; m])];
+            for (i, factor) in term.iter().enumerate() {
+                let (_, values) = factors.iter().find(|(label, _)| label == factor.label()).unwrap();
+                let factor_columns: Vec<(String, Vec<f64>)> = match values {
+                    FactorValues::Numeric(v) => vec![(factor.label().to_string(), rows.iter().map(|&i| v[i]).collect())],
+                    FactorValues::Categorical(labels, coding) => {
+                        // categorical factors outside this piece are spanned by other columns
+                        let Some(&(_, full_rank)) = piece.iter().find(|(position, _)| *position == i) else { continue };
+                        let (_, factor_levels) = learned.iter().find(|(label, _)| label == factor.label()).unwrap();
+                        let indicator = |level: &str| -> Vec<f64> {
+                            rows.iter().map(|&i| if labels[i].as_deref() == Some(level) { 1.0 } else { 0.0 }).collect()
+                        };
+                        let label = factor.label();
+                        match coding {
+                            _ if full_rank => factor_levels.iter()
+                                .map(|level| (format!("{}[{}]", label, level), indicator(level)))
+                                .collect(),
+                            Coding::Treatment(reference) => {
+                                let reference = reference.clone().unwrap_or(factor_levels[0].clone());
+                                factor_levels.iter().filter(|level| **level != reference)
+                                    .map(|level| (format!("{}[T.{}]", label, level), indicator(level)))
+                                    .collect()
+                            }
+                            Coding::Sum => {
+                                let last = indicator(factor_levels.last().unwrap());
+                                factor_levels[..factor_levels.len() - 1].iter().map(|level| {
+                                    let values = indicator(level).iter().zip(last.iter()).map(|(a, b)| a - b).collect();
+                                    (format!("{}[S.{}]", label, level), values)
+                                }).collect()
+                            }
+                        }
+                    }
+                };
+                piece_columns = piece_columns.iter().flat_map(|(name, values)| {
+                    factor_columns.iter().map(move |(factor_name, factor_values)| {
+                        let joined = if name.is_empty() { factor_name.clone() } else { format!("{}:{}", name, factor_name) };
+                        (joined, values.iter().zip(factor_values.iter()).map(|(a, b)| a * b).collect())
+                    })
+                }).collect();
+            }
+            // a piece left with no factors at all stands in for the missing intercept
+            for (name, _) in piece_columns.iter_mut().filter(|(name, _)| name.is_empty()) {
+                *name = "Intercept".to_string();
+            }
+            columns.extend(piece_columns);
+        }
+    }
+
+    let mut x = Array2::<f64>::zeros((m, columns.len()));
+    for (j, (_, values)) in columns.iter().enumerate() {
+        x.column_mut(j).assign(&Array1::from(values.clone()));
+    }
+    Ok(DesignMatrices {
+        y: response.map(|y| rows.iter().map(|&i| y[i]).collect()),
+        x,
+        column_names: columns.into_iter().map(|(name, _)| name).collect(),
+        levels: learned,
+        rows,
+    })
+}
+
+// polars DataFrame holding the named columns of a polars (or pandas) DataFrame. Columns holding
+// any string become string columns, everything else Float64 with None as null.
+fn dataframe_from_py(data: &PyAny, names: &[String]) -> PyResult<DataFrame> {
+    let mut columns = vec![];
+    for name in names {
+        let values: Vec<&PyAny> = data.get_item(name.as_str())?.call_method0("to_list")?.extract()?;
+        let series = if values.iter().any(|v| v.is_instance_of::<PyString>()) {
+            let labels = values.iter()
+                .map(|v| if v.is_none() { Ok(None) } else { v.str().map(|s| Some(s.to_string())) })
+                .collect::<PyResult<Vec<Option<String>>>>()?;
+            Series::new(name, labels)
+        } else {
+            let numbers = match values.iter().map(|v| if v.is_none() { Ok(None) } else { v.extract::<f64>().map(Some) })
+                .collect::<PyResult<Vec<Option<f64>>>>() {
+                Ok(numbers) => numbers,
+                Err(_e) => return Err(LinearModelError::Conversion.into()),
+            };
+            Series::new(name, numbers)
+        };
+        columns.push(series);
+    }
+    match DataFrame::new(columns) {
+        Ok(frame) => Ok(frame),
+        Err(_e) => Err(LinearModelError::ShapeMismatch.into()),
+    }
+}
+
+
+/// Pyclass
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Formula {
+    #[pyo3(get)]
+    pub formula: String,
+    pub spec: FormulaSpec,
+    // learned by the first design_matrices() call
+    pub levels: Option<Vec<(String, Vec<String>)>>,
+    pub column_names: Vec<String>,
+}
+
+impl Formula {
+    pub fn new_rs(formula: &str) -> Result<Formula, LinearModelError> {
+        Ok(Formula { formula: formula.to_string(), spec: parse_formula_rs(formula)?, levels: None, column_names: vec![] })
+    }
+
+    // Response and design of training data, remembering its levels and column names
+    pub fn design_matrices_rs(&mut self, data: &DataFrame) -> Result<(Array1<f64>, Array2<f64>), LinearModelError> {
+        let design = design_matrices_rs(&self.spec, data, self.levels.as_deref(), true)?;
+        self.levels = Some(design.levels);
+        self.column_names = design.column_names;
+        Ok((design.y.unwrap(), design.x))
+    }
+
+    // Design of new data with the learned levels
+    pub fn design_matrix_rs(&self, data: &DataFrame) -> Result<Array2<f64>, LinearModelError> {
+        let levels = self.levels.as_deref().ok_or(LinearModelError::NotFitted)?;
+        Ok(design_matrices_rs(&self.spec, data, Some(levels), false)?.x)
+    }
+}
+
+#[pymethods]
+impl Formula {
+    #[new]
+    fn new(formula: &str) -> PyResult<Self> {
+        Ok(Formula::new_rs(formula)?)
+    }
+
+    #[getter]
+    fn response(&self) -> Option<String> {
+        self.spec.response.as_ref().map(|(label, _)| label.clone())
+    }
+
+    #[getter]
+    fn intercept(&self) -> bool {
+        self.spec.intercept
+    }
+
+    #[getter]
+    fn terms(&self) -> Vec<String> {
+        self.spec.terms.iter().map(term_label).collect()
+    }
+
+    #[getter]
+    fn column_names(&self) -> PyResult<Vec<String>> {
+        if self.levels.is_none() { return Err(LinearModelError::NotFitted.into()); }
+        Ok(self.column_names.clone())
+    }
+
+    fn design_matrices<'py>(&mut self, py: Python<'py>, data: &PyAny) -> PyResult<(&'py PyArray1<f64>, &'py PyArray2<f64>)> {
+        // (y, x) for a polars DataFrame; x has no intercept column. Learns the categorical levels
+        // on the first call and reuses them afterwards.
+        let frame = dataframe_from_py(data, &self.spec.variables())?;
+        let (y, x) = self.design_matrices_rs(&frame)?;
+        Ok((y.into_pyarray(py), x.into_pyarray(py)))
+    }
+
+    fn design_matrix<'py>(&self, py: Python<'py>, data: &PyAny) -> PyResult<&'py PyArray2<f64>> {
+        // x for new data, which needs no response column
+        let mut names = vec![];
+        for factor in self.spec.terms.iter().flatten() {
+            let mut factor_spec = self.spec.clone();
+            factor_spec.response = None;
+            factor_spec.terms = vec![vec![factor.clone()]];
+            for name in factor_spec.variables() {
+                if !names.contains(&name) { names.push(name); }
+            }
+        }
+        Ok(self.design_matrix_rs(&dataframe_from_py(data, &names)?)?.into_pyarray(py))
+    }
+
+    fn fit<'py>(&mut self, py: Python<'py>, model: &'py PyAny, data: &PyAny) -> PyResult<&'py PyAny> {
+        // Sets model.fit_intercept from the formula and fits it; LinearRegression also gets the
+        // column names for its summary. Returns the fitted model.
+        let (y, x) = self.design_matrices(py, data)?;
+        model.setattr("fit_intercept", self.spec.intercept)?;
+        let kwargs = PyDict::new(py);
+        if model.is_instance_of::<LinearRegression>() { kwargs.set_item("feature_names", self.column_names.clone())?; }
+        model.call_method("fit", (x, y), Some(kwargs))
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, model: &'py PyAny, data: &PyAny) -> PyResult<&'py PyAny> {
+        model.call_method1("predict", (self.design_matrix(py, data)?,))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Formula('{}')", self.formula)
+    }
+}
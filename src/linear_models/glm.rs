@@ -0,0 +1,758 @@
+// Generalized linear models fit by iteratively reweighted least squares
+// GLM -- Gaussian, Binomial, Poisson, Gamma, InverseGaussian, NegativeBinomial(alpha) and
+//        Tweedie(var_power) families with canonical or non-canonical links, offsets, exposure
+//        (log link only) and frequency weights
+//
+// Links: identity, log, logit, probit, cloglog, inverse, inverse_squared, sqrt and power(p).
+// Binomial responses are proportions in [0, 1]. The dispersion is fixed at one for the binomial,
+// poisson and negative binomial families and estimated as Pearson chi^2 / df_resid otherwise.
+// Results follow statsmodels: Wald z inference, deviance, null deviance, Pearson chi^2,
+// log-likelihood (not available for Tweedie), AIC, BIC and response / pearson / deviance residuals.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{s, Array1, Array2};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+use statrs::function::gamma::ln_gamma;
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::linalg::{upper_triangular_inverse_rs, QrDecomposition};
+use crate::linear_models::ols::add_intercept_rs;
+use crate::linear_models::{features_from_py, LinearModel};
+use crate::utils::from_pyarray1;
+use crate::validate_linear_model_input;
+
+
+// Fitted means are kept this far inside the boundary of the family's mean space
+const MU_EPSILON: f64 = 1e-10;
+// Halvings of an IRLS step that produced a non-finite deviance before giving up
+const MAX_STEP_HALVINGS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Link {
+    Identity,
+    Log,
+    Logit,
+    Probit,
+    Cloglog,
+    Inverse,
+    InverseSquared,
+    Sqrt,
+    Power(f64),
+}
+
+impl Link {
+    pub fn parse(link: &str, power: Option<f64>) -> Result<Link, LinearModelError> {
+        match link.to_lowercase().as_str() {
+            "identity" => Ok(Link::Identity),
+            "log" => Ok(Link::Log),
+            "logit" => Ok(Link::Logit),
+            "probit" => Ok(Link::Probit),
+            "cloglog" => Ok(Link::Cloglog),
+            "inverse" => Ok(Link::Inverse),
+            "inverse_squared" => Ok(Link::InverseSquared),
+            "sqrt" => Ok(Link::Sqrt),
+            // power 0 is the log link in the limit
+            "power" => match power {
+                Some(0.0) => Ok(Link::Log),
+                Some(p) if p.is_finite() => Ok(Link::Power(p)),
+                _ => Err(LinearModelError::InvalidInputValue),
+            },
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Link::Identity => "identity".to_string(),
+            Link::Log => "log".to_string(),
+            Link::Logit => "logit".to_string(),
+            Link::Probit => "probit".to_string(),
+            Link::Cloglog => "cloglog".to_string(),
+            Link::Inverse => "inverse".to_string(),
+            Link::InverseSquared => "inverse_squared".to_string(),
+            Link::Sqrt => "sqrt".to_string(),
+            Link::Power(p) => format!("power({})", p),
+        }
+    }
+
+    // eta = g(mu)
+    pub fn link(&self, mu: f64) -> f64 {
+        match self {
+            Link::Identity => mu,
+            Link::Log => mu.ln(),
+            Link::Logit => (mu / (1.0 - mu)).ln(),
+            Link::Probit => Normal::new(0.0, 1.0).unwrap().inverse_cdf(mu),
+            Link::Cloglog => (-(1.0 - mu).ln()).ln(),
+            Link::Inverse => 1.0 / mu,
+            Link::InverseSquared => 1.0 / (mu * mu),
+            Link::Sqrt => mu.sqrt(),
+            Link::Power(p) => mu.powf(*p),
+        }
+    }
+
+    // mu = g^-1(eta)
+    pub fn inverse(&self, eta: f64) -> f64 {
+        match self {
+            Link::Identity => eta,
+            Link::Log => eta.exp(),
+            Link::Logit => 1.0 / (1.0 + (-eta).exp()),
+            Link::Probit => Normal::new(0.0, 1.0).unwrap().cdf(eta),
+            Link::Cloglog => 1.0 - (-eta.exp()).exp(),
+            Link::Inverse => 1.0 / eta,
+            Link::InverseSquared => 1.0 / eta.sqrt(),
+            Link::Sqrt => eta * eta,
+            Link::Power(p) => eta.powf(1.0 / p),
+        }
+    }
+
+    // g'(mu), d eta / d mu
+    pub fn derivative(&self, mu: f64) -> f64 {
+        match self {
+            Link::Identity => 1.0,
+            Link::Log => 1.0 / mu,
+            Link::Logit => 1.0 / (mu * (1.0 - mu)),
+            Link::Probit => {
+                let normal = Normal::new(0.0, 1.0).unwrap();
+                1.0 / normal.pdf(normal.inverse_cdf(mu))
+            }
+            Link::Cloglog => -1.0 / ((1.0 - mu) * (1.0 - mu).ln()),
+            Link::Inverse => -1.0 / (mu * mu),
+            Link::InverseSquared => -2.0 / (mu * mu * mu),
+            Link::Sqrt => 0.5 / mu.sqrt(),
+            Link::Power(p) => p * mu.powf(p - 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Family {
+    Gaussian,
+    Binomial,
+    Poisson,
+    Gamma,
+    InverseGaussian,
+    // var(y) = mu + alpha mu^2
+    NegativeBinomial(f64),
+    // var(y) = phi mu^p
+    Tweedie(f64),
+}
+
+impl Family {
+    pub fn parse(family: &str, alpha: f64, var_power: f64) -> Result<Family, LinearModelError> {
+        match family.to_lowercase().as_str() {
+            "gaussian" | "normal" => Ok(Family::Gaussian),
+            "binomial" => Ok(Family::Binomial),
+            "poisson" => Ok(Family::Poisson),
+            "gamma" => Ok(Family::Gamma),
+            "inverse_gaussian" => Ok(Family::InverseGaussian),
+            "negative_binomial" => {
+                if !alpha.is_finite() || alpha <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+                Ok(Family::NegativeBinomial(alpha))
+            }
+            "tweedie" => {
+                // no tweedie distribution exists for powers strictly between 0 and 1
+                if !var_power.is_finite() || (var_power > 0.0 && var_power < 1.0) { return Err(LinearModelError::InvalidInputValue); }
+                Ok(Family::Tweedie(var_power))
+            }
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Family::Gaussian => "Gaussian".to_string(),
+            Family::Binomial => "Binomial".to_string(),
+            Family::Poisson => "Poisson".to_string(),
+            Family::Gamma => "Gamma".to_string(),
+            Family::InverseGaussian => "InverseGaussian".to_string(),
+            Family::NegativeBinomial(alpha) => format!("NegativeBinomial(alpha={})", alpha),
+            Family::Tweedie(p) => format!("Tweedie(var_power={})", p),
+        }
+    }
+
+    // The canonical link, except log for the negative binomial and tweedie (as in statsmodels)
+    pub fn default_link(&self) -> Link {
+        match self {
+            Family::Gaussian => Link::Identity,
+            Family::Binomial => Link::Logit,
+            Family::Poisson | Family::NegativeBinomial(_) | Family::Tweedie(_) => Link::Log,
+            Family::Gamma => Link::Inverse,
+            Family::InverseGaussian => Link::InverseSquared,
+        }
+    }
+
+    pub fn variance(&self, mu: f64) -> f64 {
+        match self {
+            Family::Gaussian => 1.0,
+            Family::Binomial => mu * (1.0 - mu),
+            Family::Poisson => mu,
+            Family::Gamma => mu * mu,
+            Family::InverseGaussian => mu * mu * mu,
+            Family::NegativeBinomial(alpha) => mu + alpha * mu * mu,
+            Family::Tweedie(p) => mu.powf(*p),
+        }
+    }
+
+    fn fixed_scale(&self) -> bool {
+        matches!(self, Family::Binomial | Family::Poisson | Family::NegativeBinomial(_))
+    }
+
+    fn valid_response(&self, y: f64) -> bool {
+        match self {
+            Family::Gaussian => true,
+            Family::Binomial => (0.0..=1.0).contains(&y),
+            Family::Poisson | Family::NegativeBinomial(_) => y >= 0.0,
+            Family::Gamma | Family::InverseGaussian => y > 0.0,
+            Family::Tweedie(p) => if *p == 0.0 { true } else if *p < 2.0 { y >= 0.0 } else { y > 0.0 },
+        }
+    }
+
+    // Keeps a fitted mean inside the family's mean space
+    fn clamp(&self, mu: f64) -> f64 {
+        match self {
+            Family::Gaussian => mu,
+            Family::Binomial => mu.clamp(MU_EPSILON, 1.0 - MU_EPSILON),
+            Family::Tweedie(p) if *p == 0.0 => mu,
+            _ => mu.max(MU_EPSILON),
+        }
+    }
+
+    // Unit deviance d(y, mu), summing to the deviance with the weights
+    pub fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        // y ln(y / mu), zero at y = 0
+        let xlogy = |y: f64, ratio: f64| if y == 0.0 { 0.0 } else { y * ratio.ln() };
+        match self {
+            Family::Gaussian => (y - mu).powi(2),
+            Family::Binomial => 2.0 * (xlogy(y, y / mu) + xlogy(1.0 - y, (1.0 - y) / (1.0 - mu))),
+            Family::Poisson => 2.0 * (xlogy(y, y / mu) - (y - mu)),
+            Family::Gamma => 2.0 * (-(y / mu).ln() + (y - mu) / mu),
+            Family::InverseGaussian => (y - mu).powi(2) / (y * mu * mu),
+            Family::NegativeBinomial(alpha) => {
+                2.0 * (xlogy(y, y / mu) - (y + 1.0 / alpha) * ((1.0 + alpha * y) / (1.0 + alpha * mu)).ln())
+            }
+            Family::Tweedie(p) => match *p {
+                0.0 => Family::Gaussian.unit_deviance(y, mu),
+                1.0 => Family::Poisson.unit_deviance(y, mu),
+                2.0 => Family::Gamma.unit_deviance(y, mu),
+                p => {
+                    let first = if y == 0.0 { 0.0 } else { y.powf(2.0 - p) / ((1.0 - p) * (2.0 - p)) };
+                    2.0 * (first - y * mu.powf(1.0 - p) / (1.0 - p) + mu.powf(2.0 - p) / (2.0 - p))
+                }
+            },
+        }
+    }
+
+    // Log-likelihood contribution of one observation at dispersion `scale`, NaN for the tweedie
+    // family, whose density has no closed form
+    pub fn log_likelihood(&self, y: f64, mu: f64, scale: f64) -> f64 {
+        match self {
+            Family::Gaussian => -0.5 * ((y - mu).powi(2) / scale + (2.0 * std::f64::consts::PI * scale).ln()),
+            Family::Binomial => {
+                let ln = |v: f64| v.max(f64::MIN_POSITIVE).ln();
+                y * ln(mu) + (1.0 - y) * ln(1.0 - mu)
+            }
+            Family::Poisson => (if y == 0.0 { 0.0 } else { y * mu.ln() }) - mu - ln_gamma(y + 1.0),
+            Family::Gamma => {
+                let shape = 1.0 / scale;
+                shape * (shape * y / mu).ln() - shape * y / mu - y.ln() - ln_gamma(shape)
+            }
+            Family::InverseGaussian => {
+                -0.5 * ((y - mu).powi(2) / (y * mu * mu * scale) + (2.0 * std::f64::consts::PI * scale * y.powi(3)).ln())
+            }
+            Family::NegativeBinomial(alpha) => {
+                let size = 1.0 / alpha;
+                ln_gamma(y + size) - ln_gamma(size) - ln_gamma(y + 1.0)
+                    + (if y == 0.0 { 0.0 } else { y * (alpha * mu / (1.0 + alpha * mu)).ln() })
+                    - size * (1.0 + alpha * mu).ln()
+            }
+            Family::Tweedie(_) => f64::NAN,
+        }
+    }
+
+    // IRLS starting means, pulled towards the overall mean so every link is defined
+    fn starting_mu(&self, y: f64, mean: f64) -> f64 {
+        match self {
+            Family::Binomial => (y + 0.5) / 2.0,
+            _ => self.clamp((y + mean) / 2.0),
+        }
+    }
+}
+
+// Result of the IRLS iterations on one design
+struct IrlsResult {
+    params: Array1<f64>,
+    mu: Array1<f64>,
+    eta: Array1<f64>,
+    // (X' W X)^-1 at convergence
+    xtwx_inverse: Array2<f64>,
+    deviance: f64,
+    n_iter: usize,
+}
+
+fn deviance_rs(family: Family, y: &Array1<f64>, mu: &Array1<f64>, weights: &Array1<f64>) -> f64 {
+    y.iter().zip(mu.iter()).zip(weights.iter()).map(|((&y, &mu), &w)| w * family.unit_deviance(y, mu)).sum()
+}
+
+// Each step regresses the working response z = eta - offset + (y - mu) g'(mu) on the design with
+// weights w / (V(mu) g'(mu)^2), stopping once the relative change in deviance is below tol
+#[allow(clippy::too_many_arguments)]
+fn irls_rs(design: &Array2<f64>, y: &Array1<f64>, weights: &Array1<f64>, offset: &Array1<f64>, family: Family,
+           link: Link, max_iter: usize, tol: f64) -> Result<IrlsResult, LinearModelError> {
+    let (n, k) = design.dim();
+    let total: f64 = weights.sum();
+    let mean = y.iter().zip(weights.iter()).map(|(y, w)| y * w).sum::<f64>() / total;
+    let mut mu: Array1<f64> = y.mapv(|v| family.starting_mu(v, mean));
+    let mut eta: Array1<f64> = mu.mapv(|m| link.link(m));
+    let mut deviance = deviance_rs(family, y, &mu, weights);
+    let mut params: Option<Array1<f64>> = None;
+
+    for iteration in 0..max_iter {
+        let mut weighted_design = design.clone();
+        let mut weighted_z = Array1::<f64>::zeros(n);
+        for i in 0..n {
+            let derivative = link.derivative(mu[i]);
+            let working_weight = weights[i] / (family.variance(mu[i]) * derivative * derivative);
+            let root = working_weight.sqrt();
+            weighted_design.row_mut(i).mapv_inplace(|v| v * root);
+            weighted_z[i] = root * (eta[i] - offset[i] + (y[i] - mu[i]) * derivative);
+        }
+        if weighted_design.iter().chain(weighted_z.iter()).any(|v| !v.is_finite()) { return Err(LinearModelError::NotConverged); }
+        let qr = QrDecomposition::new(&weighted_design);
+        if qr.rank() < k { return Err(LinearModelError::RankDeficient); }
+        let mut candidate = qr.solve(&weighted_z).ok_or(LinearModelError::SingularMatrix)?;
+
+        // halve towards the previous estimate while the step leaves the valid region
+        let mut halvings = 0;
+        let (new_mu, new_eta, new_deviance) = loop {
+            let new_eta = design.dot(&candidate) + offset;
+            let new_mu = new_eta.mapv(|e| family.clamp(link.inverse(e)));
+            let new_deviance = deviance_rs(family, y, &new_mu, weights);
+            if new_deviance.is_finite() { break (new_mu, new_eta, new_deviance); }
+            let Some(previous) = &params else { return Err(LinearModelError::NotConverged) };
+            if halvings == MAX_STEP_HALVINGS { return Err(LinearModelError::NotConverged); }
+            candidate = (&candidate + previous) / 2.0;
+            halvings += 1;
+        };
+
+        let change = (new_deviance - deviance).abs() / (new_deviance.abs() + 0.1);
+        mu = new_mu;
+        eta = new_eta;
+        deviance = new_deviance;
+        params = Some(candidate);
+        if change < tol {
+            let r_inverse = upper_triangular_inverse_rs(&qr.r).ok_or(LinearModelError::SingularMatrix)?;
+            return Ok(IrlsResult {
+                params: params.unwrap(),
+                mu,
+                eta,
+                xtwx_inverse: r_inverse.dot(&r_inverse.t()),
+                deviance,
+                n_iter: iteration + 1,
+            });
+        }
+    }
+    Err(LinearModelError::NotConverged)
+}
+
+#[derive(Debug, Clone)]
+pub struct GlmFit {
+    pub family: Family,
+    pub link: Link,
+    pub params: Array1<f64>,
+    pub fit_intercept: bool,
+    pub cov_params: Array2<f64>,
+    pub y: Array1<f64>,
+    pub weights: Array1<f64>,
+    pub mu: Array1<f64>,
+    pub eta: Array1<f64>,
+    pub deviance: f64,
+    pub null_deviance: f64,
+    pub pearson_chi2: f64,
+    pub scale: f64,
+    // sum of the frequency weights
+    pub nobs: f64,
+    pub df_model: f64,
+    pub df_resid: f64,
+    pub n_iter: usize,
+}
+
+// Offset plus log(exposure), exposure requiring the log link
+fn total_offset(n: usize, link: Link, offset: Option<&Array1<f64>>, exposure: Option<&Array1<f64>>)
+                -> Result<Array1<f64>, LinearModelError> {
+    let mut total = Array1::<f64>::zeros(n);
+    if let Some(offset) = offset {
+        if offset.len() != n { return Err(LinearModelError::ShapeMismatch); }
+        if offset.iter().any(|v| !v.is_finite()) { return Err(LinearModelError::InvalidInputValue); }
+        total += offset;
+    }
+    if let Some(exposure) = exposure {
+        if link != Link::Log { return Err(LinearModelError::InvalidInputValue); }
+        if exposure.len() != n { return Err(LinearModelError::ShapeMismatch); }
+        if exposure.iter().any(|&v| !v.is_finite() || v <= 0.0) { return Err(LinearModelError::InvalidInputValue); }
+        total += &exposure.mapv(f64::ln);
+    }
+    Ok(total)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn glm_rs(x: &Array2<f64>, y: &Array1<f64>, family: Family, link: Link, fit_intercept: bool,
+              offset: Option<&Array1<f64>>, exposure: Option<&Array1<f64>>, freq_weights: Option<&Array1<f64>>,
+              max_iter: usize, tol: f64) -> Result<GlmFit, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    if max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    if y.iter().any(|&v| !family.valid_response(v)) { return Err(LinearModelError::InvalidInputValue); }
+    let n = x.nrows();
+    let weights = match freq_weights {
+        Some(w) => {
+            if w.len() != n { return Err(LinearModelError::ShapeMismatch); }
+            if w.iter().any(|&v| !v.is_finite() || v < 0.0) || w.sum() <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+            w.clone()
+        }
+        None => Array1::<f64>::ones(n),
+    };
+    let offset = total_offset(n, link, offset, exposure)?;
+    let design = if fit_intercept { add_intercept_rs(x) } else { x.clone() };
+    let k = design.ncols();
+    let nobs = weights.sum();
+    if nobs <= k as f64 { return Err(LinearModelError::InsufficientObservations); }
+
+    let result = irls_rs(&design, y, &weights, &offset, family, link, max_iter, tol)?;
+
+    // the null model keeps the offset, plus an intercept when the model has one
+    let null_deviance = if fit_intercept {
+        let ones = Array2::<f64>::ones((n, 1));
+        irls_rs(&ones, y, &weights, &offset, family, link, max_iter, tol)?.deviance
+    } else {
+        deviance_rs(family, y, &offset.mapv(|o| family.clamp(link.inverse(o))), &weights)
+    };
+
+    let pearson_chi2: f64 = (0..n).map(|i| weights[i] * (y[i] - result.mu[i]).powi(2) / family.variance(result.mu[i])).sum();
+    let df_resid = nobs - k as f64;
+    let scale = if family.fixed_scale() { 1.0 } else { pearson_chi2 / df_resid };
+
+    Ok(GlmFit {
+        family,
+        link,
+        cov_params: &result.xtwx_inverse * scale,
+        params: result.params,
+        fit_intercept,
+        y: y.clone(),
+        weights,
+        mu: result.mu,
+        eta: result.eta,
+        deviance: result.deviance,
+        null_deviance,
+        pearson_chi2,
+        scale,
+        nobs,
+        df_model: (k - usize::from(fit_intercept)) as f64,
+        df_resid,
+        n_iter: result.n_iter,
+    })
+}
+
+impl GlmFit {
+    pub fn standard_errors(&self) -> Array1<f64> {
+        self.cov_params.diag().mapv(f64::sqrt)
+    }
+
+    pub fn z_values(&self) -> Array1<f64> {
+        &self.params / &self.standard_errors()
+    }
+
+    pub fn p_values(&self) -> Array1<f64> {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        self.z_values().mapv(|z| 2.0 * normal.sf(z.abs()))
+    }
+
+    // (lower, upper) per parameter, one row each
+    pub fn confidence_intervals(&self, alpha: f64) -> Result<Array2<f64>, LinearModelError> {
+        if alpha <= 0.0 || alpha >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+        let critical = Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - alpha / 2.0);
+        let se = self.standard_errors();
+        let mut intervals = Array2::<f64>::zeros((self.params.len(), 2));
+        intervals.column_mut(0).assign(&(&self.params - &(&se * critical)));
+        intervals.column_mut(1).assign(&(&self.params + &(&se * critical)));
+        Ok(intervals)
+    }
+
+    // At the fitted dispersion, except the gaussian which uses its maximum likelihood
+    // estimate deviance / nobs so the value matches OLS
+    pub fn log_likelihood(&self) -> f64 {
+        let scale = match self.family {
+            Family::Gaussian => self.deviance / self.nobs,
+            _ => self.scale,
+        };
+        (0..self.y.len()).map(|i| self.weights[i] * self.family.log_likelihood(self.y[i], self.mu[i], scale)).sum()
+    }
+
+    pub fn aic(&self) -> f64 {
+        -2.0 * self.log_likelihood() + 2.0 * self.params.len() as f64
+    }
+
+    pub fn bic(&self) -> f64 {
+        -2.0 * self.log_likelihood() + self.nobs.ln() * self.params.len() as f64
+    }
+
+    // response (y - mu), pearson (y - mu) / sqrt(V(mu)) or deviance sign(y - mu) sqrt(d(y, mu))
+    pub fn residuals(&self, kind: &str) -> Result<Array1<f64>, LinearModelError> {
+        let pairs = self.y.iter().zip(self.mu.iter());
+        match kind {
+            "response" => Ok(pairs.map(|(y, mu)| y - mu).collect()),
+            "pearson" => Ok(pairs.map(|(y, mu)| (y - mu) / self.family.variance(*mu).sqrt()).collect()),
+            "deviance" => Ok(pairs.map(|(y, mu)| (y - mu).signum() * self.family.unit_deviance(*y, *mu).max(0.0).sqrt()).collect()),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+
+    // Linear predictor or mean for new features, with an optional offset / exposure
+    pub fn predict(&self, x: &Array2<f64>, offset: Option<&Array1<f64>>, exposure: Option<&Array1<f64>>,
+                   linear: bool) -> Result<Array1<f64>, LinearModelError> {
+        validate_linear_model_input!(features, x);
+        if x.ncols() + usize::from(self.fit_intercept) != self.params.len() { return Err(LinearModelError::ShapeMismatch); }
+        let design = if self.fit_intercept { add_intercept_rs(x) } else { x.clone() };
+        let eta = design.dot(&self.params) + total_offset(x.nrows(), self.link, offset, exposure)?;
+        Ok(if linear { eta } else { eta.mapv(|e| self.link.inverse(e)) })
+    }
+}
+
+
+/// Pyclass
+
+#[pyclass(name = "GLM")]
+#[derive(Debug, Clone)]
+pub struct Glm {
+    pub family: Family,
+    pub link: Link,
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub max_iter: usize,
+    #[pyo3(get)]
+    pub tol: f64,
+    pub fit: Option<GlmFit>,
+}
+
+impl LinearModel for Glm {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(glm_rs(features, target, self.family, self.link, self.fit_intercept, None, None, None,
+                               self.max_iter, self.tol)?);
+        Ok(())
+    }
+
+    // Fitted means, without any offset
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features, None, None, false)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        let fit = self.fitted()?;
+        Ok(fit.params.slice(s![usize::from(fit.fit_intercept)..]).to_owned())
+    }
+
+    // Binomial means are probabilities, so the probability scorers apply to them
+    fn predict_proba(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        if self.family != Family::Binomial { return Err(LinearModelError::InvalidInputValue); }
+        LinearModel::predict(self, features)
+    }
+}
+
+impl Glm {
+    pub fn new_rs(family: Family, link: Option<Link>, fit_intercept: bool, max_iter: usize, tol: f64)
+                  -> Result<Glm, LinearModelError> {
+        if max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+        Ok(Glm { family, link: link.unwrap_or(family.default_link()), fit_intercept, max_iter, tol, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&GlmFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+fn optional_array(values: Option<&PyAny>) -> PyResult<Option<Array1<f64>>> {
+    match values {
+        Some(values) => match from_pyarray1(values) {
+            Ok(data) => Ok(Some(data)),
+            Err(_e) => Err(LinearModelError::Conversion.into()),
+        },
+        None => Ok(None),
+    }
+}
+
+#[pymethods]
+impl Glm {
+    #[new]
+    #[pyo3(signature = (family = "gaussian", link = None, fit_intercept = true, alpha = 1.0, var_power = 1.5,
+                        link_power = None, max_iter = 100, tol = 1e-8))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(family: &str, link: Option<&str>, fit_intercept: bool, alpha: f64, var_power: f64, link_power: Option<f64>,
+           max_iter: usize, tol: f64) -> PyResult<Self> {
+        // family: gaussian, binomial, poisson, gamma, inverse_gaussian, negative_binomial (alpha)
+        //         or tweedie (var_power); link defaults to the family's canonical link
+        let family = Family::parse(family, alpha, var_power)?;
+        let link = match link {
+            Some(name) => Some(Link::parse(name, link_power)?),
+            None => None,
+        };
+        Ok(Glm::new_rs(family, link, fit_intercept, max_iter, tol)?)
+    }
+
+    #[pyo3(signature = (x, y, offset = None, exposure = None, freq_weights = None))]
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny, offset: Option<&PyAny>, exposure: Option<&PyAny>,
+                freq_weights: Option<&PyAny>) -> PyResult<PyRefMut<'py, Self>> {
+        let x_data = features_from_py(x)?;
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        let (offset, exposure, freq_weights) = (optional_array(offset)?, optional_array(exposure)?, optional_array(freq_weights)?);
+        let fit = glm_rs(&x_data, &y_data, slf.family, slf.link, slf.fit_intercept, offset.as_ref(), exposure.as_ref(),
+                         freq_weights.as_ref(), slf.max_iter, slf.tol)?;
+        slf.fit = Some(fit);
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (x, offset = None, exposure = None, linear = false))]
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny, offset: Option<&PyAny>, exposure: Option<&PyAny>,
+                    linear: bool) -> PyResult<&'py PyArray1<f64>> {
+        // the mean, or the linear predictor with linear=True
+        let (offset, exposure) = (optional_array(offset)?, optional_array(exposure)?);
+        let prediction = self.fitted()?.predict(&features_from_py(x)?, offset.as_ref(), exposure.as_ref(), linear)?;
+        Ok(prediction.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        Ok(LinearModel::score(self, &features_from_py(x)?, &y_data, metric)?)
+    }
+
+    #[getter]
+    fn family(&self) -> String {
+        self.family.name()
+    }
+
+    #[getter]
+    fn link(&self) -> String {
+        self.link.name()
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn standard_errors<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.standard_errors().into_pyarray(py))
+    }
+
+    #[getter]
+    fn z_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.z_values().into_pyarray(py))
+    }
+
+    #[getter]
+    fn p_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.p_values().into_pyarray(py))
+    }
+
+    #[getter]
+    fn cov_params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.cov_params.clone().into_pyarray(py))
+    }
+
+    #[pyo3(signature = (alpha = 0.05))]
+    fn confidence_intervals<'py>(&self, py: Python<'py>, alpha: f64) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.confidence_intervals(alpha)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn deviance(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.deviance)
+    }
+
+    #[getter]
+    fn null_deviance(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.null_deviance)
+    }
+
+    #[getter]
+    fn pearson_chi2(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.pearson_chi2)
+    }
+
+    #[getter]
+    fn scale(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.scale)
+    }
+
+    #[getter]
+    fn log_likelihood(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.log_likelihood())
+    }
+
+    #[getter]
+    fn aic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.aic())
+    }
+
+    #[getter]
+    fn bic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.bic())
+    }
+
+    #[getter]
+    fn nobs(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.nobs)
+    }
+
+    #[getter]
+    fn df_model(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.df_model)
+    }
+
+    #[getter]
+    fn df_resid(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.df_resid)
+    }
+
+    #[getter]
+    fn n_iter(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.n_iter)
+    }
+
+    #[getter]
+    fn fitted_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.mu.clone().into_pyarray(py))
+    }
+
+    #[pyo3(signature = (kind = "deviance"))]
+    fn residuals<'py>(&self, py: Python<'py>, kind: &str) -> PyResult<&'py PyArray1<f64>> {
+        // kind: response, pearson or deviance
+        Ok(self.fitted()?.residuals(kind)?.into_pyarray(py))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("GLM(family={}, link={}, fit_intercept={})", self.family.name(), self.link.name(),
+                if self.fit_intercept { "True" } else { "False" })
+    }
+}
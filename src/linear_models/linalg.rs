@@ -0,0 +1,198 @@
+// Dense linear algebra for the linear models, ndarray has no decompositions of its own
+// QrDecomposition -- householder QR of a tall matrix, with rank check and Q'y / thin Q
+// cholesky_rs -- lower cholesky factor of a symmetric positive definite matrix
+// solve_upper_triangular_rs / solve_lower_triangular_rs / upper_triangular_inverse_rs
+// svd_rs -- thin singular value decomposition by one-sided jacobi rotations
+
+use ndarray::{s, Array1, Array2};
+
+
+// Jacobi sweeps usually converge in well under ten, this only guards against cycling
+const SVD_MAX_SWEEPS: usize = 60;
+
+// Columns whose diagonal in R falls below this fraction of the largest are treated as dependent
+pub const RANK_TOLERANCE: f64 = 1e-10;
+
+#[derive(Debug, Clone)]
+pub struct QrDecomposition {
+    // householder vectors, reflector k acting on rows k..
+    reflectors: Vec<Array1<f64>>,
+    pub r: Array2<f64>,
+}
+
+impl QrDecomposition {
+    pub fn new(x: &Array2<f64>) -> QrDecomposition {
+        let (n, p) = x.dim();
+        let mut a = x.clone();
+        let mut reflectors = Vec::with_capacity(p.min(n));
+        for k in 0..p.min(n) {
+            let mut v = a.slice(s![k.., k]).to_owned();
+            let norm = v.dot(&v).sqrt();
+            if norm > 0.0 {
+                let alpha = if v[0] > 0.0 { -norm } else { norm };
+                v[0] -= alpha;
+                let v_norm = v.dot(&v);
+                for j in k..p {
+                    let mut column = a.slice_mut(s![k.., j]);
+                    let factor = 2.0 * v.dot(&column) / v_norm;
+                    column.scaled_add(-factor, &v);
+                }
+            }
+            reflectors.push(v);
+        }
+        let r = a.slice(s![..p.min(n), ..]).to_owned();
+        QrDecomposition { reflectors, r }
+    }
+
+    // Number of columns of R with a non-negligible diagonal
+    pub fn rank(&self) -> usize {
+        let diagonal: Vec<f64> = (0..self.r.nrows().min(self.r.ncols())).map(|k| self.r[[k, k]].abs()).collect();
+        let largest = diagonal.iter().cloned().fold(0.0, f64::max);
+        diagonal.iter().filter(|&&d| d > RANK_TOLERANCE * largest).count()
+    }
+
+    // Q'y, the first p entries feed the triangular solve and the rest are the residual coordinates
+    pub fn apply_qt(&self, y: &Array1<f64>) -> Array1<f64> {
+        let mut result = y.clone();
+        for (k, v) in self.reflectors.iter().enumerate() {
+            let v_norm = v.dot(v);
+            if v_norm == 0.0 { continue; }
+            let mut tail = result.slice_mut(s![k..]);
+            let factor = 2.0 * v.dot(&tail) / v_norm;
+            tail.scaled_add(-factor, v);
+        }
+        result
+    }
+
+    // Thin Q (n x p), whose squared row norms are the hat values
+    pub fn q_thin(&self, n: usize) -> Array2<f64> {
+        let p = self.reflectors.len();
+        let mut q = Array2::<f64>::zeros((n, p));
+        for k in 0..p { q[[k, k]] = 1.0; }
+        for (k, v) in self.reflectors.iter().enumerate().rev() {
+            let v_norm = v.dot(v);
+            if v_norm == 0.0 { continue; }
+            for j in 0..p {
+                let mut column = q.slice_mut(s![k.., j]);
+                let factor = 2.0 * v.dot(&column) / v_norm;
+                column.scaled_add(-factor, v);
+            }
+        }
+        q
+    }
+
+    // Least squares solution of x b = y, None when x does not have full column rank
+    pub fn solve(&self, y: &Array1<f64>) -> Option<Array1<f64>> {
+        if self.rank() < self.r.ncols() { return None; }
+        let p = self.r.ncols();
+        let qty = self.apply_qt(y);
+        solve_upper_triangular_rs(&self.r.slice(s![..p, ..]).to_owned(), &qty.slice(s![..p]).to_owned())
+    }
+}
+
+pub fn solve_upper_triangular_rs(r: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let p = r.ncols();
+    let mut x = Array1::<f64>::zeros(p);
+    for i in (0..p).rev() {
+        if r[[i, i]] == 0.0 { return None; }
+        let partial: f64 = (i + 1..p).map(|j| r[[i, j]] * x[j]).sum();
+        x[i] = (b[i] - partial) / r[[i, i]];
+    }
+    Some(x)
+}
+
+pub fn solve_lower_triangular_rs(l: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let p = l.ncols();
+    let mut x = Array1::<f64>::zeros(p);
+    for i in 0..p {
+        if l[[i, i]] == 0.0 { return None; }
+        let partial: f64 = (0..i).map(|j| l[[i, j]] * x[j]).sum();
+        x[i] = (b[i] - partial) / l[[i, i]];
+    }
+    Some(x)
+}
+
+// R^-1 column by column, so (X'X)^-1 = R^-1 R^-T without forming X'X
+pub fn upper_triangular_inverse_rs(r: &Array2<f64>) -> Option<Array2<f64>> {
+    let p = r.ncols();
+    let mut inverse = Array2::<f64>::zeros((p, p));
+    for j in 0..p {
+        let mut e = Array1::<f64>::zeros(p);
+        e[j] = 1.0;
+        inverse.column_mut(j).assign(&solve_upper_triangular_rs(r, &e)?);
+    }
+    Some(inverse)
+}
+
+// Lower triangular L with L L' = a, None when a is not (numerically) positive definite
+pub fn cholesky_rs(a: &Array2<f64>) -> Option<Array2<f64>> {
+    let p = a.nrows();
+    let scale = (0..p).map(|i| a[[i, i]].abs()).fold(0.0, f64::max);
+    let mut l = Array2::<f64>::zeros((p, p));
+    for j in 0..p {
+        let diagonal = a[[j, j]] - (0..j).map(|k| l[[j, k]] * l[[j, k]]).sum::<f64>();
+        if diagonal <= RANK_TOLERANCE * scale { return None; }
+        l[[j, j]] = diagonal.sqrt();
+        for i in j + 1..p {
+            let off_diagonal = a[[i, j]] - (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum::<f64>();
+            l[[i, j]] = off_diagonal / l[[j, j]];
+        }
+    }
+    Some(l)
+}
+
+// Solves a x = b from the cholesky factor of a
+pub fn cholesky_solve_rs(l: &Array2<f64>, b: &Array1<f64>) -> Option<Array1<f64>> {
+    let z = solve_lower_triangular_rs(l, b)?;
+    solve_upper_triangular_rs(&l.t().to_owned(), &z)
+}
+
+// x = u diag(s) v', with u n x p, s descending and v p x p. Pairs of columns of x are rotated
+// until all are orthogonal, the rotations accumulate into v and the column norms are s
+pub fn svd_rs(x: &Array2<f64>) -> (Array2<f64>, Array1<f64>, Array2<f64>) {
+    let (n, p) = x.dim();
+    let mut u = x.clone();
+    let mut v = Array2::<f64>::eye(p);
+    for _sweep in 0..SVD_MAX_SWEEPS {
+        let mut rotated = false;
+        for j in 0..p {
+            for k in j + 1..p {
+                let alpha = u.column(j).dot(&u.column(j));
+                let beta = u.column(k).dot(&u.column(k));
+                let gamma = u.column(j).dot(&u.column(k));
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() { continue; }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for i in 0..n {
+                    let (a, b) = (u[[i, j]], u[[i, k]]);
+                    u[[i, j]] = c * a - s * b;
+                    u[[i, k]] = s * a + c * b;
+                }
+                for i in 0..p {
+                    let (a, b) = (v[[i, j]], v[[i, k]]);
+                    v[[i, j]] = c * a - s * b;
+                    v[[i, k]] = s * a + c * b;
+                }
+            }
+        }
+        if !rotated { break; }
+    }
+
+    let norms: Vec<f64> = (0..p).map(|j| u.column(j).dot(&u.column(j)).sqrt()).collect();
+    let mut order: Vec<usize> = (0..p).collect();
+    order.sort_by(|&a, &b| norms[b].partial_cmp(&norms[a]).unwrap_or(std::cmp::Ordering::Equal));
+    let mut u_sorted = Array2::<f64>::zeros((n, p));
+    let mut v_sorted = Array2::<f64>::zeros((p, p));
+    let mut s = Array1::<f64>::zeros(p);
+    for (position, &j) in order.iter().enumerate() {
+        s[position] = norms[j];
+        if norms[j] > 0.0 {
+            u_sorted.column_mut(position).assign(&(&u.column(j) / norms[j]));
+        }
+        v_sorted.column_mut(position).assign(&v.column(j));
+    }
+    (u_sorted, s, v_sorted)
+}
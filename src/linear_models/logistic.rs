@@ -0,0 +1,727 @@
+// Logistic regression, binary and multinomial
+// LogisticRegression -- maximum likelihood or penalized fits, predict / predict_proba, inference
+//
+// The first (smallest) class is the reference: with K classes there are K - 1 columns of
+// parameters, each the log odds of one class against the reference, so the binary case is the
+// usual logit. Fits minimize
+//   -sum_i w_i log p(y_i) + alpha * penalty
+// with penalty 0.5 ||b||^2 (l2) or ||b||_1 (l1) on the slopes only and w_i the class weights;
+// alpha is 1 / C in scikit-learn's terms.
+// Solvers: newton (IRLS, with coordinate descent on each weighted least squares step for l1) and
+// lbfgs (none or l2). Unpenalized fits report Wald inference, odds ratios, deviance and pseudo R^2.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{Array1, Array2, Axis};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::linalg::{cholesky_rs, cholesky_solve_rs};
+use crate::linear_models::ols::add_intercept_rs;
+use crate::linear_models::scoring::scorer_rs;
+use crate::linear_models::{features_from_py, LinearModel};
+use crate::utils::{from_pyarray1, lbfgs_rs};
+use crate::validate_linear_model_input;
+
+
+// Inner coordinate descent sweeps per class on each l1 newton step
+const L1_INNER_SWEEPS: usize = 100;
+// Floor on p (1 - p) so the working response stays finite for fitted probabilities near 0 or 1
+const WEIGHT_FLOOR: f64 = 1e-10;
+// Every observation fitted with at least this much certainty means the classes are separated
+const SEPARATION_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    None,
+    L1,
+    L2,
+}
+
+impl Penalty {
+    pub fn parse(penalty: &str) -> Result<Penalty, LinearModelError> {
+        match penalty.to_lowercase().as_str() {
+            "none" => Ok(Penalty::None),
+            "l1" => Ok(Penalty::L1),
+            "l2" => Ok(Penalty::L2),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogisticSolver {
+    Newton,
+    Lbfgs,
+}
+
+impl LogisticSolver {
+    pub fn parse(solver: &str) -> Result<LogisticSolver, LinearModelError> {
+        match solver.to_lowercase().as_str() {
+            "newton" | "irls" => Ok(LogisticSolver::Newton),
+            "lbfgs" => Ok(LogisticSolver::Lbfgs),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassWeight {
+    Uniform,
+    // n / (K * count of the class), so every class carries the same total weight
+    Balanced,
+    // (label, weight) pairs, unlisted classes keep weight one
+    Custom(Vec<(f64, f64)>),
+}
+
+impl ClassWeight {
+    fn weights(&self, classes: &[f64], targets: &[usize]) -> Result<Array1<f64>, LinearModelError> {
+        let per_class: Vec<f64> = match self {
+            ClassWeight::Uniform => vec![1.0; classes.len()],
+            ClassWeight::Balanced => {
+                let mut counts = vec![0.0; classes.len()];
+                for &t in targets { counts[t] += 1.0; }
+                let total = targets.len() as f64 / classes.len() as f64;
+                counts.iter().map(|count| total / count).collect()
+            }
+            ClassWeight::Custom(pairs) => {
+                if pairs.iter().any(|&(_, w)| !w.is_finite() || w <= 0.0) { return Err(LinearModelError::InvalidInputValue); }
+                classes.iter().map(|class| {
+                    pairs.iter().find(|(label, _)| label == class).map(|&(_, w)| w).unwrap_or(1.0)
+                }).collect()
+            }
+        };
+        Ok(targets.iter().map(|&t| per_class[t]).collect())
+    }
+}
+
+// Class probabilities (n x K) for a design and the (d x K-1) parameters, by a stable softmax
+// with the reference class fixed at zero
+pub fn class_probabilities_rs(design: &Array2<f64>, params: &Array2<f64>) -> Array2<f64> {
+    let eta = design.dot(params);
+    let (n, m) = eta.dim();
+    let mut probabilities = Array2::<f64>::zeros((n, m + 1));
+    for i in 0..n {
+        let largest = eta.row(i).iter().fold(0.0_f64, |a, &b| a.max(b));
+        let reference = (-largest).exp();
+        let total = reference + eta.row(i).iter().map(|v| (v - largest).exp()).sum::<f64>();
+        probabilities[[i, 0]] = reference / total;
+        for k in 0..m {
+            probabilities[[i, k + 1]] = (eta[[i, k]] - largest).exp() / total;
+        }
+    }
+    probabilities
+}
+
+// Parameters are flattened class by class, index k * d + j
+fn unflatten(theta: &Array1<f64>, d: usize, m: usize) -> Array2<f64> {
+    Array2::from_shape_vec((m, d), theta.to_vec()).unwrap().reversed_axes()
+}
+
+fn flatten(params: &Array2<f64>) -> Array1<f64> {
+    params.t().iter().cloned().collect()
+}
+
+struct LogisticProblem<'a> {
+    design: &'a Array2<f64>,
+    targets: &'a [usize],
+    weights: &'a Array1<f64>,
+    m: usize,
+    alpha: f64,
+    // false for the intercept column, which is never penalized
+    penalized: Vec<bool>,
+}
+
+impl LogisticProblem<'_> {
+    fn log_likelihood(&self, probabilities: &Array2<f64>) -> f64 {
+        self.targets.iter().enumerate()
+            .map(|(i, &t)| self.weights[i] * probabilities[[i, t]].max(f64::MIN_POSITIVE).ln())
+            .sum()
+    }
+
+    fn l2_penalty(&self, params: &Array2<f64>) -> f64 {
+        let mut total = 0.0;
+        for ((j, _k), &value) in params.indexed_iter() {
+            if self.penalized[j] { total += value * value; }
+        }
+        0.5 * self.alpha * total
+    }
+
+    fn l1_penalty(&self, params: &Array2<f64>) -> f64 {
+        let mut total = 0.0;
+        for ((j, _k), &value) in params.indexed_iter() {
+            if self.penalized[j] { total += value.abs(); }
+        }
+        self.alpha * total
+    }
+
+    // Negative log likelihood plus the l2 penalty, and its gradient, for flattened parameters
+    fn objective(&self, theta: &Array1<f64>) -> (f64, Array1<f64>) {
+        let d = self.design.ncols();
+        let params = unflatten(theta, d, self.m);
+        let probabilities = class_probabilities_rs(self.design, &params);
+        let mut residuals = Array2::<f64>::zeros((self.targets.len(), self.m));
+        for (i, &t) in self.targets.iter().enumerate() {
+            for k in 0..self.m {
+                let observed = if t == k + 1 { 1.0 } else { 0.0 };
+                residuals[[i, k]] = self.weights[i] * (probabilities[[i, k + 1]] - observed);
+            }
+        }
+        let mut gradient = self.design.t().dot(&residuals);
+        for ((j, k), value) in params.indexed_iter() {
+            if self.penalized[j] { gradient[[j, k]] += self.alpha * value; }
+        }
+        (-self.log_likelihood(&probabilities) + self.l2_penalty(&params), flatten(&gradient))
+    }
+
+    // Hessian of the negative log likelihood, blocks X' diag(w p_k (delta_kl - p_l)) X
+    fn information(&self, params: &Array2<f64>) -> Array2<f64> {
+        let (n, d) = self.design.dim();
+        let probabilities = class_probabilities_rs(self.design, params);
+        let mut hessian = Array2::<f64>::zeros((d * self.m, d * self.m));
+        for k in 0..self.m {
+            for l in k..self.m {
+                let mut block = Array2::<f64>::zeros((d, d));
+                for i in 0..n {
+                    let (pk, pl) = (probabilities[[i, k + 1]], probabilities[[i, l + 1]]);
+                    let w = self.weights[i] * pk * (if k == l { 1.0 - pl } else { -pl });
+                    let row = self.design.row(i);
+                    for a in 0..d {
+                        for b in 0..d {
+                            block[[a, b]] += w * row[a] * row[b];
+                        }
+                    }
+                }
+                for a in 0..d {
+                    for b in 0..d {
+                        hessian[[k * d + a, l * d + b]] = block[[a, b]];
+                        hessian[[l * d + b, k * d + a]] = block[[a, b]];
+                    }
+                }
+            }
+        }
+        hessian
+    }
+
+    fn gradient_scale(&self) -> f64 {
+        self.weights.sum()
+    }
+
+    // Newton-Raphson with step halving, for the smooth (none / l2) objective
+    fn newton(&self, max_iter: usize, tol: f64) -> Result<(Array1<f64>, usize), LinearModelError> {
+        let d = self.design.ncols();
+        let mut theta = Array1::<f64>::zeros(d * self.m);
+        let (mut value, mut gradient) = self.objective(&theta);
+        for iteration in 0..max_iter {
+            if largest_abs(&gradient) / self.gradient_scale() <= tol { return Ok((theta, iteration)); }
+            let mut hessian = self.information(&unflatten(&theta, d, self.m));
+            for k in 0..self.m {
+                for j in 0..d {
+                    if self.penalized[j] { hessian[[k * d + j, k * d + j]] += self.alpha; }
+                }
+            }
+            // a singular hessian at the start is a collinear design, later on it is divergence
+            let l = match cholesky_rs(&hessian) {
+                Some(l) => l,
+                None if iteration == 0 => return Err(LinearModelError::RankDeficient),
+                None => return Err(LinearModelError::NotConverged),
+            };
+            let step = cholesky_solve_rs(&l, &gradient).ok_or(LinearModelError::NotConverged)?;
+            let mut scale = 1.0;
+            loop {
+                let candidate = &theta - &(&step * scale);
+                let (candidate_value, candidate_gradient) = self.objective(&candidate);
+                if candidate_value <= value || scale < 1e-10 {
+                    theta = candidate;
+                    value = candidate_value;
+                    gradient = candidate_gradient;
+                    break;
+                }
+                scale *= 0.5;
+            }
+        }
+        if largest_abs(&gradient) / self.gradient_scale() <= tol { Ok((theta, max_iter)) } else { Err(LinearModelError::NotConverged) }
+    }
+
+    fn lbfgs(&self, max_iter: usize, tol: f64) -> Result<(Array1<f64>, usize), LinearModelError> {
+        let d = self.design.ncols();
+        let scale = self.gradient_scale();
+        let (theta, n_iter, converged) = lbfgs_rs(|theta| {
+            let (value, gradient) = self.objective(theta);
+            (value / scale, gradient / scale)
+        }, Array1::<f64>::zeros(d * self.m), tol, max_iter);
+        if converged { Ok((theta, n_iter)) } else { Err(LinearModelError::NotConverged) }
+    }
+
+    // Proximal newton for the l1 penalty: each class in turn gets the IRLS quadratic approximation,
+    // minimized with its l1 term by coordinate descent, and the combined step is halved until
+    // the penalized objective decreases
+    fn l1_newton(&self, max_iter: usize, tol: f64) -> Result<(Array1<f64>, usize), LinearModelError> {
+        let (n, d) = self.design.dim();
+        let objective = |params: &Array2<f64>| {
+            -self.log_likelihood(&class_probabilities_rs(self.design, params)) + self.l1_penalty(params)
+        };
+        let mut params = Array2::<f64>::zeros((d, self.m));
+        let mut value = objective(&params);
+        for iteration in 0..max_iter {
+            let previous = params.clone();
+            for k in 0..self.m {
+                let probabilities = class_probabilities_rs(self.design, &params);
+                let eta = self.design.dot(&params.column(k));
+                let mut w = Array1::<f64>::zeros(n);
+                let mut z = Array1::<f64>::zeros(n);
+                for i in 0..n {
+                    let p = probabilities[[i, k + 1]];
+                    let variance = (p * (1.0 - p)).max(WEIGHT_FLOOR);
+                    let observed = if self.targets[i] == k + 1 { 1.0 } else { 0.0 };
+                    w[i] = self.weights[i] * variance;
+                    z[i] = eta[i] + (observed - p) / variance;
+                }
+                let mut b = params.column(k).to_owned();
+                let mut residuals = &z - &self.design.dot(&b);
+                for _sweep in 0..L1_INNER_SWEEPS {
+                    let mut change = 0.0_f64;
+                    for j in 0..d {
+                        let column = self.design.column(j);
+                        let curvature: f64 = column.iter().zip(w.iter()).map(|(x, w)| w * x * x).sum();
+                        if curvature == 0.0 { continue; }
+                        let rho: f64 = column.iter().zip(w.iter()).zip(residuals.iter())
+                            .map(|((x, w), r)| w * x * r).sum::<f64>() + curvature * b[j];
+                        let updated = if self.penalized[j] {
+                            rho.signum() * (rho.abs() - self.alpha).max(0.0) / curvature
+                        } else {
+                            rho / curvature
+                        };
+                        if updated != b[j] {
+                            residuals.scaled_add(b[j] - updated, &column);
+                            change = change.max((updated - b[j]).abs());
+                            b[j] = updated;
+                        }
+                    }
+                    if change <= tol { break; }
+                }
+                params.column_mut(k).assign(&b);
+            }
+            let direction = &params - &previous;
+            let mut scale = 1.0;
+            let mut candidate_value = objective(&params);
+            while candidate_value > value && scale > 1e-10 {
+                scale *= 0.5;
+                params = &previous + &(&direction * scale);
+                candidate_value = objective(&params);
+            }
+            value = candidate_value;
+            if largest_abs(&flatten(&(&params - &previous))) <= tol { return Ok((flatten(&params), iteration + 1)); }
+        }
+        Err(LinearModelError::NotConverged)
+    }
+}
+
+fn largest_abs(values: &Array1<f64>) -> f64 {
+    values.iter().fold(0.0_f64, |m, v| m.max(v.abs()))
+}
+
+#[derive(Debug, Clone)]
+pub struct LogisticFit {
+    pub classes: Vec<f64>,
+    // d x (K - 1), the intercept in the first row when fitted
+    pub params: Array2<f64>,
+    pub fit_intercept: bool,
+    pub n_iter: usize,
+    pub nobs: usize,
+    pub log_likelihood: f64,
+    pub null_log_likelihood: f64,
+    // inverse information, only for unpenalized fits
+    pub cov_params: Option<Array2<f64>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn logistic_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool, penalty: Penalty, alpha: f64,
+                   solver: LogisticSolver, class_weight: &ClassWeight, max_iter: usize, tol: f64)
+                   -> Result<LogisticFit, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    if !alpha.is_finite() || alpha < 0.0 || max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    if penalty == Penalty::L1 && solver == LogisticSolver::Lbfgs { return Err(LinearModelError::InvalidInputValue); }
+
+    let mut classes: Vec<f64> = y.to_vec();
+    classes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    classes.dedup();
+    if classes.len() < 2 { return Err(LinearModelError::InsufficientGroups); }
+    let targets: Vec<usize> = y.iter().map(|v| classes.iter().position(|c| c == v).unwrap()).collect();
+    let weights = class_weight.weights(&classes, &targets)?;
+
+    let design = if fit_intercept { add_intercept_rs(x) } else { x.clone() };
+    let d = design.ncols();
+    let m = classes.len() - 1;
+    let alpha = if penalty == Penalty::None { 0.0 } else { alpha };
+    let penalized: Vec<bool> = (0..d).map(|j| !(fit_intercept && j == 0)).collect();
+    let problem = LogisticProblem { design: &design, targets: &targets, weights: &weights, m, alpha, penalized };
+
+    let (theta, n_iter) = match (penalty, solver) {
+        (Penalty::L1, _) if alpha > 0.0 => problem.l1_newton(max_iter, tol)?,
+        (_, LogisticSolver::Newton) => problem.newton(max_iter, tol)?,
+        (_, LogisticSolver::Lbfgs) => problem.lbfgs(max_iter, tol)?,
+    };
+    let params = unflatten(&theta, d, m);
+    let probabilities = class_probabilities_rs(&design, &params);
+    // with perfectly separated classes the likelihood only approaches its supremum as the
+    // slopes run off to infinity, so a tiny gradient there is not a maximum
+    if alpha == 0.0 && targets.iter().enumerate().all(|(i, &t)| probabilities[[i, t]] > 1.0 - SEPARATION_TOLERANCE) {
+        return Err(LinearModelError::NotConverged);
+    }
+    let log_likelihood = problem.log_likelihood(&probabilities);
+
+    // the intercept-only model predicts the weighted class shares
+    let mut shares = vec![0.0; classes.len()];
+    for (i, &t) in targets.iter().enumerate() { shares[t] += weights[i]; }
+    let total: f64 = shares.iter().sum();
+    let null_log_likelihood = targets.iter().enumerate().map(|(i, &t)| weights[i] * (shares[t] / total).ln()).sum();
+
+    let cov_params = if alpha == 0.0 {
+        let information = problem.information(&params);
+        let l = cholesky_rs(&information).ok_or(LinearModelError::SingularMatrix)?;
+        let k = information.nrows();
+        let mut inverse = Array2::<f64>::zeros((k, k));
+        for j in 0..k {
+            let mut e = Array1::<f64>::zeros(k);
+            e[j] = 1.0;
+            inverse.column_mut(j).assign(&cholesky_solve_rs(&l, &e).ok_or(LinearModelError::SingularMatrix)?);
+        }
+        Some(inverse)
+    } else {
+        None
+    };
+
+    Ok(LogisticFit {
+        classes,
+        params,
+        fit_intercept,
+        n_iter,
+        nobs: x.nrows(),
+        log_likelihood,
+        null_log_likelihood,
+        cov_params,
+    })
+}
+
+impl LogisticFit {
+    fn design(&self, x: &Array2<f64>) -> Result<Array2<f64>, LinearModelError> {
+        validate_linear_model_input!(features, x);
+        if x.ncols() + usize::from(self.fit_intercept) != self.params.nrows() { return Err(LinearModelError::ShapeMismatch); }
+        Ok(if self.fit_intercept { add_intercept_rs(x) } else { x.clone() })
+    }
+
+    pub fn predict_proba(&self, x: &Array2<f64>) -> Result<Array2<f64>, LinearModelError> {
+        Ok(class_probabilities_rs(&self.design(x)?, &self.params))
+    }
+
+    // The most probable class for every row
+    pub fn predict(&self, x: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        let probabilities = self.predict_proba(x)?;
+        Ok(probabilities.map_axis(Axis(1), |row| {
+            let best = (0..row.len()).fold(0, |best, k| if row[k] > row[best] { k } else { best });
+            self.classes[best]
+        }))
+    }
+
+    // Slopes, one row per non-reference class
+    pub fn coef(&self) -> Array2<f64> {
+        let start = usize::from(self.fit_intercept);
+        self.params.slice(ndarray::s![start.., ..]).t().to_owned()
+    }
+
+    pub fn intercept(&self) -> Array1<f64> {
+        if self.fit_intercept { self.params.row(0).to_owned() } else { Array1::zeros(self.params.ncols()) }
+    }
+
+    pub fn standard_errors(&self) -> Result<Array2<f64>, LinearModelError> {
+        let cov = self.cov_params.as_ref().ok_or(LinearModelError::PenalizedInference)?;
+        let variances = cov.diag().mapv(f64::sqrt);
+        Ok(unflatten(&variances, self.params.nrows(), self.params.ncols()))
+    }
+
+    pub fn z_values(&self) -> Result<Array2<f64>, LinearModelError> {
+        Ok(&self.params / &self.standard_errors()?)
+    }
+
+    pub fn p_values(&self) -> Result<Array2<f64>, LinearModelError> {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        Ok(self.z_values()?.mapv(|z| 2.0 * normal.sf(z.abs())))
+    }
+
+    // Wald intervals (lower, upper) for the parameters
+    pub fn confidence_intervals(&self, alpha: f64) -> Result<(Array2<f64>, Array2<f64>), LinearModelError> {
+        if alpha <= 0.0 || alpha >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+        let critical = Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - alpha / 2.0);
+        let margin = self.standard_errors()? * critical;
+        Ok((&self.params - &margin, &self.params + &margin))
+    }
+
+    pub fn odds_ratios(&self) -> Array2<f64> {
+        self.params.mapv(f64::exp)
+    }
+
+    pub fn odds_ratio_intervals(&self, alpha: f64) -> Result<(Array2<f64>, Array2<f64>), LinearModelError> {
+        let (lower, upper) = self.confidence_intervals(alpha)?;
+        Ok((lower.mapv(f64::exp), upper.mapv(f64::exp)))
+    }
+
+    pub fn deviance(&self) -> f64 {
+        -2.0 * self.log_likelihood
+    }
+
+    pub fn null_deviance(&self) -> f64 {
+        -2.0 * self.null_log_likelihood
+    }
+
+    // McFadden's 1 - ll / ll_null
+    pub fn pseudo_r_squared(&self) -> f64 {
+        1.0 - self.log_likelihood / self.null_log_likelihood
+    }
+
+    pub fn aic(&self) -> f64 {
+        self.deviance() + 2.0 * self.params.len() as f64
+    }
+
+    pub fn bic(&self) -> f64 {
+        self.deviance() + (self.nobs as f64).ln() * self.params.len() as f64
+    }
+}
+
+
+/// Pyclass
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct LogisticRegression {
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    pub penalty: Penalty,
+    #[pyo3(get)]
+    pub alpha: f64,
+    pub solver: LogisticSolver,
+    pub class_weight: ClassWeight,
+    #[pyo3(get)]
+    pub max_iter: usize,
+    #[pyo3(get)]
+    pub tol: f64,
+    pub fit: Option<LogisticFit>,
+}
+
+impl LinearModel for LogisticRegression {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(logistic_rs(features, target, self.fit_intercept, self.penalty, self.alpha, self.solver,
+                                    &self.class_weight, self.max_iter, self.tol)?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    // Flattened class by class for multinomial fits
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef().iter().cloned().collect())
+    }
+
+    // Probability of the second class, binary fits only
+    fn predict_proba(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        let fit = self.fitted()?;
+        if fit.classes.len() != 2 { return Err(LinearModelError::InvalidInputValue); }
+        Ok(fit.predict_proba(features)?.column(1).to_owned())
+    }
+
+    // Probability scorers compare against the indicator of the second class
+    fn score(&self, features: &Array2<f64>, target: &Array1<f64>, metric: &str) -> Result<f64, LinearModelError> {
+        let scorer = scorer_rs(metric)?;
+        if !scorer.needs_probabilities() {
+            return scorer.score(&LinearModel::predict(self, features)?, target);
+        }
+        let positive = *self.fitted()?.classes.last().unwrap();
+        let indicator = target.mapv(|v| if v == positive { 1.0 } else { 0.0 });
+        scorer.score(&LinearModel::predict_proba(self, features)?, &indicator)
+    }
+}
+
+impl LogisticRegression {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_rs(penalty: Penalty, alpha: f64, solver: LogisticSolver, fit_intercept: bool, class_weight: ClassWeight,
+                  max_iter: usize, tol: f64) -> Result<LogisticRegression, LinearModelError> {
+        if !alpha.is_finite() || alpha < 0.0 || max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+        if penalty == Penalty::L1 && solver == LogisticSolver::Lbfgs { return Err(LinearModelError::InvalidInputValue); }
+        Ok(LogisticRegression { fit_intercept, penalty, alpha, solver, class_weight, max_iter, tol, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&LogisticFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+fn class_weight_from_py(class_weight: Option<&PyAny>) -> PyResult<ClassWeight> {
+    let Some(class_weight) = class_weight else { return Ok(ClassWeight::Uniform) };
+    if let Ok(name) = class_weight.extract::<&str>() {
+        return if name == "balanced" { Ok(ClassWeight::Balanced) } else { Err(LinearModelError::InvalidInputValue.into()) };
+    }
+    let dict: &PyDict = class_weight.downcast().map_err(|_| LinearModelError::InvalidInputValue)?;
+    let mut pairs = Vec::with_capacity(dict.len());
+    for (label, weight) in dict.iter() {
+        pairs.push((label.extract::<f64>()?, weight.extract::<f64>()?));
+    }
+    Ok(ClassWeight::Custom(pairs))
+}
+
+#[pymethods]
+impl LogisticRegression {
+    #[new]
+    #[pyo3(signature = (penalty = "none", alpha = 1.0, solver = "newton", fit_intercept = true, class_weight = None,
+                        max_iter = 100, tol = 1e-8))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(penalty: &str, alpha: f64, solver: &str, fit_intercept: bool, class_weight: Option<&PyAny>,
+           max_iter: usize, tol: f64) -> PyResult<Self> {
+        // penalty: none, l2 or l1 (newton only); alpha = 1 / C
+        // class_weight: None, "balanced" or {label: weight}
+        Ok(LogisticRegression::new_rs(Penalty::parse(penalty)?, alpha, LogisticSolver::parse(solver)?, fit_intercept,
+                                      class_weight_from_py(class_weight)?, max_iter, tol)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        // y holds the class labels, two of them for a binary fit or more for a multinomial one
+        let x_data = features_from_py(x)?;
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    fn predict_proba<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray2<f64>> {
+        // one column per class, in the order of classes
+        Ok(self.fitted()?.predict_proba(&features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "accuracy"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        Ok(LinearModel::score(self, &features_from_py(x)?, &y_data, metric)?)
+    }
+
+    #[getter]
+    fn classes(&self) -> PyResult<Vec<f64>> {
+        Ok(self.fitted()?.classes.clone())
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.coef().into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.intercept().into_pyarray(py))
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn n_iter(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.n_iter)
+    }
+
+    #[getter]
+    fn standard_errors<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.standard_errors()?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn z_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.z_values()?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn p_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.p_values()?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn cov_params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        let cov = self.fitted()?.cov_params.clone().ok_or(LinearModelError::PenalizedInference)?;
+        Ok(cov.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (alpha = 0.05))]
+    fn confidence_intervals<'py>(&self, py: Python<'py>, alpha: f64) -> PyResult<(&'py PyArray2<f64>, &'py PyArray2<f64>)> {
+        let (lower, upper) = self.fitted()?.confidence_intervals(alpha)?;
+        Ok((lower.into_pyarray(py), upper.into_pyarray(py)))
+    }
+
+    #[getter]
+    fn odds_ratios<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.odds_ratios().into_pyarray(py))
+    }
+
+    #[pyo3(signature = (alpha = 0.05))]
+    fn odds_ratio_intervals<'py>(&self, py: Python<'py>, alpha: f64) -> PyResult<(&'py PyArray2<f64>, &'py PyArray2<f64>)> {
+        let (lower, upper) = self.fitted()?.odds_ratio_intervals(alpha)?;
+        Ok((lower.into_pyarray(py), upper.into_pyarray(py)))
+    }
+
+    #[getter]
+    fn log_likelihood(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.log_likelihood)
+    }
+
+    #[getter]
+    fn null_log_likelihood(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.null_log_likelihood)
+    }
+
+    #[getter]
+    fn deviance(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.deviance())
+    }
+
+    #[getter]
+    fn null_deviance(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.null_deviance())
+    }
+
+    #[getter]
+    fn pseudo_r_squared(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.pseudo_r_squared())
+    }
+
+    #[getter]
+    fn aic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.aic())
+    }
+
+    #[getter]
+    fn bic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.bic())
+    }
+
+    fn __repr__(&self) -> String {
+        let penalty = match self.penalty {
+            Penalty::None => "none",
+            Penalty::L1 => "l1",
+            Penalty::L2 => "l2",
+        };
+        format!("LogisticRegression(penalty='{}', alpha={}, fit_intercept={})", penalty, self.alpha,
+                if self.fit_intercept { "True" } else { "False" })
+    }
+}
@@ -1,137 +1,116 @@
-// // linear models mod.rs
-//
-// // macro to ensure data makes sense for a linear regression model
-//
-// // trait for linear models?
-//
-// // Methods to fit and predict?
-//
-// // or just a function?
-//
-// // Define your macro for data validation here
-// use ndarray::{Array1, ArrayBase, Data, Ix1};
-//
-// pub trait LinearModel {
-//     fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>);
-//     fn predict(&self, features: &Array2<f64>) -> Array1<f64>;
-//     fn score(&self, features: &Array2<f64>, metric: &str) -> f64;
-//     fn coef(&self) -> &Array1<f64>;
-//     //fn visualize(&self) -> visualization of linear model.
-//     // Don't know how this will work in practice
-// }
-// pub trait Scorer {
-//     fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> f64;
-// }
-//
-// #[macro_export] macro_rules! validate_linear_model_input {
-//     ($features:expr, $target:expr) => {
-//         if $features.is_empty() {
-//             return Err(LinearModelError::EmptyDataSet.into());
-//         }
-//         if $features.ncols() < 1 || $features.ncols() > 100 {
-//             return Err(LinearModelError::InvalidFeatures.into());
-//         }
-//         if $features.genrows().into_iter().any(|row| row.len() != $features.ncols()) {
-//             return Err(LinearModelError::InconsistentLength.into());
-//         }
-//         if $features.iter().any(|&val| val.is_nan() || val.is_infinite()) {
-//             return Err(LinearModelError::InvalidInputValue.into());
-//         }
-//         if $target.is_empty() {
-//             return Err(LinearModelError::EmptyDataSet.into());
-//         }
-//         if $target.ndim() != 1 || $target.len() != $features.nrows() {
-//             return Err(LinearModelError::InconsistentLength.into());
-//         }
-//     };
-// }
-//
-// pub struct LinearRegression {
-//     coef: Vec<f64>,
-//     scorer: Box<dyn Scorer>,
-//     // must build new error codes for if the scorer method inst valid
-//     // regression scorers for Logit
-//     // Logit Scorers for regressions
-// }
-// pub struct LogisticRegression {
-//     coef: Vec<f64>,
-//     scorer: Box<dyn Scorer>
-// }
-//
-// struct MeanSquaredErrorScorer;
-//
-// impl Scorer for MeanSquaredErrorScorer {
-//     fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> f64 {
-//         // zip method
-//         // validate_linear_model_input!(scorer, $items)
-//         let mse = predictions.iter()
-//             .zip(actuals.iter())
-//             .map(|(pred, actual)| (pred - actual).powi(2))
-//             .sum::<f64>() / predictions.len() as f64;
-//
-//         Ok(mse)
-//     }
-// }
-// impl LinearModel for LinearRegression {
-//     fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) {
-//         let learning_rate = 0.01;
-//         let iterations = 1000;
-//         let m = features.nrows() as f64;
-//
-//         self.coef = vec![0.0; features.ncols() + 1];
-//
-//         for _ in 0..iterations {
-//             let predictions = self.predict(features);
-//             let errors = &predictions - target;
-//             let gradient = features.t().dot(&errors) / m;
-//
-//             for (c, g) in self.coef.iter_mut().zip(gradient.iter()) {
-//                 *c -= learning_rate * g;
-//             }
-//         }
-//     }
-//
-//     fn predict(&self, features: &Array2<f64>) -> Array1<f64> {
-//         let mut predictions = Array1::zeros(features.nrows());
-//
-//         for (i, feature_row) in features.outer_iter().enumerate() {
-//             let mut prediction = self.coef[0];
-//             for (feature, &coef) in feature_row.iter().zip(&self.coef[1..]) {
-//                 prediction += feature * coef;
-//             }
-//             predictions[i] = prediction;
-//         }
-//         predictions
-//     }
-//
-//     fn score(&self, features: &Array2<f64>, actuals: &Array1<f64>) -> f64 {
-//         let predictions = self.predict(features);
-//         self.scorer.score(&predictions, actuals)
-//     }
-//
-//     fn coef(&self) -> &Vec<f64> {
-//         &self.coef
-//     }
-// }
-//
-//
-//
-// // Input:
-// // X = Matrix of input features (each row is a training example, each column is a feature)
-// // y = Vector of target values
-// // alpha = Learning rate
-// // iterations = Number of iterations for the gradient descent
-// //
-// // Output:
-// // beta = Vector of fitted coefficients (including intercept)
-// //
-// // Procedure:
-// // 1: Initialize beta (e.g., to zeros or small random numbers)
-// // 2: for iter in 1 to iterations do
-// // 3:     predictions = X * beta               // Calculate predictions for the current beta
-// // 4:     errors = predictions - y             // Compute the difference between predictions and actual values
-// // 5:     for j in 1 to length(beta) do
-// // 6:         gradient_j = sum(errors * X[:, j]) / length(y)  // Calculate the gradient for beta_j
-// // 7:         beta[j] = beta[j] - alpha * gradient_j          // Update beta_j
-// // 8:     end for
-// // 9: end for
\ No newline at end of file
+// linear models mod.rs
+// covariance -- HC0-HC3, clustered and Newey-West sandwich covariances for OLS
+// diagnostics -- residual types, leverage, influence measures, VIF and specification tests for OLS fits
+// errors -- LinearModelError and the python exception classes it maps to
+// formula -- Formula, patsy-style "y ~ x1 + C(group)" formulas building design matrices from polars
+//            DataFrames for any of the estimators
+// glm -- GLM, generalized linear models over exponential families fit by IRLS
+// linalg -- householder QR and cholesky helpers shared by the estimators
+// logistic -- LogisticRegression, binary and multinomial, with inference for unpenalized fits
+// ols -- LinearRegression, ordinary least squares with inference
+// regularized -- Ridge, Lasso and ElasticNet, with regularization paths
+// robust -- RobustLinearModel (Huber / Tukey M-estimation), RANSACRegressor, TheilSenRegressor and
+//           QuantileRegression
+// scoring -- Scorer implementations and the by-name registry used by LinearModel::score
+//
+// Every model implements LinearModel, so any of them can be scored with any registered metric.
+pub mod covariance;
+pub mod diagnostics;
+pub mod errors;
+pub mod formula;
+pub mod glm;
+pub mod linalg;
+pub mod logistic;
+pub mod ols;
+pub mod regularized;
+pub mod robust;
+pub mod scoring;
+
+#[cfg(test)]
+mod unit_tests;
+
+use ndarray::{Array1, Array2, Axis};
+use pyo3::prelude::*;
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::scoring::scorer_rs;
+use crate::utils::{from_pyarray1, from_pyarray2};
+
+
+// Checks shared by every model: a non-empty, finite feature matrix and a finite target with one
+// value per row. (features, x) checks a matrix passed to predict on its own.
+#[macro_export] macro_rules! validate_linear_model_input {
+    (features, $features:expr) => {
+        if $features.is_empty() || $features.ncols() < 1 {
+            return Err(LinearModelError::EmptyDataSet.into());
+        }
+        if $features.iter().any(|val: &f64| val.is_nan() || val.is_infinite()) {
+            return Err(LinearModelError::InvalidInputValue.into());
+        }
+    };
+    ($features:expr, $target:expr) => {
+        $crate::validate_linear_model_input!(features, $features);
+        if $target.is_empty() {
+            return Err(LinearModelError::EmptyDataSet.into());
+        }
+        if $target.len() != $features.nrows() {
+            return Err(LinearModelError::ShapeMismatch.into());
+        }
+        if $target.iter().any(|val: &f64| val.is_nan() || val.is_infinite()) {
+            return Err(LinearModelError::InvalidInputValue.into());
+        }
+    };
+}
+
+
+pub trait LinearModel {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError>;
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError>;
+    // Slopes, without the intercept
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError>;
+
+    // Probability of the positive class, only classifiers provide one
+    fn predict_proba(&self, _features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        Err(LinearModelError::InvalidInputValue)
+    }
+
+    // Any registered metric, fed probabilities when the scorer needs them and predictions otherwise
+    fn score(&self, features: &Array2<f64>, target: &Array1<f64>, metric: &str) -> Result<f64, LinearModelError> {
+        let scorer = scorer_rs(metric)?;
+        let predictions = if scorer.needs_probabilities() { self.predict_proba(features)? } else { self.predict(features)? };
+        scorer.score(&predictions, target)
+    }
+}
+
+pub trait Scorer {
+    fn name(&self) -> &'static str;
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError>;
+
+    // Losses are minimised, so model selection needs to know which way is better
+    fn greater_is_better(&self) -> bool {
+        true
+    }
+
+    fn needs_probabilities(&self) -> bool {
+        false
+    }
+}
+
+
+// Feature matrix from a 2-d array, or a single feature column from a 1-d array
+pub(crate) fn features_from_py(x: &PyAny) -> PyResult<Array2<f64>> {
+    if let Ok(features) = from_pyarray2(x) {
+        return Ok(features);
+    }
+    match from_pyarray1(x) {
+        Ok(feature) => Ok(feature.insert_axis(Axis(1))),
+        Err(_e) => Err(LinearModelError::Conversion.into()),
+    }
+}
+
+pub(crate) fn target_from_py(y: &PyAny) -> PyResult<Array1<f64>> {
+    match from_pyarray1(y) {
+        Ok(data) => Ok(data),
+        Err(_e) => Err(LinearModelError::Conversion.into()),
+    }
+}
@@ -0,0 +1,497 @@
+// Ordinary least squares
+// LinearRegression -- OLS solved by householder QR (default) or cholesky of X'X, with an optional
+//                     intercept, fit / predict / score and statsmodels style inference:
+//                     standard errors, t values, p-values, confidence intervals, R^2, adjusted R^2,
+//                     F statistic, log-likelihood, AIC and BIC, plus a printable summary()
+//                     Standard errors can be nonrobust, HC0-HC3, clustered or Newey-West (see
+//                     covariance), chosen in fit() or for a single summary()
+//
+// Parameters are ordered intercept first. Without an intercept R^2 and F are uncentered, as in
+// statsmodels.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{Array1, Array2};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use statrs::distribution::{ContinuousCDF, FisherSnedecor, StudentsT};
+
+use crate::linear_models::covariance::{sandwich_rs, CovType};
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::{features_from_py, LinearModel};
+use crate::linear_models::linalg::{cholesky_rs, cholesky_solve_rs, upper_triangular_inverse_rs, QrDecomposition};
+use crate::utils::{from_pyarray1, invert_rs};
+use crate::validate_linear_model_input;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Solver {
+    Qr,
+    Cholesky,
+}
+
+impl Solver {
+    pub fn parse(solver: &str) -> Result<Solver, LinearModelError> {
+        match solver {
+            "qr" => Ok(Solver::Qr),
+            "cholesky" => Ok(Solver::Cholesky),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+}
+
+// Prepends a column of ones
+pub fn add_intercept_rs(x: &Array2<f64>) -> Array2<f64> {
+    let mut design = Array2::<f64>::ones((x.nrows(), x.ncols() + 1));
+    design.slice_mut(ndarray::s![.., 1..]).assign(x);
+    design
+}
+
+#[derive(Debug, Clone)]
+pub struct OlsFit {
+    pub params: Array1<f64>,
+    pub fit_intercept: bool,
+    // the regressors actually used, including the intercept column
+    pub design: Array2<f64>,
+    pub xtx_inverse: Array2<f64>,
+    pub residuals: Array1<f64>,
+    pub fitted_values: Array1<f64>,
+    pub nobs: usize,
+    pub df_model: f64,
+    pub df_resid: f64,
+    // residual sum of squares and the (centered with an intercept) total sum of squares
+    pub ssr: f64,
+    pub tss: f64,
+    pub cov_type: CovType,
+    // sandwich covariance of the params, None for the classical s^2 (X'X)^-1
+    pub robust_cov: Option<Array2<f64>>,
+}
+
+pub fn ols_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool, solver: Solver) -> Result<OlsFit, LinearModelError> {
+    validate_linear_model_input!(x, y);
+
+    let design = if fit_intercept { add_intercept_rs(x) } else { x.clone() };
+    let (n, k) = design.dim();
+    if n <= k { return Err(LinearModelError::InsufficientObservations); }
+
+    let (params, xtx_inverse) = match solver {
+        Solver::Qr => {
+            let qr = QrDecomposition::new(&design);
+            if qr.rank() < k { return Err(LinearModelError::RankDeficient); }
+            let params = qr.solve(y).ok_or(LinearModelError::SingularMatrix)?;
+            let r_inverse = upper_triangular_inverse_rs(&qr.r).ok_or(LinearModelError::SingularMatrix)?;
+            (params, r_inverse.dot(&r_inverse.t()))
+        }
+        Solver::Cholesky => {
+            let l = cholesky_rs(&design.t().dot(&design)).ok_or(LinearModelError::SingularMatrix)?;
+            let params = cholesky_solve_rs(&l, &design.t().dot(y)).ok_or(LinearModelError::SingularMatrix)?;
+            let mut inverse = Array2::<f64>::zeros((k, k));
+            for j in 0..k {
+                let mut e = Array1::<f64>::zeros(k);
+                e[j] = 1.0;
+                inverse.column_mut(j).assign(&cholesky_solve_rs(&l, &e).ok_or(LinearModelError::SingularMatrix)?);
+            }
+            (params, inverse)
+        }
+    };
+
+    let fitted_values = design.dot(&params);
+    let residuals = y - &fitted_values;
+    let ssr = residuals.dot(&residuals);
+    let tss = if fit_intercept {
+        let mean = y.mean().unwrap();
+        y.iter().map(|v| (v - mean).powi(2)).sum()
+    } else {
+        y.dot(y)
+    };
+    let df_model = (k - usize::from(fit_intercept)) as f64;
+    Ok(OlsFit {
+        params,
+        fit_intercept,
+        design,
+        xtx_inverse,
+        residuals,
+        fitted_values,
+        nobs: n,
+        df_model,
+        df_resid: (n - k) as f64,
+        ssr,
+        tss,
+        cov_type: CovType::Nonrobust,
+        robust_cov: None,
+    })
+}
+
+impl OlsFit {
+    // Residual variance ssr / df_resid
+    pub fn scale(&self) -> f64 {
+        self.ssr / self.df_resid
+    }
+
+    // The same fit reporting the given covariance type
+    pub fn with_cov_type(&self, cov_type: CovType) -> Result<OlsFit, LinearModelError> {
+        let mut fit = self.clone();
+        fit.robust_cov = sandwich_rs(&cov_type, &self.design, &self.residuals, &self.xtx_inverse)?;
+        fit.cov_type = cov_type;
+        Ok(fit)
+    }
+
+    pub fn cov_params(&self) -> Array2<f64> {
+        match &self.robust_cov {
+            Some(cov) => cov.clone(),
+            None => &self.xtx_inverse * self.scale(),
+        }
+    }
+
+    // Degrees of freedom of the t and F reference distributions
+    pub fn inference_df(&self) -> f64 {
+        self.cov_type.inference_df(self.df_resid)
+    }
+
+    pub fn standard_errors(&self) -> Array1<f64> {
+        self.cov_params().diag().mapv(f64::sqrt)
+    }
+
+    pub fn t_values(&self) -> Array1<f64> {
+        &self.params / &self.standard_errors()
+    }
+
+    pub fn p_values(&self) -> Array1<f64> {
+        let t = StudentsT::new(0.0, 1.0, self.inference_df()).unwrap();
+        self.t_values().mapv(|value| 2.0 * t.sf(value.abs()))
+    }
+
+    // (lower, upper) per parameter, one row each
+    pub fn confidence_intervals(&self, alpha: f64) -> Result<Array2<f64>, LinearModelError> {
+        if alpha <= 0.0 || alpha >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+        let critical = StudentsT::new(0.0, 1.0, self.inference_df()).unwrap().inverse_cdf(1.0 - alpha / 2.0);
+        let se = self.standard_errors();
+        let mut intervals = Array2::<f64>::zeros((self.params.len(), 2));
+        intervals.column_mut(0).assign(&(&self.params - &(&se * critical)));
+        intervals.column_mut(1).assign(&(&self.params + &(&se * critical)));
+        Ok(intervals)
+    }
+
+    pub fn r_squared(&self) -> f64 {
+        1.0 - self.ssr / self.tss
+    }
+
+    pub fn adj_r_squared(&self) -> f64 {
+        let total_df = self.nobs as f64 - if self.fit_intercept { 1.0 } else { 0.0 };
+        1.0 - (1.0 - self.r_squared()) * total_df / self.df_resid
+    }
+
+    // Overall F test of every slope being zero, NaN for a model with no slopes. With a robust
+    // covariance this is the Wald form b' V^-1 b / q on the slopes.
+    pub fn f_test(&self) -> (f64, f64) {
+        if self.df_model == 0.0 { return (f64::NAN, f64::NAN); }
+        let f = match &self.robust_cov {
+            None => ((self.tss - self.ssr) / self.df_model) / self.scale(),
+            Some(cov) => {
+                let start = usize::from(self.fit_intercept);
+                let slopes = self.params.slice(ndarray::s![start..]);
+                match invert_rs(&cov.slice(ndarray::s![start.., start..]).to_owned()) {
+                    Some(inverse) => slopes.dot(&inverse.dot(&slopes)) / self.df_model,
+                    None => return (f64::NAN, f64::NAN),
+                }
+            }
+        };
+        let p = FisherSnedecor::new(self.df_model, self.inference_df()).unwrap().sf(f);
+        (f, p)
+    }
+
+    pub fn log_likelihood(&self) -> f64 {
+        let n = self.nobs as f64;
+        -n / 2.0 * ((2.0 * std::f64::consts::PI).ln() + (self.ssr / n).ln() + 1.0)
+    }
+
+    pub fn aic(&self) -> f64 {
+        -2.0 * self.log_likelihood() + 2.0 * self.params.len() as f64
+    }
+
+    pub fn bic(&self) -> f64 {
+        -2.0 * self.log_likelihood() + (self.nobs as f64).ln() * self.params.len() as f64
+    }
+
+    // Predictions for new features, without the intercept column
+    pub fn predict(&self, x: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        let expected = self.params.len() - usize::from(self.fit_intercept);
+        validate_linear_model_input!(features, x);
+        if x.ncols() != expected { return Err(LinearModelError::ShapeMismatch); }
+        let design = if self.fit_intercept { add_intercept_rs(x) } else { x.clone() };
+        Ok(design.dot(&self.params))
+    }
+
+    pub fn parameter_names(&self) -> Vec<String> {
+        let slopes = self.params.len() - usize::from(self.fit_intercept);
+        let mut names: Vec<String> = if self.fit_intercept { vec!["const".to_string()] } else { vec![] };
+        names.extend((1..=slopes).map(|i| format!("x{}", i)));
+        names
+    }
+
+    // Text table in the layout of statsmodels' OLS summary
+    pub fn summary(&self, names: &[String], dependent: &str) -> String {
+        let (f, f_p) = self.f_test();
+        let rule = "=".repeat(78);
+        let row = |l1: &str, v1: String, l2: &str, v2: String| format!("{:<16}{:>22}   {:<20}{:>17}", l1, v1, l2, v2);
+        let mut lines = vec![
+            format!("{:^78}", "OLS Regression Results"),
+            rule.clone(),
+            row("Dep. Variable:", dependent.to_string(), "R-squared:", format!("{:.3}", self.r_squared())),
+            row("Model:", "OLS".to_string(), "Adj. R-squared:", format!("{:.3}", self.adj_r_squared())),
+            row("Method:", "Least Squares".to_string(), "F-statistic:", format!("{:.4}", f)),
+            row("No. Observations:", self.nobs.to_string(), "Prob (F-statistic):", format!("{:.3e}", f_p)),
+            row("Df Residuals:", self.df_resid.to_string(), "Log-Likelihood:", format!("{:.4}", self.log_likelihood())),
+            row("Df Model:", self.df_model.to_string(), "AIC:", format!("{:.4}", self.aic())),
+            row("Covariance Type:", self.cov_type.name(), "BIC:", format!("{:.4}", self.bic())),
+            rule.clone(),
+            format!("{:<10}{:>11}{:>11}{:>11}{:>11}{:>12}{:>12}", "", "coef", "std err", "t", "P>|t|", "[0.025", "0.975]"),
+            "-".repeat(78),
+        ];
+        let (se, t, p) = (self.standard_errors(), self.t_values(), self.p_values());
+        let intervals = self.confidence_intervals(0.05).unwrap();
+        for (i, name) in names.iter().enumerate() {
+            lines.push(format!("{:<10}{:>11.4}{:>11.3}{:>11.3}{:>11.3}{:>12.3}{:>12.3}",
+                               name, self.params[i], se[i], t[i], p[i], intervals[[i, 0]], intervals[[i, 1]]));
+        }
+        lines.push(rule);
+        lines.join("\n")
+    }
+}
+
+
+/// Pyclass
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct LinearRegression {
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    pub solver: Solver,
+    pub fit: Option<OlsFit>,
+    names: Vec<String>,
+}
+
+impl LinearModel for LinearRegression {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        let fit = ols_rs(features, target, self.fit_intercept, self.solver)?;
+        self.names = fit.parameter_names();
+        self.fit = Some(fit);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        let fit = self.fitted()?;
+        Ok(fit.params.slice(ndarray::s![usize::from(fit.fit_intercept)..]).to_owned())
+    }
+}
+
+impl LinearRegression {
+    pub fn new_rs(fit_intercept: bool, solver: Solver) -> LinearRegression {
+        LinearRegression { fit_intercept, solver, fit: None, names: vec![] }
+    }
+
+    fn fitted(&self) -> Result<&OlsFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+fn cov_type_from_py(cov_type: &str, groups: Option<&PyAny>, maxlags: Option<usize>, nobs: usize) -> PyResult<CovType> {
+    let groups = match groups {
+        Some(groups) => Some(features_from_py(groups)?),
+        None => None,
+    };
+    Ok(CovType::parse(cov_type, groups.as_ref(), maxlags, nobs)?)
+}
+
+#[pymethods]
+impl LinearRegression {
+    #[new]
+    #[pyo3(signature = (fit_intercept = true, solver = "qr"))]
+    fn new(fit_intercept: bool, solver: &str) -> PyResult<Self> {
+        // solver: qr (householder, default) or cholesky (normal equations, faster but less stable)
+        Ok(LinearRegression::new_rs(fit_intercept, Solver::parse(solver)?))
+    }
+
+    #[pyo3(signature = (x, y, feature_names = None, cov_type = "nonrobust", groups = None, maxlags = None))]
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny, feature_names: Option<Vec<String>>, cov_type: &str,
+                groups: Option<&PyAny>, maxlags: Option<usize>) -> PyResult<PyRefMut<'py, Self>> {
+        // x is (n, k), or a 1-d array for a single feature. Returns the model for chaining.
+        // cov_type: nonrobust, hc0, hc1, hc2, hc3, cluster (groups of shape (n,) or (n, 2) for
+        // two-way) or hac (Newey-West, optional maxlags)
+        let x_data = features_from_py(x)?;
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        if let Some(features) = &feature_names {
+            if features.len() != x_data.ncols() { return Err(LinearModelError::ShapeMismatch.into()); }
+        }
+        let cov_type = cov_type_from_py(cov_type, groups, maxlags, y_data.len())?;
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        if cov_type != CovType::Nonrobust {
+            let fit = slf.fitted()?.with_cov_type(cov_type)?;
+            slf.fit = Some(fit);
+        }
+        if let Some(features) = feature_names {
+            let intercept = usize::from(slf.fit_intercept);
+            slf.names.truncate(intercept);
+            slf.names.extend(features);
+        }
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        // Any name listed by scorers(), R^2 by default
+        let y_data = match from_pyarray1(y) {
+            Ok(data) => data,
+            Err(_e) => return Err(LinearModelError::Conversion.into()),
+        };
+        Ok(LinearModel::score(self, &features_from_py(x)?, &y_data, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn parameter_names(&self) -> PyResult<Vec<String>> {
+        self.fitted()?;
+        Ok(self.names.clone())
+    }
+
+    #[getter]
+    fn standard_errors<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.standard_errors().into_pyarray(py))
+    }
+
+    #[getter]
+    fn t_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.t_values().into_pyarray(py))
+    }
+
+    #[getter]
+    fn p_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.p_values().into_pyarray(py))
+    }
+
+    #[pyo3(signature = (alpha = 0.05))]
+    fn confidence_intervals<'py>(&self, py: Python<'py>, alpha: f64) -> PyResult<&'py PyArray2<f64>> {
+        // (k, 2) array of lower and upper bounds
+        Ok(self.fitted()?.confidence_intervals(alpha)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn cov_params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
+        Ok(self.fitted()?.cov_params().into_pyarray(py))
+    }
+
+    #[getter]
+    fn r_squared(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.r_squared())
+    }
+
+    #[getter]
+    fn adj_r_squared(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.adj_r_squared())
+    }
+
+    #[getter]
+    fn f_statistic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.f_test().0)
+    }
+
+    #[getter]
+    fn f_p_value(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.f_test().1)
+    }
+
+    #[getter]
+    fn log_likelihood(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.log_likelihood())
+    }
+
+    #[getter]
+    fn aic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.aic())
+    }
+
+    #[getter]
+    fn bic(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.bic())
+    }
+
+    #[getter]
+    fn nobs(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.nobs)
+    }
+
+    #[getter]
+    fn df_model(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.df_model)
+    }
+
+    #[getter]
+    fn df_resid(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.df_resid)
+    }
+
+    #[getter]
+    fn residuals<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.residuals.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn fitted_values<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.fitted_values.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn cov_type(&self) -> PyResult<String> {
+        Ok(self.fitted()?.cov_type.name())
+    }
+
+    #[pyo3(signature = (dependent = "y", cov_type = None, groups = None, maxlags = None))]
+    fn summary(&self, dependent: &str, cov_type: Option<&str>, groups: Option<&PyAny>, maxlags: Option<usize>)
+               -> PyResult<String> {
+        // cov_type overrides the covariance chosen in fit() for this summary only
+        let fit = self.fitted()?;
+        match cov_type {
+            Some(name) => {
+                let robust = fit.with_cov_type(cov_type_from_py(name, groups, maxlags, fit.nobs)?)?;
+                Ok(robust.summary(&self.names, dependent))
+            }
+            None => Ok(fit.summary(&self.names, dependent)),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        let solver = match self.solver { Solver::Qr => "qr", Solver::Cholesky => "cholesky" };
+        let fit_intercept = if self.fit_intercept { "True" } else { "False" };
+        format!("LinearRegression(fit_intercept={}, solver='{}')", fit_intercept, solver)
+    }
+}
@@ -0,0 +1,560 @@
+// Regularized least squares
+// Ridge -- squared l2 penalty, solved in closed form (cholesky) or through the SVD
+// Lasso / ElasticNet -- l1 (plus l2) penalty by cyclic coordinate descent, with warm starts
+// ridge_path / enet_path -- coefficients over a grid of alphas, as a RegularizationPath
+//
+// Objectives follow scikit-learn:
+//   ridge        ||y - Xb||^2 + alpha ||b||^2
+//   elastic net  1/(2n) ||y - Xb||^2 + alpha l1_ratio ||b||_1 + alpha (1 - l1_ratio) / 2 ||b||^2
+// The intercept is never penalized: with fit_intercept the features and target are centered
+// first. With standardize the features are also scaled to unit (population) variance and the
+// coefficients are mapped back to the original scale afterwards.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{Array1, Array2, Axis};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::{features_from_py, target_from_py, LinearModel};
+use crate::linear_models::linalg::{cholesky_rs, cholesky_solve_rs, svd_rs};
+use crate::validate_linear_model_input;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RidgeSolver {
+    Cholesky,
+    Svd,
+}
+
+impl RidgeSolver {
+    pub fn parse(solver: &str) -> Result<RidgeSolver, LinearModelError> {
+        match solver.to_lowercase().as_str() {
+            "cholesky" => Ok(RidgeSolver::Cholesky),
+            "svd" => Ok(RidgeSolver::Svd),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+}
+
+// Column means and scales taken off the features (and the mean off the target) before fitting
+#[derive(Debug, Clone)]
+pub struct Preprocessing {
+    pub x_mean: Array1<f64>,
+    pub x_scale: Array1<f64>,
+    pub y_mean: f64,
+}
+
+impl Preprocessing {
+    // Coefficients on the original scale, and the intercept that goes with them
+    pub fn restore(&self, coef: &Array1<f64>) -> (Array1<f64>, f64) {
+        let coef = coef / &self.x_scale;
+        let intercept = self.y_mean - self.x_mean.dot(&coef);
+        (coef, intercept)
+    }
+}
+
+pub fn preprocess_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool, standardize: bool)
+                     -> (Array2<f64>, Array1<f64>, Preprocessing) {
+    let p = x.ncols();
+    let (x_mean, y_mean) = if fit_intercept {
+        (x.mean_axis(Axis(0)).unwrap(), y.mean().unwrap())
+    } else {
+        (Array1::<f64>::zeros(p), 0.0)
+    };
+    let centered = x - &x_mean;
+    let x_scale = if standardize {
+        // a constant column is left unscaled rather than divided by zero
+        let n = x.nrows() as f64;
+        centered.map_axis(Axis(0), |column| {
+            let sd = (column.dot(&column) / n).sqrt();
+            if sd > 0.0 { sd } else { 1.0 }
+        })
+    } else {
+        Array1::<f64>::ones(p)
+    };
+    let scaled = &centered / &x_scale;
+    (scaled, y - y_mean, Preprocessing { x_mean, x_scale, y_mean })
+}
+
+// A fitted penalized model, with the coordinate descent diagnostics (zero for closed forms)
+#[derive(Debug, Clone)]
+pub struct PenalizedFit {
+    pub coef: Array1<f64>,
+    pub intercept: f64,
+    pub n_iter: usize,
+    pub dual_gap: f64,
+    pub converged: bool,
+}
+
+impl PenalizedFit {
+    pub fn predict(&self, x: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        validate_linear_model_input!(features, x);
+        if x.ncols() != self.coef.len() { return Err(LinearModelError::ShapeMismatch); }
+        Ok(x.dot(&self.coef) + self.intercept)
+    }
+}
+
+fn check_penalty(alpha: f64, l1_ratio: f64) -> Result<(), LinearModelError> {
+    if !alpha.is_finite() || alpha < 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    if !(0.0..=1.0).contains(&l1_ratio) { return Err(LinearModelError::InvalidInputValue); }
+    Ok(())
+}
+
+// Ridge coefficients of already preprocessed data, by the normal equations plus alpha on the diagonal
+fn ridge_cholesky_rs(x: &Array2<f64>, y: &Array1<f64>, alpha: f64) -> Result<Array1<f64>, LinearModelError> {
+    let p = x.ncols();
+    let gram = x.t().dot(x) + Array2::<f64>::eye(p) * alpha;
+    let l = cholesky_rs(&gram).ok_or(LinearModelError::SingularMatrix)?;
+    cholesky_solve_rs(&l, &x.t().dot(y)).ok_or(LinearModelError::SingularMatrix)
+}
+
+// Ridge coefficients for every alpha from a single SVD: b = V diag(s / (s^2 + alpha)) U'y.
+// Directions with a zero singular value get no weight, so alpha = 0 gives the minimum norm solution
+fn ridge_svd_rs(x: &Array2<f64>, y: &Array1<f64>, alphas: &[f64]) -> Vec<Array1<f64>> {
+    let (u, s, v) = svd_rs(x);
+    let uty = u.t().dot(y);
+    alphas.iter().map(|&alpha| {
+        let shrunk = Array1::from_iter(s.iter().zip(uty.iter()).map(|(&s, &uty)| {
+            if s > 0.0 { s * uty / (s * s + alpha) } else { 0.0 }
+        }));
+        v.dot(&shrunk)
+    }).collect()
+}
+
+pub fn ridge_rs(x: &Array2<f64>, y: &Array1<f64>, alpha: f64, fit_intercept: bool, standardize: bool,
+                solver: RidgeSolver) -> Result<PenalizedFit, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    check_penalty(alpha, 0.0)?;
+    let (xs, ys, preprocessing) = preprocess_rs(x, y, fit_intercept, standardize);
+    let coef = match solver {
+        RidgeSolver::Cholesky => ridge_cholesky_rs(&xs, &ys, alpha)?,
+        RidgeSolver::Svd => ridge_svd_rs(&xs, &ys, &[alpha]).remove(0),
+    };
+    let (coef, intercept) = preprocessing.restore(&coef);
+    Ok(PenalizedFit { coef, intercept, n_iter: 0, dual_gap: 0.0, converged: true })
+}
+
+// Duality gap of the elastic net in its unscaled form
+// 0.5 ||y - Xw||^2 + l1_reg ||w||_1 + 0.5 l2_reg ||w||^2, zero exactly at the optimum
+fn duality_gap_rs(x: &Array2<f64>, y: &Array1<f64>, w: &Array1<f64>, residuals: &Array1<f64>,
+                  l1_reg: f64, l2_reg: f64) -> f64 {
+    let xta = x.t().dot(residuals) - w * l2_reg;
+    let dual_norm = xta.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    let r_norm2 = residuals.dot(residuals);
+    let (constant, mut gap) = if dual_norm > l1_reg {
+        let constant = l1_reg / dual_norm;
+        (constant, 0.5 * (r_norm2 + r_norm2 * constant * constant))
+    } else {
+        (1.0, r_norm2)
+    };
+    let l1_norm: f64 = w.iter().map(|v| v.abs()).sum();
+    gap += l1_reg * l1_norm - constant * residuals.dot(y) + 0.5 * l2_reg * (1.0 + constant * constant) * w.dot(w);
+    gap
+}
+
+// Cyclic coordinate descent from `start` on preprocessed data. A sweep that moves no coefficient
+// by more than tol (relative to the largest) triggers a duality gap check, and the fit has
+// converged once the gap is below tol * ||y||^2
+pub fn coordinate_descent_rs(x: &Array2<f64>, y: &Array1<f64>, alpha: f64, l1_ratio: f64, start: Array1<f64>,
+                             max_iter: usize, tol: f64) -> PenalizedFit {
+    let n = x.nrows() as f64;
+    let (l1_reg, l2_reg) = (alpha * l1_ratio * n, alpha * (1.0 - l1_ratio) * n);
+    let column_norms: Vec<f64> = x.columns().into_iter().map(|column| column.dot(&column)).collect();
+    let mut w = start;
+    let mut residuals = y - &x.dot(&w);
+    let gap_tolerance = tol * y.dot(y);
+    let mut fit = PenalizedFit { coef: Array1::zeros(0), intercept: 0.0, n_iter: max_iter, dual_gap: f64::NAN, converged: false };
+
+    for iteration in 0..max_iter {
+        let (mut w_max, mut change_max) = (0.0_f64, 0.0_f64);
+        for (j, &norm) in column_norms.iter().enumerate() {
+            if norm == 0.0 { continue; }
+            let column = x.column(j);
+            let previous = w[j];
+            if previous != 0.0 { residuals.scaled_add(previous, &column); }
+            let rho = column.dot(&residuals);
+            w[j] = rho.signum() * (rho.abs() - l1_reg).max(0.0) / (norm + l2_reg);
+            if w[j] != 0.0 { residuals.scaled_add(-w[j], &column); }
+            change_max = change_max.max((w[j] - previous).abs());
+            w_max = w_max.max(w[j].abs());
+        }
+        if w_max == 0.0 || change_max / w_max < tol || iteration == max_iter - 1 {
+            fit.dual_gap = duality_gap_rs(x, y, &w, &residuals, l1_reg, l2_reg);
+            if fit.dual_gap <= gap_tolerance {
+                fit.n_iter = iteration + 1;
+                fit.converged = true;
+                break;
+            }
+        }
+    }
+    fit.coef = w;
+    fit
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn elastic_net_rs(x: &Array2<f64>, y: &Array1<f64>, alpha: f64, l1_ratio: f64, fit_intercept: bool,
+                      standardize: bool, max_iter: usize, tol: f64, start: Option<&Array1<f64>>)
+                      -> Result<PenalizedFit, LinearModelError> {
+    // start is a previous solution on the original scale, for warm starts
+    validate_linear_model_input!(x, y);
+    check_penalty(alpha, l1_ratio)?;
+    if max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    let (xs, ys, preprocessing) = preprocess_rs(x, y, fit_intercept, standardize);
+    let start = match start {
+        Some(coef) if coef.len() == x.ncols() => coef * &preprocessing.x_scale,
+        _ => Array1::<f64>::zeros(x.ncols()),
+    };
+    let fit = coordinate_descent_rs(&xs, &ys, alpha, l1_ratio, start, max_iter, tol);
+    let (coef, intercept) = preprocessing.restore(&fit.coef);
+    Ok(PenalizedFit { coef, intercept, ..fit })
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RegularizationPath {
+    // decreasing, one row of coefs per alpha
+    #[pyo3(get)]
+    pub alphas: Vec<f64>,
+    pub coefs: Array2<f64>,
+    #[pyo3(get)]
+    pub intercepts: Vec<f64>,
+    #[pyo3(get)]
+    pub dual_gaps: Vec<f64>,
+    #[pyo3(get)]
+    pub n_iters: Vec<usize>,
+    #[pyo3(get)]
+    pub converged: Vec<bool>,
+}
+
+fn sorted_alphas(alphas: Vec<f64>) -> Result<Vec<f64>, LinearModelError> {
+    if alphas.is_empty() { return Err(LinearModelError::EmptyDataSet); }
+    if alphas.iter().any(|&alpha| !alpha.is_finite() || alpha < 0.0) { return Err(LinearModelError::InvalidInputValue); }
+    let mut alphas = alphas;
+    alphas.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    Ok(alphas)
+}
+
+pub fn ridge_path_rs(x: &Array2<f64>, y: &Array1<f64>, alphas: Vec<f64>, fit_intercept: bool, standardize: bool)
+                     -> Result<RegularizationPath, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    let alphas = sorted_alphas(alphas)?;
+    let (xs, ys, preprocessing) = preprocess_rs(x, y, fit_intercept, standardize);
+    let mut coefs = Array2::<f64>::zeros((alphas.len(), x.ncols()));
+    let mut intercepts = Vec::with_capacity(alphas.len());
+    for (i, coef) in ridge_svd_rs(&xs, &ys, &alphas).iter().enumerate() {
+        let (coef, intercept) = preprocessing.restore(coef);
+        coefs.row_mut(i).assign(&coef);
+        intercepts.push(intercept);
+    }
+    let steps = alphas.len();
+    Ok(RegularizationPath { alphas, coefs, intercepts, dual_gaps: vec![0.0; steps], n_iters: vec![0; steps],
+                            converged: vec![true; steps] })
+}
+
+// Elastic net over a decreasing grid, each fit warm started from the previous one. Without
+// explicit alphas the grid runs geometrically from the smallest alpha that zeroes every
+// coefficient down to eps times it
+#[allow(clippy::too_many_arguments)]
+pub fn enet_path_rs(x: &Array2<f64>, y: &Array1<f64>, l1_ratio: f64, alphas: Option<Vec<f64>>, n_alphas: usize,
+                    eps: f64, fit_intercept: bool, standardize: bool, max_iter: usize, tol: f64)
+                    -> Result<RegularizationPath, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    check_penalty(0.0, l1_ratio)?;
+    if max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    let (xs, ys, preprocessing) = preprocess_rs(x, y, fit_intercept, standardize);
+    let alphas = match alphas {
+        Some(alphas) => sorted_alphas(alphas)?,
+        None => {
+            if l1_ratio == 0.0 || n_alphas == 0 || eps <= 0.0 || eps >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+            let n = x.nrows() as f64;
+            let alpha_max = xs.t().dot(&ys).iter().fold(0.0_f64, |m, v| m.max(v.abs())) / (n * l1_ratio);
+            if n_alphas == 1 { vec![alpha_max] } else {
+                (0..n_alphas).map(|i| alpha_max * eps.powf(i as f64 / (n_alphas - 1) as f64)).collect()
+            }
+        }
+    };
+
+    let steps = alphas.len();
+    let mut path = RegularizationPath {
+        alphas: alphas.clone(),
+        coefs: Array2::<f64>::zeros((steps, x.ncols())),
+        intercepts: Vec::with_capacity(steps),
+        dual_gaps: Vec::with_capacity(steps),
+        n_iters: Vec::with_capacity(steps),
+        converged: Vec::with_capacity(steps),
+    };
+    let mut start = Array1::<f64>::zeros(x.ncols());
+    for (i, &alpha) in alphas.iter().enumerate() {
+        let fit = coordinate_descent_rs(&xs, &ys, alpha, l1_ratio, start, max_iter, tol);
+        let (coef, intercept) = preprocessing.restore(&fit.coef);
+        path.coefs.row_mut(i).assign(&coef);
+        path.intercepts.push(intercept);
+        path.dual_gaps.push(fit.dual_gap);
+        path.n_iters.push(fit.n_iter);
+        path.converged.push(fit.converged);
+        start = fit.coef;
+    }
+    Ok(path)
+}
+
+/// Pyclasses
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Ridge {
+    #[pyo3(get)]
+    pub alpha: f64,
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub standardize: bool,
+    pub solver: RidgeSolver,
+    pub fit: Option<PenalizedFit>,
+}
+
+impl LinearModel for Ridge {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(ridge_rs(features, target, self.alpha, self.fit_intercept, self.standardize, self.solver)?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef.clone())
+    }
+}
+
+impl Ridge {
+    pub fn new_rs(alpha: f64, fit_intercept: bool, standardize: bool, solver: RidgeSolver) -> Result<Ridge, LinearModelError> {
+        check_penalty(alpha, 0.0)?;
+        Ok(Ridge { alpha, fit_intercept, standardize, solver, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&PenalizedFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+#[pymethods]
+impl Ridge {
+    #[new]
+    #[pyo3(signature = (alpha = 1.0, fit_intercept = true, standardize = false, solver = "cholesky"))]
+    fn new(alpha: f64, fit_intercept: bool, standardize: bool, solver: &str) -> PyResult<Self> {
+        // solver: cholesky (closed form) or svd (stable for ill-conditioned or wide designs)
+        Ok(Ridge::new_rs(alpha, fit_intercept, standardize, RidgeSolver::parse(solver)?)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.intercept)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Ridge(alpha={}, fit_intercept={}, standardize={})", self.alpha,
+                if self.fit_intercept { "True" } else { "False" }, if self.standardize { "True" } else { "False" })
+    }
+}
+
+// Defines a coordinate descent model class. Lasso and ElasticNet differ only in their
+// constructors, Lasso fixing l1_ratio at one. `$settings` maps the constructor arguments to
+// (alpha, l1_ratio, fit_intercept, standardize, max_iter, tol, warm_start).
+macro_rules! coordinate_descent_model {
+    ($name:ident, $py_name:literal, ($($signature:tt)*), ($($param:ident: $ty:ty),*) => $settings:expr) => {
+        #[pyclass(name = $py_name)]
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            #[pyo3(get)]
+            pub alpha: f64,
+            #[pyo3(get)]
+            pub l1_ratio: f64,
+            #[pyo3(get, set)]
+            pub fit_intercept: bool,
+            #[pyo3(get)]
+            pub standardize: bool,
+            #[pyo3(get)]
+            pub max_iter: usize,
+            #[pyo3(get)]
+            pub tol: f64,
+            // refits start from the previous coefficients instead of zero
+            #[pyo3(get, set)]
+            pub warm_start: bool,
+            pub fit: Option<PenalizedFit>,
+        }
+
+        impl LinearModel for $name {
+            fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+                let start = if self.warm_start { self.fit.as_ref().map(|fit| fit.coef.clone()) } else { None };
+                self.fit = Some(elastic_net_rs(features, target, self.alpha, self.l1_ratio, self.fit_intercept,
+                                               self.standardize, self.max_iter, self.tol, start.as_ref())?);
+                Ok(())
+            }
+
+            fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+                self.fitted()?.predict(features)
+            }
+
+            fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+                Ok(self.fitted()?.coef.clone())
+            }
+        }
+
+        impl $name {
+            #[allow(clippy::too_many_arguments)]
+            pub fn new_rs($($param: $ty),*) -> Result<$name, LinearModelError> {
+                let (alpha, l1_ratio, fit_intercept, standardize, max_iter, tol, warm_start) = $settings;
+                check_penalty(alpha, l1_ratio)?;
+                if max_iter == 0 || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+                Ok($name { alpha, l1_ratio, fit_intercept, standardize, max_iter, tol, warm_start, fit: None })
+            }
+
+            fn fitted(&self) -> Result<&PenalizedFit, LinearModelError> {
+                self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+            }
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = ($($signature)*))]
+            #[allow(clippy::too_many_arguments)]
+            fn new($($param: $ty),*) -> PyResult<Self> {
+                Ok($name::new_rs($($param),*)?)
+            }
+
+            fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+                // check converged / dual_gap afterwards, running out of iterations is not an error
+                let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+                LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+                Ok(slf)
+            }
+
+            fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+                Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+            }
+
+            #[pyo3(signature = (x, y, metric = "r2"))]
+            fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+                Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+            }
+
+            #[getter]
+            fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+                Ok(LinearModel::coef(self)?.into_pyarray(py))
+            }
+
+            #[getter]
+            fn intercept(&self) -> PyResult<f64> {
+                Ok(self.fitted()?.intercept)
+            }
+
+            #[getter]
+            fn n_iter(&self) -> PyResult<usize> {
+                Ok(self.fitted()?.n_iter)
+            }
+
+            #[getter]
+            fn dual_gap(&self) -> PyResult<f64> {
+                Ok(self.fitted()?.dual_gap)
+            }
+
+            #[getter]
+            fn converged(&self) -> PyResult<bool> {
+                Ok(self.fitted()?.converged)
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{}(alpha={}, l1_ratio={}, fit_intercept={}, standardize={})", $py_name, self.alpha,
+                        self.l1_ratio, if self.fit_intercept { "True" } else { "False" },
+                        if self.standardize { "True" } else { "False" })
+            }
+        }
+    };
+}
+
+coordinate_descent_model!(
+    Lasso, "Lasso",
+    (alpha = 1.0, fit_intercept = true, standardize = false, max_iter = 1000, tol = 1e-4, warm_start = false),
+    (alpha: f64, fit_intercept: bool, standardize: bool, max_iter: usize, tol: f64, warm_start: bool)
+        => (alpha, 1.0, fit_intercept, standardize, max_iter, tol, warm_start)
+);
+
+coordinate_descent_model!(
+    ElasticNet, "ElasticNet",
+    (alpha = 1.0, l1_ratio = 0.5, fit_intercept = true, standardize = false, max_iter = 1000, tol = 1e-4,
+     warm_start = false),
+    (alpha: f64, l1_ratio: f64, fit_intercept: bool, standardize: bool, max_iter: usize, tol: f64,
+     warm_start: bool) => (alpha, l1_ratio, fit_intercept, standardize, max_iter, tol, warm_start)
+);
+
+#[pymethods]
+impl RegularizationPath {
+    #[getter]
+    fn coefs<'py>(&self, py: Python<'py>) -> &'py PyArray2<f64> {
+        self.coefs.clone().into_pyarray(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RegularizationPath(n_alphas={}, n_features={}, all_converged={})", self.alphas.len(),
+                self.coefs.ncols(), if self.converged.iter().all(|&c| c) { "True" } else { "False" })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("alphas", self.alphas.clone())?;
+        dict.set_item("coefs", self.coefs.clone().into_pyarray(py))?;
+        dict.set_item("intercepts", self.intercepts.clone())?;
+        dict.set_item("dual_gaps", self.dual_gaps.clone())?;
+        dict.set_item("n_iters", self.n_iters.clone())?;
+        dict.set_item("converged", self.converged.clone())?;
+        Ok(dict)
+    }
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, y, alphas, fit_intercept = true, standardize = false))]
+pub fn ridge_path(x: &PyAny, y: &PyAny, alphas: Vec<f64>, fit_intercept: bool, standardize: bool)
+                  -> PyResult<RegularizationPath> {
+    // every alpha from one SVD of the design
+    Ok(ridge_path_rs(&features_from_py(x)?, &target_from_py(y)?, alphas, fit_intercept, standardize)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, y, l1_ratio = 1.0, alphas = None, n_alphas = 100, eps = 1e-3, fit_intercept = true,
+                    standardize = false, max_iter = 1000, tol = 1e-4))]
+#[allow(clippy::too_many_arguments)]
+pub fn enet_path(x: &PyAny, y: &PyAny, l1_ratio: f64, alphas: Option<Vec<f64>>, n_alphas: usize, eps: f64,
+                 fit_intercept: bool, standardize: bool, max_iter: usize, tol: f64) -> PyResult<RegularizationPath> {
+    // l1_ratio = 1 is the lasso path
+    Ok(enet_path_rs(&features_from_py(x)?, &target_from_py(y)?, l1_ratio, alphas, n_alphas, eps, fit_intercept,
+                    standardize, max_iter, tol)?)
+}
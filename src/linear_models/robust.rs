@@ -0,0 +1,683 @@
+// Outlier resistant regression
+// RobustLinearModel -- M-estimation with Huber or Tukey bisquare weights by IRLS, the scale
+//                      re-estimated each iteration as MAD / 0.6745 of the residuals (as statsmodels' RLM)
+// RANSACRegressor -- best consensus of OLS fits on random minimal subsets, refit on its inliers
+// TheilSenRegressor -- spatial median of the exact fits to every (or randomly sampled) subset of
+//                      k observations, as scikit-learn's TheilSenRegressor
+// QuantileRegression -- conditional quantile by IRLS on the check loss (as statsmodels' QuantReg)
+//
+// All fits start from, and are compared with, ordinary least squares. Parameters are intercept first.
+
+// pyo3 0.20 expands #[new] into impls nested in a function, which newer compilers flag
+#![allow(non_local_definitions)]
+
+use ndarray::{s, Array1, Array2, Axis};
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::descriptive_statistics::median_rs;
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::linalg::QrDecomposition;
+use crate::linear_models::ols::add_intercept_rs;
+use crate::linear_models::{features_from_py, target_from_py, LinearModel};
+use crate::validate_linear_model_input;
+
+
+// MAD / 0.6745 estimates the standard deviation of normal errors
+const MAD_NORMAL_CONSTANT: f64 = 0.6745;
+// Residuals are kept this far from zero in the quantile regression weights
+const QUANTILE_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Norm {
+    // weight 1 inside the tuning constant and c / |u| outside
+    Huber(f64),
+    // weight (1 - (u / c)^2)^2 inside the tuning constant and 0 outside
+    TukeyBisquare(f64),
+}
+
+impl Norm {
+    // Default tuning constants give 95% efficiency at the normal
+    pub fn parse(norm: &str, tuning: Option<f64>) -> Result<Norm, LinearModelError> {
+        if tuning.is_some_and(|c| !c.is_finite() || c <= 0.0) { return Err(LinearModelError::InvalidInputValue); }
+        match norm.to_lowercase().as_str() {
+            "huber" => Ok(Norm::Huber(tuning.unwrap_or(1.345))),
+            "bisquare" | "tukey" => Ok(Norm::TukeyBisquare(tuning.unwrap_or(4.685))),
+            _ => Err(LinearModelError::InvalidInputValue),
+        }
+    }
+
+    pub fn weight(&self, u: f64) -> f64 {
+        match self {
+            Norm::Huber(c) => if u.abs() <= *c { 1.0 } else { c / u.abs() },
+            Norm::TukeyBisquare(c) => if u.abs() < *c { (1.0 - (u / c).powi(2)).powi(2) } else { 0.0 },
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Norm::Huber(c) => format!("huber, tuning={}", c),
+            Norm::TukeyBisquare(c) => format!("bisquare, tuning={}", c),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RobustFit {
+    pub params: Array1<f64>,
+    pub fit_intercept: bool,
+    // final IRLS weights for the M and quantile estimators, 1 for inliers and 0 for outliers for
+    // RANSAC, and 1 for Theil-Sen
+    pub weights: Array1<f64>,
+    // MAD scale for M-estimation, the residual threshold for RANSAC and NaN otherwise
+    pub scale: f64,
+    // IRLS or Weiszfeld iterations, or RANSAC trials
+    pub n_iter: usize,
+}
+
+impl RobustFit {
+    pub fn predict(&self, x: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        validate_linear_model_input!(features, x);
+        if x.ncols() + usize::from(self.fit_intercept) != self.params.len() { return Err(LinearModelError::ShapeMismatch); }
+        let design = if self.fit_intercept { add_intercept_rs(x) } else { x.clone() };
+        Ok(design.dot(&self.params))
+    }
+
+    fn coef(&self) -> Array1<f64> {
+        self.params.slice(s![usize::from(self.fit_intercept)..]).to_owned()
+    }
+}
+
+fn design_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool) -> Result<Array2<f64>, LinearModelError> {
+    validate_linear_model_input!(x, y);
+    let design = if fit_intercept { add_intercept_rs(x) } else { x.clone() };
+    if design.nrows() <= design.ncols() { return Err(LinearModelError::InsufficientObservations); }
+    Ok(design)
+}
+
+// argmin sum w_i (y_i - x_i' b)^2 by QR of the rows scaled by sqrt(w_i)
+fn weighted_least_squares_rs(design: &Array2<f64>, y: &Array1<f64>, weights: &Array1<f64>)
+                             -> Result<Array1<f64>, LinearModelError> {
+    let roots = weights.mapv(f64::sqrt);
+    let weighted_design = design * &roots.view().insert_axis(Axis(1));
+    let qr = QrDecomposition::new(&weighted_design);
+    if qr.rank() < design.ncols() { return Err(LinearModelError::RankDeficient); }
+    qr.solve(&(y * &roots)).ok_or(LinearModelError::SingularMatrix)
+}
+
+fn max_change(new: &Array1<f64>, old: &Array1<f64>) -> f64 {
+    new.iter().zip(old.iter()).fold(0.0_f64, |m, (a, b)| m.max((a - b).abs()))
+}
+
+pub fn m_estimate_rs(x: &Array2<f64>, y: &Array1<f64>, norm: Norm, fit_intercept: bool, max_iter: usize, tol: f64)
+                     -> Result<RobustFit, LinearModelError> {
+    let design = design_rs(x, y, fit_intercept)?;
+    let n = design.nrows();
+    let mut params = weighted_least_squares_rs(&design, y, &Array1::ones(n))?;
+    for iteration in 0..max_iter {
+        let residuals = y - &design.dot(&params);
+        let scale = median_rs(&residuals.mapv(f64::abs)) / MAD_NORMAL_CONSTANT;
+        // more than half the points fitted exactly, nothing left to downweight
+        if scale == 0.0 {
+            return Ok(RobustFit { params, fit_intercept, weights: Array1::ones(n), scale, n_iter: iteration });
+        }
+        let weights = residuals.mapv(|r| norm.weight(r / scale));
+        let updated = weighted_least_squares_rs(&design, y, &weights)?;
+        let converged = max_change(&updated, &params) <= tol * (1.0 + params.iter().fold(0.0_f64, |m, v| m.max(v.abs())));
+        params = updated;
+        if converged {
+            return Ok(RobustFit { params, fit_intercept, weights, scale, n_iter: iteration + 1 });
+        }
+    }
+    Err(LinearModelError::NotConverged)
+}
+
+// threshold defaults to the median absolute deviation of y and min_samples to the number of
+// parameters; ties in the inlier count go to the consensus set with the smaller residual sum of squares
+#[allow(clippy::too_many_arguments)]
+pub fn ransac_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool, min_samples: Option<usize>,
+                 residual_threshold: Option<f64>, max_trials: usize, seed: u64) -> Result<RobustFit, LinearModelError> {
+    let design = design_rs(x, y, fit_intercept)?;
+    let (n, k) = design.dim();
+    let min_samples = min_samples.unwrap_or(k);
+    if min_samples < k || min_samples > n || max_trials == 0 { return Err(LinearModelError::InvalidInputValue); }
+    let threshold = match residual_threshold {
+        Some(t) if t.is_finite() && t >= 0.0 => t,
+        Some(_) => return Err(LinearModelError::InvalidInputValue),
+        None => {
+            let median = median_rs(y);
+            median_rs(&y.mapv(|v| (v - median).abs()))
+        }
+    };
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut best: Option<(usize, f64, Array1<f64>)> = None;
+    for _ in 0..max_trials {
+        let subset = sample(&mut rng, n, min_samples).into_vec();
+        let Ok(params) = weighted_least_squares_rs(&design.select(Axis(0), &subset), &y.select(Axis(0), &subset),
+                                                   &Array1::ones(min_samples)) else { continue };
+        let residuals = y - &design.dot(&params);
+        let inliers = residuals.mapv(|r| if r.abs() <= threshold { 1.0 } else { 0.0 });
+        let count = inliers.sum() as usize;
+        let ssr: f64 = residuals.iter().zip(inliers.iter()).map(|(r, w)| w * r * r).sum();
+        let better = match &best {
+            None => true,
+            Some((best_count, best_ssr, _)) => count > *best_count || (count == *best_count && ssr < *best_ssr),
+        };
+        if better { best = Some((count, ssr, inliers)); }
+    }
+    // every subset was degenerate
+    let (_count, _ssr, inliers) = best.ok_or(LinearModelError::NotConverged)?;
+    let params = weighted_least_squares_rs(&design, y, &inliers)?;
+    Ok(RobustFit { params, fit_intercept, weights: inliers, scale: threshold, n_iter: max_trials })
+}
+
+// n choose k, None once it passes `limit`
+fn combinations_within(n: usize, k: usize, limit: usize) -> Option<usize> {
+    let mut count: usize = 1;
+    for i in 0..k {
+        count = count.checked_mul(n - i)? / (i + 1);
+        if count > limit { return None; }
+    }
+    Some(count)
+}
+
+// Geometric median of the rows by Weiszfeld's iteration with the Vardi-Zhang fix for an iterate
+// landing on a data point
+fn spatial_median_rs(points: &Array2<f64>, max_iter: usize, tol: f64) -> Result<(Array1<f64>, usize), LinearModelError> {
+    let mut median = points.mean_axis(Axis(0)).unwrap();
+    for iteration in 0..max_iter {
+        let mut weighted_sum = Array1::<f64>::zeros(points.ncols());
+        let mut total_weight = 0.0;
+        let mut coincident = 0.0;
+        for point in points.axis_iter(Axis(0)) {
+            let distance = (&point - &median).mapv(|v| v * v).sum().sqrt();
+            if distance <= f64::EPSILON * (1.0 + median.iter().fold(0.0_f64, |m, v| m.max(v.abs()))) {
+                coincident += 1.0;
+            } else {
+                weighted_sum.scaled_add(1.0 / distance, &point);
+                total_weight += 1.0 / distance;
+            }
+        }
+        if total_weight == 0.0 { return Ok((median, iteration)); }
+        let mean = &weighted_sum / total_weight;
+        let pull = (&weighted_sum - &(&median * total_weight)).mapv(|v| v * v).sum().sqrt();
+        let updated = if coincident > 0.0 {
+            // stay on the data point when the others cannot pull it away
+            if pull <= coincident { return Ok((median, iteration)); }
+            let share = coincident / pull;
+            &mean * (1.0 - share) + &median * share
+        } else {
+            mean
+        };
+        let step = (&updated - &median).mapv(|v| v * v).sum().sqrt();
+        median = updated;
+        if step <= tol { return Ok((median, iteration + 1)); }
+    }
+    Err(LinearModelError::NotConverged)
+}
+
+// Exact fits to every k-subset when there are at most max_subpopulation of them, otherwise to
+// max_subpopulation random subsets
+#[allow(clippy::too_many_arguments)]
+pub fn theil_sen_rs(x: &Array2<f64>, y: &Array1<f64>, fit_intercept: bool, max_subpopulation: usize, max_iter: usize,
+                    tol: f64, seed: u64) -> Result<RobustFit, LinearModelError> {
+    let design = design_rs(x, y, fit_intercept)?;
+    let (n, k) = design.dim();
+    if max_subpopulation == 0 { return Err(LinearModelError::InvalidInputValue); }
+    let subsets: Vec<Vec<usize>> = match combinations_within(n, k, max_subpopulation) {
+        Some(_) => {
+            let mut subsets = vec![];
+            let mut current: Vec<usize> = (0..k).collect();
+            loop {
+                subsets.push(current.clone());
+                // advance the rightmost index that still has room
+                let Some(i) = (0..k).rev().find(|&i| current[i] < n - k + i) else { break };
+                current[i] += 1;
+                for j in i + 1..k {
+                    current[j] = current[j - 1] + 1;
+                }
+            }
+            subsets
+        }
+        None => {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            (0..max_subpopulation).map(|_| sample(&mut rng, n, k).into_vec()).collect()
+        }
+    };
+    let solutions: Vec<Array1<f64>> = subsets.iter().filter_map(|subset| {
+        let qr = QrDecomposition::new(&design.select(Axis(0), subset));
+        if qr.rank() < k { return None; }
+        qr.solve(&y.select(Axis(0), subset))
+    }).collect();
+    if solutions.is_empty() { return Err(LinearModelError::RankDeficient); }
+    let mut points = Array2::<f64>::zeros((solutions.len(), k));
+    for (i, solution) in solutions.iter().enumerate() {
+        points.row_mut(i).assign(solution);
+    }
+    let (params, n_iter) = spatial_median_rs(&points, max_iter, tol)?;
+    Ok(RobustFit { params, fit_intercept, weights: Array1::ones(n), scale: f64::NAN, n_iter })
+}
+
+// Weights q / |r| above the fit and (1 - q) / |r| below turn the check loss into least squares
+pub fn quantile_regression_rs(x: &Array2<f64>, y: &Array1<f64>, quantile: f64, fit_intercept: bool, max_iter: usize,
+                              tol: f64) -> Result<RobustFit, LinearModelError> {
+    if quantile <= 0.0 || quantile >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+    let design = design_rs(x, y, fit_intercept)?;
+    let mut params = weighted_least_squares_rs(&design, y, &Array1::ones(design.nrows()))?;
+    for iteration in 0..max_iter {
+        let residuals = y - &design.dot(&params);
+        let weights = residuals.mapv(|r| {
+            let share = if r > 0.0 { quantile } else { 1.0 - quantile };
+            share / r.abs().max(QUANTILE_EPSILON)
+        });
+        let updated = weighted_least_squares_rs(&design, y, &weights)?;
+        let converged = max_change(&updated, &params) < tol;
+        params = updated;
+        if converged {
+            return Ok(RobustFit { params, fit_intercept, weights, scale: f64::NAN, n_iter: iteration + 1 });
+        }
+    }
+    Err(LinearModelError::NotConverged)
+}
+
+fn check_iterations(max_iter: usize, tol: f64) -> Result<(), LinearModelError> {
+    if max_iter == 0 || !tol.is_finite() || tol <= 0.0 { return Err(LinearModelError::InvalidInputValue); }
+    Ok(())
+}
+
+
+/// Pyclasses
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RobustLinearModel {
+    pub norm: Norm,
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub max_iter: usize,
+    #[pyo3(get)]
+    pub tol: f64,
+    pub fit: Option<RobustFit>,
+}
+
+impl LinearModel for RobustLinearModel {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(m_estimate_rs(features, target, self.norm, self.fit_intercept, self.max_iter, self.tol)?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef())
+    }
+}
+
+impl RobustLinearModel {
+    pub fn new_rs(norm: Norm, fit_intercept: bool, max_iter: usize, tol: f64) -> Result<RobustLinearModel, LinearModelError> {
+        check_iterations(max_iter, tol)?;
+        Ok(RobustLinearModel { norm, fit_intercept, max_iter, tol, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&RobustFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+#[pymethods]
+impl RobustLinearModel {
+    #[new]
+    #[pyo3(signature = (norm = "huber", tuning = None, fit_intercept = true, max_iter = 50, tol = 1e-8))]
+    fn new(norm: &str, tuning: Option<f64>, fit_intercept: bool, max_iter: usize, tol: f64) -> PyResult<Self> {
+        // norm: huber (tuning 1.345 by default) or bisquare (Tukey, tuning 4.685)
+        Ok(RobustLinearModel::new_rs(Norm::parse(norm, tuning)?, fit_intercept, max_iter, tol)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn weights<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.weights.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn scale(&self) -> PyResult<f64> {
+        Ok(self.fitted()?.scale)
+    }
+
+    #[getter]
+    fn n_iter(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.n_iter)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RobustLinearModel(norm={}, fit_intercept={})", self.norm.name(),
+                if self.fit_intercept { "True" } else { "False" })
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RANSACRegressor {
+    #[pyo3(get)]
+    pub min_samples: Option<usize>,
+    #[pyo3(get)]
+    pub residual_threshold: Option<f64>,
+    #[pyo3(get)]
+    pub max_trials: usize,
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub seed: Option<u64>,
+    pub fit: Option<RobustFit>,
+}
+
+impl LinearModel for RANSACRegressor {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(ransac_rs(features, target, self.fit_intercept, self.min_samples, self.residual_threshold,
+                                  self.max_trials, self.seed.unwrap_or_else(rand::random))?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef())
+    }
+}
+
+impl RANSACRegressor {
+    pub fn new_rs(min_samples: Option<usize>, residual_threshold: Option<f64>, max_trials: usize, fit_intercept: bool,
+                  seed: Option<u64>) -> Result<RANSACRegressor, LinearModelError> {
+        if max_trials == 0 { return Err(LinearModelError::InvalidInputValue); }
+        Ok(RANSACRegressor { min_samples, residual_threshold, max_trials, fit_intercept, seed, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&RobustFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+#[pymethods]
+impl RANSACRegressor {
+    #[new]
+    #[pyo3(signature = (min_samples = None, residual_threshold = None, max_trials = 100, fit_intercept = true, seed = None))]
+    fn new(min_samples: Option<usize>, residual_threshold: Option<f64>, max_trials: usize, fit_intercept: bool,
+           seed: Option<u64>) -> PyResult<Self> {
+        // min_samples defaults to the number of parameters, residual_threshold to the MAD of y
+        Ok(RANSACRegressor::new_rs(min_samples, residual_threshold, max_trials, fit_intercept, seed)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn inlier_mask(&self) -> PyResult<Vec<bool>> {
+        Ok(self.fitted()?.weights.iter().map(|&w| w == 1.0).collect())
+    }
+
+    #[getter]
+    fn threshold(&self) -> PyResult<f64> {
+        // the residual threshold actually used
+        Ok(self.fitted()?.scale)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RANSACRegressor(max_trials={}, fit_intercept={})", self.max_trials,
+                if self.fit_intercept { "True" } else { "False" })
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct TheilSenRegressor {
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub max_subpopulation: usize,
+    #[pyo3(get)]
+    pub max_iter: usize,
+    #[pyo3(get)]
+    pub tol: f64,
+    #[pyo3(get)]
+    pub seed: Option<u64>,
+    pub fit: Option<RobustFit>,
+}
+
+impl LinearModel for TheilSenRegressor {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(theil_sen_rs(features, target, self.fit_intercept, self.max_subpopulation, self.max_iter,
+                                     self.tol, self.seed.unwrap_or_else(rand::random))?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef())
+    }
+}
+
+impl TheilSenRegressor {
+    pub fn new_rs(fit_intercept: bool, max_subpopulation: usize, max_iter: usize, tol: f64, seed: Option<u64>)
+                  -> Result<TheilSenRegressor, LinearModelError> {
+        check_iterations(max_iter, tol)?;
+        if max_subpopulation == 0 { return Err(LinearModelError::InvalidInputValue); }
+        Ok(TheilSenRegressor { fit_intercept, max_subpopulation, max_iter, tol, seed, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&RobustFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+#[pymethods]
+impl TheilSenRegressor {
+    #[new]
+    #[pyo3(signature = (fit_intercept = true, max_subpopulation = 10000, max_iter = 300, tol = 1e-6, seed = None))]
+    fn new(fit_intercept: bool, max_subpopulation: usize, max_iter: usize, tol: f64, seed: Option<u64>) -> PyResult<Self> {
+        // seed only matters when there are more than max_subpopulation subsets to choose from
+        Ok(TheilSenRegressor::new_rs(fit_intercept, max_subpopulation, max_iter, tol, seed)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "r2"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn n_iter(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.n_iter)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TheilSenRegressor(fit_intercept={}, max_subpopulation={})",
+                if self.fit_intercept { "True" } else { "False" }, self.max_subpopulation)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct QuantileRegression {
+    #[pyo3(get)]
+    pub quantile: f64,
+    #[pyo3(get, set)]
+    pub fit_intercept: bool,
+    #[pyo3(get)]
+    pub max_iter: usize,
+    #[pyo3(get)]
+    pub tol: f64,
+    pub fit: Option<RobustFit>,
+}
+
+impl LinearModel for QuantileRegression {
+    fn fit(&mut self, features: &Array2<f64>, target: &Array1<f64>) -> Result<(), LinearModelError> {
+        self.fit = Some(quantile_regression_rs(features, target, self.quantile, self.fit_intercept, self.max_iter, self.tol)?);
+        Ok(())
+    }
+
+    fn predict(&self, features: &Array2<f64>) -> Result<Array1<f64>, LinearModelError> {
+        self.fitted()?.predict(features)
+    }
+
+    fn coef(&self) -> Result<Array1<f64>, LinearModelError> {
+        Ok(self.fitted()?.coef())
+    }
+}
+
+impl QuantileRegression {
+    pub fn new_rs(quantile: f64, fit_intercept: bool, max_iter: usize, tol: f64) -> Result<QuantileRegression, LinearModelError> {
+        check_iterations(max_iter, tol)?;
+        if quantile <= 0.0 || quantile >= 1.0 { return Err(LinearModelError::InvalidInputValue); }
+        Ok(QuantileRegression { quantile, fit_intercept, max_iter, tol, fit: None })
+    }
+
+    fn fitted(&self) -> Result<&RobustFit, LinearModelError> {
+        self.fit.as_ref().ok_or(LinearModelError::NotFitted)
+    }
+}
+
+#[pymethods]
+impl QuantileRegression {
+    #[new]
+    #[pyo3(signature = (quantile = 0.5, fit_intercept = true, max_iter = 1000, tol = 1e-6))]
+    fn new(quantile: f64, fit_intercept: bool, max_iter: usize, tol: f64) -> PyResult<Self> {
+        // quantile in (0, 1), 0.5 is least absolute deviations
+        Ok(QuantileRegression::new_rs(quantile, fit_intercept, max_iter, tol)?)
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: &PyAny, y: &PyAny) -> PyResult<PyRefMut<'py, Self>> {
+        let (x_data, y_data) = (features_from_py(x)?, target_from_py(y)?);
+        LinearModel::fit(&mut *slf, &x_data, &y_data)?;
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: &PyAny) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::predict(self, &features_from_py(x)?)?.into_pyarray(py))
+    }
+
+    #[pyo3(signature = (x, y, metric = "mae"))]
+    fn score(&self, x: &PyAny, y: &PyAny, metric: &str) -> PyResult<f64> {
+        Ok(LinearModel::score(self, &features_from_py(x)?, &target_from_py(y)?, metric)?)
+    }
+
+    #[getter]
+    fn coef<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(LinearModel::coef(self)?.into_pyarray(py))
+    }
+
+    #[getter]
+    fn intercept(&self) -> PyResult<f64> {
+        let fit = self.fitted()?;
+        Ok(if fit.fit_intercept { fit.params[0] } else { 0.0 })
+    }
+
+    #[getter]
+    fn params<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        Ok(self.fitted()?.params.clone().into_pyarray(py))
+    }
+
+    #[getter]
+    fn n_iter(&self) -> PyResult<usize> {
+        Ok(self.fitted()?.n_iter)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("QuantileRegression(quantile={}, fit_intercept={})", self.quantile,
+                if self.fit_intercept { "True" } else { "False" })
+    }
+}
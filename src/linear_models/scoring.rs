@@ -0,0 +1,219 @@
+// Scorers for fitted models, selectable by name
+// regression -- mse, rmse, mae, mape, r2, explained_variance
+// classification -- log_loss and auc on predicted probabilities, accuracy on predicted labels
+//
+// scorer_rs looks a name up in SCORERS; score and scorers expose the registry to python.
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::Scorer;
+use crate::utils::{from_pyarray1, rank_rs};
+
+
+// Probabilities are clipped away from 0 and 1 so a confident miss costs a large but finite loss
+const LOG_LOSS_EPSILON: f64 = 1e-15;
+
+pub type ScorerFactory = fn() -> Box<dyn Scorer>;
+
+pub const SCORERS: [(&str, ScorerFactory); 9] = [
+    ("mse", || Box::new(MeanSquaredErrorScorer)),
+    ("rmse", || Box::new(RootMeanSquaredErrorScorer)),
+    ("mae", || Box::new(MeanAbsoluteErrorScorer)),
+    ("mape", || Box::new(MeanAbsolutePercentageErrorScorer)),
+    ("r2", || Box::new(RSquaredScorer)),
+    ("explained_variance", || Box::new(ExplainedVarianceScorer)),
+    ("log_loss", || Box::new(LogLossScorer)),
+    ("accuracy", || Box::new(AccuracyScorer)),
+    ("auc", || Box::new(RocAucScorer)),
+];
+
+pub fn scorer_rs(name: &str) -> Result<Box<dyn Scorer>, LinearModelError> {
+    let name = name.to_lowercase();
+    SCORERS.iter()
+        .find(|(registered, _)| *registered == name)
+        .map(|(_, make)| make())
+        .ok_or(LinearModelError::InvalidInputValue)
+}
+
+fn check_scorer_input(predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<(), LinearModelError> {
+    if predictions.is_empty() || actuals.is_empty() { return Err(LinearModelError::EmptyDataSet); }
+    if predictions.len() != actuals.len() { return Err(LinearModelError::ShapeMismatch); }
+    if predictions.iter().chain(actuals.iter()).any(|v| !v.is_finite()) { return Err(LinearModelError::InvalidInputValue); }
+    Ok(())
+}
+
+fn check_binary_labels(actuals: &Array1<f64>) -> Result<(), LinearModelError> {
+    if actuals.iter().any(|&v| v != 0.0 && v != 1.0) { return Err(LinearModelError::InvalidInputValue); }
+    Ok(())
+}
+
+fn mean_of<F: Fn(f64, f64) -> f64>(predictions: &Array1<f64>, actuals: &Array1<f64>, f: F) -> f64 {
+    predictions.iter().zip(actuals.iter()).map(|(&p, &a)| f(p, a)).sum::<f64>() / predictions.len() as f64
+}
+
+fn centered_sum_of_squares(values: &Array1<f64>) -> f64 {
+    let mean = values.mean().unwrap();
+    values.iter().map(|v| (v - mean).powi(2)).sum()
+}
+
+pub struct MeanSquaredErrorScorer;
+
+impl Scorer for MeanSquaredErrorScorer {
+    fn name(&self) -> &'static str { "mse" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        Ok(mean_of(predictions, actuals, |p, a| (p - a).powi(2)))
+    }
+
+    fn greater_is_better(&self) -> bool { false }
+}
+
+pub struct RootMeanSquaredErrorScorer;
+
+impl Scorer for RootMeanSquaredErrorScorer {
+    fn name(&self) -> &'static str { "rmse" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        Ok(MeanSquaredErrorScorer.score(predictions, actuals)?.sqrt())
+    }
+
+    fn greater_is_better(&self) -> bool { false }
+}
+
+pub struct MeanAbsoluteErrorScorer;
+
+impl Scorer for MeanAbsoluteErrorScorer {
+    fn name(&self) -> &'static str { "mae" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        Ok(mean_of(predictions, actuals, |p, a| (p - a).abs()))
+    }
+
+    fn greater_is_better(&self) -> bool { false }
+}
+
+// As a fraction, not a percentage. Undefined when any actual value is zero
+pub struct MeanAbsolutePercentageErrorScorer;
+
+impl Scorer for MeanAbsolutePercentageErrorScorer {
+    fn name(&self) -> &'static str { "mape" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        if actuals.iter().any(|&a| a == 0.0) { return Err(LinearModelError::InvalidInputValue); }
+        Ok(mean_of(predictions, actuals, |p, a| ((p - a) / a).abs()))
+    }
+
+    fn greater_is_better(&self) -> bool { false }
+}
+
+// 1 - ssr / tss around the mean of the actuals, negative for predictions worse than that mean
+pub struct RSquaredScorer;
+
+impl Scorer for RSquaredScorer {
+    fn name(&self) -> &'static str { "r2" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        let tss = centered_sum_of_squares(actuals);
+        if tss == 0.0 { return Err(LinearModelError::ZeroVariance); }
+        let ssr: f64 = predictions.iter().zip(actuals.iter()).map(|(p, a)| (a - p).powi(2)).sum();
+        Ok(1.0 - ssr / tss)
+    }
+}
+
+// 1 - var(actual - predicted) / var(actual), which ignores a constant bias in the predictions
+pub struct ExplainedVarianceScorer;
+
+impl Scorer for ExplainedVarianceScorer {
+    fn name(&self) -> &'static str { "explained_variance" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        let tss = centered_sum_of_squares(actuals);
+        if tss == 0.0 { return Err(LinearModelError::ZeroVariance); }
+        Ok(1.0 - centered_sum_of_squares(&(actuals - predictions)) / tss)
+    }
+}
+
+// Mean binary cross-entropy of 0/1 actuals against predicted probabilities
+pub struct LogLossScorer;
+
+impl Scorer for LogLossScorer {
+    fn name(&self) -> &'static str { "log_loss" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        check_binary_labels(actuals)?;
+        if predictions.iter().any(|&p| !(0.0..=1.0).contains(&p)) { return Err(LinearModelError::InvalidInputValue); }
+        Ok(mean_of(predictions, actuals, |p, a| {
+            let p = p.clamp(LOG_LOSS_EPSILON, 1.0 - LOG_LOSS_EPSILON);
+            -(a * p.ln() + (1.0 - a) * (1.0 - p).ln())
+        }))
+    }
+
+    fn greater_is_better(&self) -> bool { false }
+
+    fn needs_probabilities(&self) -> bool { true }
+}
+
+// Share of predicted labels equal to the actual labels
+pub struct AccuracyScorer;
+
+impl Scorer for AccuracyScorer {
+    fn name(&self) -> &'static str { "accuracy" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        Ok(mean_of(predictions, actuals, |p, a| if p == a { 1.0 } else { 0.0 }))
+    }
+}
+
+// Area under the ROC curve from the Mann-Whitney form: the chance a random positive outscores
+// a random negative, ties counting one half
+pub struct RocAucScorer;
+
+impl Scorer for RocAucScorer {
+    fn name(&self) -> &'static str { "auc" }
+
+    fn score(&self, predictions: &Array1<f64>, actuals: &Array1<f64>) -> Result<f64, LinearModelError> {
+        check_scorer_input(predictions, actuals)?;
+        check_binary_labels(actuals)?;
+        let positives = actuals.iter().filter(|&&a| a == 1.0).count() as f64;
+        let negatives = actuals.len() as f64 - positives;
+        if positives == 0.0 || negatives == 0.0 { return Err(LinearModelError::InsufficientGroups); }
+        let (ranks, _ties) = rank_rs(&predictions.to_vec());
+        let rank_sum: f64 = ranks.iter().zip(actuals.iter()).filter(|(_, &a)| a == 1.0).map(|(r, _)| r).sum();
+        Ok((rank_sum - positives * (positives + 1.0) / 2.0) / (positives * negatives))
+    }
+
+    fn needs_probabilities(&self) -> bool { true }
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (y_true, y_pred, metric = "r2"))]
+pub fn score(y_true: &PyAny, y_pred: &PyAny, metric: &str) -> PyResult<f64> {
+    // y_pred holds probabilities for log_loss and auc, predicted values or labels otherwise
+    let actuals = match from_pyarray1(y_true) {
+        Ok(data) => data,
+        Err(_e) => return Err(LinearModelError::Conversion.into()),
+    };
+    let predictions = match from_pyarray1(y_pred) {
+        Ok(data) => data,
+        Err(_e) => return Err(LinearModelError::Conversion.into()),
+    };
+    Ok(scorer_rs(metric)?.score(&predictions, &actuals)?)
+}
+
+#[pyfunction]
+pub fn scorers() -> Vec<(&'static str, bool)> {
+    // (name, greater_is_better) for every registered scorer
+    SCORERS.iter().map(|(name, make)| (*name, make().greater_is_better())).collect()
+}
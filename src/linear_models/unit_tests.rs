@@ -0,0 +1,938 @@
+// Unit Tests
+use crate::linear_models::*;
+use crate::linear_models::glm::*;
+use crate::linear_models::linalg::*;
+use crate::linear_models::logistic::*;
+use crate::linear_models::ols::*;
+use crate::linear_models::regularized::*;
+use crate::linear_models::robust::*;
+use crate::linear_models::scoring::*;
+use crate::linear_models::covariance::*;
+use crate::linear_models::diagnostics::*;
+use crate::linear_models::errors::LinearModelError;
+use crate::linear_models::formula::*;
+use ndarray::{array, Array1, Array2};
+use polars::prelude::{DataFrame, NamedFrom, Series};
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tol: f64) {
+        assert!((actual - expected).abs() <= tol, "expected {}, got {}", expected, actual);
+    }
+
+    fn simple_data() -> (Array2<f64>, Array1<f64>) {
+        (array![[1.0], [2.0], [3.0], [4.0], [5.0]], array![2.2, 4.1, 6.2, 7.9, 10.1])
+    }
+
+    fn correlated_data() -> (Array2<f64>, Array1<f64>) {
+        let x = array![[1.0, 2.0, 0.5], [2.0, 1.0, 1.5], [3.0, 4.0, -1.0], [4.0, 3.0, 0.0], [5.0, 6.0, 2.0],
+                       [6.0, 5.0, 1.0], [7.0, 8.0, -0.5], [8.0, 7.5, 0.5]];
+        let y = array![3.1, 3.9, 7.2, 7.8, 11.5, 11.0, 15.2, 15.4];
+        (x, y)
+    }
+
+    // x = 0: 3 of 8 positive, x = 1: 6 of 8 positive
+    fn binary_data() -> (Array2<f64>, Array1<f64>) {
+        let x: Array1<f64> = (0..16).map(|i| if i < 8 { 0.0 } else { 1.0 }).collect();
+        let y = array![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0];
+        (x.insert_axis(ndarray::Axis(1)), y)
+    }
+
+    fn fit_logistic(x: &Array2<f64>, y: &Array1<f64>, penalty: Penalty, alpha: f64, solver: LogisticSolver) -> LogisticFit {
+        logistic_rs(x, y, true, penalty, alpha, solver, &ClassWeight::Uniform, 100, 1e-10).unwrap()
+    }
+
+    mod ols_tests {
+        use super::*;
+
+        #[test]
+        fn test_qr_solves_square_system() {
+            let a = array![[2.0, 1.0], [1.0, 3.0]];
+            let qr = QrDecomposition::new(&a);
+            let x = qr.solve(&array![3.0, 5.0]).unwrap();
+            assert_close(x[0], 0.8, 1e-12);
+            assert_close(x[1], 1.4, 1e-12);
+            let q = qr.q_thin(2);
+            let product = q.dot(&qr.r);
+            for (actual, expected) in product.iter().zip(a.iter()) {
+                assert_close(*actual, *expected, 1e-12);
+            }
+        }
+
+        #[test]
+        fn test_cholesky_factor() {
+            let a = array![[4.0, 2.0], [2.0, 3.0]];
+            let l = cholesky_rs(&a).unwrap();
+            assert_close(l[[0, 0]], 2.0, 1e-12);
+            assert_close(l[[1, 0]], 1.0, 1e-12);
+            assert_close(l[[1, 1]], 2.0_f64.sqrt(), 1e-12);
+            assert!(cholesky_rs(&array![[1.0, 2.0], [2.0, 1.0]]).is_none());
+        }
+
+        #[test]
+        fn test_ols_simple_regression() {
+            // slope = sxy / sxx = 19.6 / 10, intercept = 6.1 - 1.96 * 3
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            assert_close(fit.params[0], 0.22, 1e-12);
+            assert_close(fit.params[1], 1.96, 1e-12);
+            assert_close(fit.ssr, 0.044, 1e-12);
+            assert_close(fit.tss, 38.46, 1e-12);
+            assert_close(fit.r_squared(), 1.0 - 0.044 / 38.46, 1e-12);
+            // se(slope) = sqrt(s^2 / sxx)
+            let se = fit.standard_errors();
+            assert_close(se[1], (0.044 / 3.0 / 10.0_f64).sqrt(), 1e-12);
+            assert_close(se[0], (0.044_f64 / 3.0 * (1.0 / 5.0 + 9.0 / 10.0)).sqrt(), 1e-12);
+            let (f, _p) = fit.f_test();
+            assert_close(f, fit.t_values()[1].powi(2), 1e-8);
+            assert_eq!(fit.parameter_names(), vec!["const".to_string(), "x1".to_string()]);
+        }
+
+        #[test]
+        fn test_ols_solvers_agree() {
+            let x = array![[1.0, 0.5], [2.0, -1.0], [3.0, 2.5], [4.0, 0.0], [5.0, 1.5], [6.0, -0.5]];
+            let y = array![1.0, 2.5, 2.0, 4.5, 4.0, 6.5];
+            let qr = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let cholesky = ols_rs(&x, &y, true, Solver::Cholesky).unwrap();
+            for (a, b) in qr.params.iter().zip(cholesky.params.iter()) {
+                assert_close(*a, *b, 1e-10);
+            }
+            for (a, b) in qr.cov_params().iter().zip(cholesky.cov_params().iter()) {
+                assert_close(*a, *b, 1e-10);
+            }
+        }
+
+        #[test]
+        fn test_ols_information_criteria() {
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            assert_close(fit.adj_r_squared(), 1.0 - (1.0 - fit.r_squared()) * 4.0 / 3.0, 1e-12);
+            assert_close(fit.aic(), -2.0 * fit.log_likelihood() + 4.0, 1e-12);
+            assert_close(fit.bic(), -2.0 * fit.log_likelihood() + 2.0 * 5.0_f64.ln(), 1e-12);
+            let intervals = fit.confidence_intervals(0.05).unwrap();
+            assert!(intervals[[1, 0]] < 1.96 && 1.96 < intervals[[1, 1]]);
+            assert_close(intervals[[1, 0]] + intervals[[1, 1]], 2.0 * 1.96, 1e-12);
+        }
+
+        #[test]
+        fn test_ols_without_intercept() {
+            // through the origin: b = sum(xy) / sum(x^2), r squared is uncentered
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, false, Solver::Qr).unwrap();
+            assert_close(fit.params[0], 111.1 / 55.0, 1e-12);
+            assert_close(fit.tss, y.dot(&y), 1e-12);
+            assert_eq!(fit.df_resid, 4.0);
+            let prediction = fit.predict(&array![[10.0]]).unwrap();
+            assert_close(prediction[0], 1111.0 / 55.0, 1e-10);
+        }
+
+        #[test]
+        fn test_ols_errors() {
+            let x = array![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0], [4.0, 8.0]];
+            let y = array![1.0, 2.0, 3.0, 5.0];
+            assert_eq!(ols_rs(&x, &y, true, Solver::Qr).unwrap_err(), LinearModelError::RankDeficient);
+            assert_eq!(ols_rs(&x, &y, true, Solver::Cholesky).unwrap_err(), LinearModelError::SingularMatrix);
+            assert_eq!(ols_rs(&x.slice(ndarray::s![..2, ..]).to_owned(), &array![1.0, 2.0], false, Solver::Qr).unwrap_err(),
+                       LinearModelError::InsufficientObservations);
+            let mut with_nan = y.clone();
+            with_nan[1] = f64::NAN;
+            assert_eq!(ols_rs(&x, &with_nan, true, Solver::Qr).unwrap_err(), LinearModelError::InvalidInputValue);
+            assert_eq!(ols_rs(&x, &array![1.0, 2.0], true, Solver::Qr).unwrap_err(), LinearModelError::ShapeMismatch);
+            assert_eq!(Solver::parse("svd").unwrap_err(), LinearModelError::InvalidInputValue);
+        }
+    }
+
+    mod scoring_tests {
+        use super::*;
+
+        #[test]
+        fn test_regression_scorers() {
+            let actuals = array![3.0, -0.5, 2.0, 7.0];
+            let predictions = array![2.5, 0.0, 2.0, 8.0];
+            assert_close(scorer_rs("mse").unwrap().score(&predictions, &actuals).unwrap(), 0.375, 1e-12);
+            assert_close(scorer_rs("rmse").unwrap().score(&predictions, &actuals).unwrap(), 0.375_f64.sqrt(), 1e-12);
+            assert_close(scorer_rs("mae").unwrap().score(&predictions, &actuals).unwrap(), 0.5, 1e-12);
+            // |0.5/3| + |0.5/0.5| + 0 + |1/7|, averaged
+            assert_close(scorer_rs("mape").unwrap().score(&predictions, &actuals).unwrap(),
+                         (0.5 / 3.0 + 1.0 + 1.0 / 7.0) / 4.0, 1e-12);
+            // tss = 29.1875 around the mean 2.875, ssr = 1.5
+            assert_close(scorer_rs("R2").unwrap().score(&predictions, &actuals).unwrap(), 1.0 - 1.5 / 29.1875, 1e-12);
+            // residuals 0.5, -0.5, 0, -1 have mean -0.25 and centered sum of squares 1.25
+            assert_close(scorer_rs("explained_variance").unwrap().score(&predictions, &actuals).unwrap(),
+                         1.0 - 1.25 / 29.1875, 1e-12);
+            assert!(!scorer_rs("mse").unwrap().greater_is_better());
+            assert_eq!(scorer_rs("mape").unwrap().score(&predictions, &array![0.0, 1.0, 2.0, 3.0]).unwrap_err(),
+                       LinearModelError::InvalidInputValue);
+            assert_eq!(scorer_rs("bogus").err().unwrap(), LinearModelError::InvalidInputValue);
+        }
+
+        #[test]
+        fn test_classification_scorers() {
+            let labels = array![0.0, 0.0, 1.0, 1.0];
+            let probabilities = array![0.1, 0.4, 0.35, 0.8];
+            // one of the four positive-negative pairs is ordered the wrong way
+            assert_close(scorer_rs("auc").unwrap().score(&probabilities, &labels).unwrap(), 0.75, 1e-12);
+            let expected = -(0.9_f64.ln() + 0.6_f64.ln() + 0.35_f64.ln() + 0.8_f64.ln()) / 4.0;
+            assert_close(scorer_rs("log_loss").unwrap().score(&probabilities, &labels).unwrap(), expected, 1e-12);
+            let predicted = array![0.0, 1.0, 1.0, 1.0];
+            assert_close(scorer_rs("accuracy").unwrap().score(&predicted, &labels).unwrap(), 0.75, 1e-12);
+            assert_eq!(scorer_rs("auc").unwrap().score(&probabilities, &array![1.0, 1.0, 1.0, 1.0]).unwrap_err(),
+                       LinearModelError::InsufficientGroups);
+            assert!(scorer_rs("log_loss").unwrap().needs_probabilities());
+        }
+
+        #[test]
+        fn test_linear_model_trait_scoring() {
+            let (x, y) = simple_data();
+            let mut model = LinearRegression::new_rs(true, Solver::Qr);
+            assert_eq!(model.predict(&x).unwrap_err(), LinearModelError::NotFitted);
+            LinearModel::fit(&mut model, &x, &y).unwrap();
+            assert_close(model.coef().unwrap()[0], 1.96, 1e-12);
+            assert_eq!(model.predict(&array![[1.0, 2.0]]).unwrap_err(), LinearModelError::ShapeMismatch);
+            assert_close(model.score(&x, &y, "r2").unwrap(), 1.0 - 0.044 / 38.46, 1e-12);
+            assert_close(model.score(&x, &y, "mse").unwrap(), 0.044 / 5.0, 1e-12);
+            // a regression model has no probabilities to feed log_loss
+            assert_eq!(model.score(&x, &y, "log_loss").unwrap_err(), LinearModelError::InvalidInputValue);
+        }
+    }
+
+    mod regularized_tests {
+        use super::*;
+
+        #[test]
+        fn test_svd_reconstructs() {
+            let (x, _y) = correlated_data();
+            let (u, s, v) = svd_rs(&x);
+            let rebuilt = (&u * &s).dot(&v.t());
+            for (actual, expected) in rebuilt.iter().zip(x.iter()) {
+                assert_close(*actual, *expected, 1e-10);
+            }
+            assert!(s[0] >= s[1] && s[1] >= s[2]);
+            let identity = v.t().dot(&v);
+            for i in 0..3 {
+                for j in 0..3 {
+                    assert_close(identity[[i, j]], if i == j { 1.0 } else { 0.0 }, 1e-12);
+                }
+            }
+        }
+
+        #[test]
+        fn test_ridge_solutions() {
+            // one feature through the origin: b = sum(xy) / (sum(x^2) + alpha)
+            let (x, y) = simple_data();
+            let fit = ridge_rs(&x, &y, 5.0, false, false, RidgeSolver::Cholesky).unwrap();
+            assert_close(fit.coef[0], 111.1 / 60.0, 1e-12);
+            let (x, y) = correlated_data();
+            let cholesky = ridge_rs(&x, &y, 2.0, true, true, RidgeSolver::Cholesky).unwrap();
+            let svd = ridge_rs(&x, &y, 2.0, true, true, RidgeSolver::Svd).unwrap();
+            assert_close(cholesky.intercept, svd.intercept, 1e-10);
+            for (a, b) in cholesky.coef.iter().zip(svd.coef.iter()) {
+                assert_close(*a, *b, 1e-10);
+            }
+            // without a penalty ridge is ols, whatever the scaling
+            let ols = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let unpenalized = ridge_rs(&x, &y, 0.0, true, true, RidgeSolver::Svd).unwrap();
+            assert_close(unpenalized.intercept, ols.params[0], 1e-9);
+            for j in 0..3 {
+                assert_close(unpenalized.coef[j], ols.params[j + 1], 1e-9);
+            }
+            let path = ridge_path_rs(&x, &y, vec![0.5, 2.0], true, true).unwrap();
+            assert_eq!(path.alphas, vec![2.0, 0.5]);
+            assert_close(path.coefs[[0, 1]], svd.coef[1], 1e-10);
+        }
+
+        #[test]
+        fn test_lasso_single_feature_soft_threshold() {
+            // centered x = -2..2, x'y / n = 3.92 and x'x / n = 2, so b = (3.92 - alpha) / 2
+            let (x, y) = simple_data();
+            let fit = elastic_net_rs(&x, &y, 1.0, 1.0, true, false, 1000, 1e-10, None).unwrap();
+            assert!(fit.converged);
+            assert_close(fit.coef[0], 1.46, 1e-9);
+            assert_close(fit.intercept, 6.1 - 1.46 * 3.0, 1e-9);
+            let zeroed = elastic_net_rs(&x, &y, 4.0, 1.0, true, false, 1000, 1e-10, None).unwrap();
+            assert_eq!(zeroed.coef[0], 0.0);
+            assert_close(zeroed.intercept, 6.1, 1e-12);
+        }
+
+        #[test]
+        fn test_elastic_net_without_l1_is_ridge() {
+            // alpha / 2 ||b||^2 on the 1 / 2n scaled loss is ridge with alpha * n
+            let (x, y) = correlated_data();
+            let enet = elastic_net_rs(&x, &y, 0.1, 0.0, true, true, 10000, 1e-12, None).unwrap();
+            let ridge = ridge_rs(&x, &y, 0.8, true, true, RidgeSolver::Cholesky).unwrap();
+            for (a, b) in enet.coef.iter().zip(ridge.coef.iter()) {
+                assert_close(*a, *b, 1e-6);
+            }
+            assert!(enet.dual_gap >= -1e-12);
+        }
+
+        #[test]
+        fn test_enet_path_and_warm_start() {
+            let (x, y) = correlated_data();
+            let path = enet_path_rs(&x, &y, 1.0, None, 10, 1e-3, true, true, 10000, 1e-10).unwrap();
+            assert_eq!(path.alphas.len(), 10);
+            assert!(path.coefs.row(0).iter().all(|&c| c.abs() < 1e-12));
+            assert!(path.converged.iter().all(|&c| c));
+            let single = elastic_net_rs(&x, &y, path.alphas[5], 1.0, true, true, 10000, 1e-10, None).unwrap();
+            for j in 0..3 {
+                assert_close(path.coefs[[5, j]], single.coef[j], 1e-6);
+            }
+            let mut model = Lasso::new_rs(path.alphas[5], true, true, 10000, 1e-10, true).unwrap();
+            LinearModel::fit(&mut model, &x, &y).unwrap();
+            let cold = model.fit.as_ref().unwrap().n_iter;
+            LinearModel::fit(&mut model, &x, &y).unwrap();
+            assert!(model.fit.as_ref().unwrap().n_iter <= cold);
+            assert!(model.score(&x, &y, "r2").unwrap() > 0.9);
+            assert_eq!(ElasticNet::new_rs(1.0, 1.5, true, false, 100, 1e-4, false).unwrap_err(),
+                       LinearModelError::InvalidInputValue);
+        }
+    }
+
+    mod logistic_tests {
+        use super::*;
+
+        #[test]
+        fn test_logistic_binary_saturated() {
+            // a single binary feature is saturated: the intercept is the log odds at x = 0 and the slope
+            // the log odds ratio, with se sqrt(sum of 1 / cell counts)
+            let (x, y) = binary_data();
+            let fit = fit_logistic(&x, &y, Penalty::None, 0.0, LogisticSolver::Newton);
+            assert_close(fit.params[[0, 0]], (3.0_f64 / 5.0).ln(), 1e-8);
+            assert_close(fit.params[[1, 0]], 5.0_f64.ln(), 1e-8);
+            let se = fit.standard_errors().unwrap();
+            assert_close(se[[0, 0]], (1.0_f64 / 3.0 + 1.0 / 5.0).sqrt(), 1e-8);
+            assert_close(se[[1, 0]], 1.2_f64.sqrt(), 1e-8);
+            assert_close(fit.odds_ratios()[[1, 0]], 5.0, 1e-7);
+            let (lower, upper) = fit.odds_ratio_intervals(0.05).unwrap();
+            assert_close((lower[[1, 0]] * upper[[1, 0]]).sqrt(), 5.0, 1e-7);
+            let ll = 3.0 * (3.0_f64 / 8.0).ln() + 5.0 * (5.0_f64 / 8.0).ln() + 6.0 * 0.75_f64.ln() + 2.0 * 0.25_f64.ln();
+            let ll_null = 9.0 * (9.0_f64 / 16.0).ln() + 7.0 * (7.0_f64 / 16.0).ln();
+            assert_close(fit.log_likelihood, ll, 1e-10);
+            assert_close(fit.null_deviance(), -2.0 * ll_null, 1e-10);
+            assert_close(fit.pseudo_r_squared(), 1.0 - ll / ll_null, 1e-10);
+            assert_close(fit.aic(), -2.0 * ll + 4.0, 1e-10);
+
+            let lbfgs = fit_logistic(&x, &y, Penalty::None, 0.0, LogisticSolver::Lbfgs);
+            assert_close(lbfgs.params[[1, 0]], fit.params[[1, 0]], 1e-6);
+        }
+
+        #[test]
+        fn test_logistic_multinomial_saturated() {
+            // class counts (4, 2, 2) at x = 0 and (1, 3, 4) at x = 1, class 0 the reference
+            let x: Array1<f64> = (0..16).map(|i| if i < 8 { 0.0 } else { 1.0 }).collect();
+            let y = array![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0];
+            let x = x.insert_axis(ndarray::Axis(1));
+            let fit = fit_logistic(&x, &y, Penalty::None, 0.0, LogisticSolver::Newton);
+            assert_eq!(fit.classes, vec![0.0, 1.0, 2.0]);
+            assert_close(fit.params[[0, 0]], 0.5_f64.ln(), 1e-8);
+            assert_close(fit.params[[1, 0]], 6.0_f64.ln(), 1e-8);
+            assert_close(fit.params[[1, 1]], 8.0_f64.ln(), 1e-8);
+            let se = fit.standard_errors().unwrap();
+            assert_close(se[[1, 0]], (1.0_f64 / 4.0 + 1.0 / 2.0 + 1.0 + 1.0 / 3.0).sqrt(), 1e-7);
+            let probabilities = fit.predict_proba(&array![[0.0], [1.0]]).unwrap();
+            assert_close(probabilities[[0, 0]], 0.5, 1e-8);
+            assert_close(probabilities[[1, 2]], 0.5, 1e-8);
+            assert_close(probabilities.row(1).sum(), 1.0, 1e-12);
+            assert_eq!(fit.predict(&array![[0.0], [1.0]]).unwrap(), array![0.0, 2.0]);
+            let lbfgs = fit_logistic(&x, &y, Penalty::None, 0.0, LogisticSolver::Lbfgs);
+            assert_close(lbfgs.params[[1, 1]], fit.params[[1, 1]], 1e-6);
+        }
+
+        #[test]
+        fn test_logistic_penalties() {
+            let (x, y) = binary_data();
+            let ridge = fit_logistic(&x, &y, Penalty::L2, 2.0, LogisticSolver::Newton);
+            assert!(ridge.params[[1, 0]] > 0.0 && ridge.params[[1, 0]] < 5.0_f64.ln());
+            assert_eq!(ridge.standard_errors().unwrap_err(), LinearModelError::PenalizedInference);
+            let ridge_lbfgs = fit_logistic(&x, &y, Penalty::L2, 2.0, LogisticSolver::Lbfgs);
+            assert_close(ridge_lbfgs.params[[1, 0]], ridge.params[[1, 0]], 1e-6);
+
+            // at a zero slope the gradient is sum over x = 1 of (y - 9/16) = 1.5, so alpha = 2 zeroes it
+            let zeroed = fit_logistic(&x, &y, Penalty::L1, 2.0, LogisticSolver::Newton);
+            assert_eq!(zeroed.params[[1, 0]], 0.0);
+            assert_close(zeroed.params[[0, 0]], (9.0_f64 / 7.0).ln(), 1e-8);
+            // below that, the slope is where the score equals the penalty
+            let lasso = fit_logistic(&x, &y, Penalty::L1, 0.5, LogisticSolver::Newton);
+            let p = lasso.predict_proba(&x).unwrap();
+            let score: f64 = (8..16).map(|i| y[i] - p[[i, 1]]).sum();
+            assert!(lasso.params[[1, 0]] > 0.0);
+            assert_close(score, 0.5, 1e-6);
+            assert!(logistic_rs(&x, &y, true, Penalty::L1, 0.5, LogisticSolver::Lbfgs, &ClassWeight::Uniform, 100, 1e-8).is_err());
+        }
+
+        #[test]
+        fn test_logistic_class_weights() {
+            // weighting the positives by two is the same fit as listing them twice
+            let (x, y) = binary_data();
+            let weighted = logistic_rs(&x, &y, true, Penalty::None, 0.0, LogisticSolver::Newton,
+                                       &ClassWeight::Custom(vec![(1.0, 2.0)]), 100, 1e-10).unwrap();
+            let positives: Vec<usize> = (0..16).filter(|&i| y[i] == 1.0).collect();
+            let mut rows: Vec<usize> = (0..16).collect();
+            rows.extend(positives);
+            let x_repeated = x.select(ndarray::Axis(0), &rows);
+            let y_repeated = y.select(ndarray::Axis(0), &rows);
+            let repeated = fit_logistic(&x_repeated, &y_repeated, Penalty::None, 0.0, LogisticSolver::Newton);
+            assert_close(weighted.params[[0, 0]], repeated.params[[0, 0]], 1e-8);
+            assert_close(weighted.params[[1, 0]], repeated.params[[1, 0]], 1e-8);
+            // balanced weights give both classes the same total, so the intercept-only shares are one half
+            let balanced = logistic_rs(&x, &y, true, Penalty::None, 0.0, LogisticSolver::Newton,
+                                       &ClassWeight::Balanced, 100, 1e-10).unwrap();
+            assert_close(balanced.null_log_likelihood, 16.0 * 0.5_f64.ln(), 1e-10);
+        }
+
+        #[test]
+        fn test_logistic_model_scoring_and_errors() {
+            let (x, y) = binary_data();
+            let mut model = LogisticRegression::new_rs(Penalty::None, 1.0, LogisticSolver::Newton, true,
+                                                       ClassWeight::Uniform, 100, 1e-10).unwrap();
+            LinearModel::fit(&mut model, &x, &y).unwrap();
+            // predicts 0 at x = 0 and 1 at x = 1, so 5 + 6 of 16 right
+            assert_close(model.score(&x, &y, "accuracy").unwrap(), 11.0 / 16.0, 1e-12);
+            // every positive at x = 1 outranks every negative at x = 0, the rest tie
+            let auc = (6.0 * 5.0 + 0.5 * (6.0 * 2.0 + 3.0 * 5.0)) / (9.0 * 7.0);
+            assert_close(model.score(&x, &y, "auc").unwrap(), auc, 1e-12);
+
+            let separated = array![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+            assert_eq!(logistic_rs(&x, &separated, true, Penalty::None, 0.0, LogisticSolver::Newton,
+                                   &ClassWeight::Uniform, 100, 1e-10).unwrap_err(), LinearModelError::NotConverged);
+            assert_eq!(logistic_rs(&x, &Array1::ones(16), true, Penalty::None, 0.0, LogisticSolver::Newton,
+                                   &ClassWeight::Uniform, 100, 1e-10).unwrap_err(), LinearModelError::InsufficientGroups);
+        }
+    }
+
+    mod glm_tests {
+        use super::*;
+
+        fn count_data() -> (Array2<f64>, Array1<f64>) {
+            // group means 2 at x = 0 and 6 at x = 1
+            let x = array![[0.0], [0.0], [0.0], [0.0], [1.0], [1.0], [1.0], [1.0]];
+            (x, array![1.0, 3.0, 2.0, 2.0, 5.0, 7.0, 4.0, 8.0])
+        }
+
+        fn fit_glm(x: &Array2<f64>, y: &Array1<f64>, family: Family, link: Link) -> GlmFit {
+            glm_rs(x, y, family, link, true, None, None, None, 100, 1e-12).unwrap()
+        }
+
+        #[test]
+        fn test_glm_gaussian_matches_ols() {
+            let (x, y) = simple_data();
+            let fit = fit_glm(&x, &y, Family::Gaussian, Link::Identity);
+            let ols = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            assert_close(fit.params[0], 0.22, 1e-10);
+            assert_close(fit.params[1], 1.96, 1e-10);
+            assert_close(fit.deviance, 0.044, 1e-10);
+            assert_close(fit.null_deviance, 38.46, 1e-10);
+            assert_close(fit.scale, 0.044 / 3.0, 1e-10);
+            assert_close(fit.log_likelihood(), ols.log_likelihood(), 1e-10);
+            let se = fit.standard_errors();
+            let ols_se = ols.standard_errors();
+            assert_close(se[1], ols_se[1], 1e-10);
+        }
+
+        #[test]
+        fn test_glm_poisson_saturated() {
+            // with one binary feature the fitted means are the group means for any log-link family
+            let (x, y) = count_data();
+            let fit = fit_glm(&x, &y, Family::Poisson, Link::Log);
+            assert_close(fit.params[0], 2.0_f64.ln(), 1e-8);
+            assert_close(fit.params[1], 3.0_f64.ln(), 1e-8);
+            // var = 1 / sum(y) per group
+            let se = fit.standard_errors();
+            assert_close(se[0], (1.0_f64 / 8.0).sqrt(), 1e-8);
+            assert_close(se[1], (1.0_f64 / 8.0 + 1.0 / 24.0).sqrt(), 1e-8);
+            assert_eq!(fit.scale, 1.0);
+
+            let means = [2.0, 2.0, 2.0, 2.0, 6.0, 6.0, 6.0, 6.0];
+            let unit = |y: f64, mu: f64| 2.0 * (y * (y / mu).ln() - (y - mu));
+            let deviance: f64 = y.iter().zip(means.iter()).map(|(&y, &mu)| unit(y, mu)).sum();
+            let null_deviance: f64 = y.iter().map(|&y| unit(y, 4.0)).sum();
+            let pearson: f64 = y.iter().zip(means.iter()).map(|(&y, &mu)| (y - mu).powi(2) / mu).sum();
+            assert_close(fit.deviance, deviance, 1e-8);
+            assert_close(fit.null_deviance, null_deviance, 1e-8);
+            assert_close(fit.pearson_chi2, pearson, 1e-8);
+            let residuals = fit.residuals("deviance").unwrap();
+            assert_close(residuals.mapv(|r| r * r).sum(), deviance, 1e-8);
+
+            for family in [Family::NegativeBinomial(0.5), Family::Tweedie(1.5)] {
+                let fit = fit_glm(&x, &y, family, Link::Log);
+                assert_close(fit.mu[0], 2.0, 1e-6);
+                assert_close(fit.mu[7], 6.0, 1e-6);
+            }
+        }
+
+        #[test]
+        fn test_glm_gamma_inverse_link() {
+            let (x, y) = count_data();
+            let fit = fit_glm(&x, &y, Family::Gamma, Link::Inverse);
+            assert_close(fit.params[0], 0.5, 1e-8);
+            assert_close(fit.params[1], 1.0 / 6.0 - 0.5, 1e-8);
+            let means = [2.0, 2.0, 2.0, 2.0, 6.0, 6.0, 6.0, 6.0];
+            let pearson: f64 = y.iter().zip(means.iter()).map(|(&y, &mu)| ((y - mu) / mu).powi(2)).sum();
+            assert_close(fit.scale, pearson / 6.0, 1e-8);
+        }
+
+        #[test]
+        fn test_glm_binomial_links() {
+            let (x, y) = binary_data();
+            let fit = fit_glm(&x, &y, Family::Binomial, Link::Logit);
+            let logistic = fit_logistic(&x, &y, Penalty::None, 0.0, LogisticSolver::Newton);
+            assert_close(fit.params[0], logistic.params[[0, 0]], 1e-8);
+            assert_close(fit.params[1], logistic.params[[1, 0]], 1e-8);
+            assert_close(fit.log_likelihood(), logistic.log_likelihood, 1e-8);
+            assert_close(fit.standard_errors()[1], 1.2_f64.sqrt(), 1e-6);
+
+            // probit fits the normal quantiles of the group proportions
+            let probit = fit_glm(&x, &y, Family::Binomial, Link::Probit);
+            let normal = Link::Probit;
+            assert_close(probit.params[0], normal.link(3.0 / 8.0), 1e-8);
+            assert_close(probit.params[1], normal.link(6.0 / 8.0) - normal.link(3.0 / 8.0), 1e-8);
+            let cloglog = fit_glm(&x, &y, Family::Binomial, Link::Cloglog);
+            assert_close(cloglog.mu[0], 3.0 / 8.0, 1e-8);
+        }
+
+        #[test]
+        fn test_glm_exposure_and_weights() {
+            let (x, y) = count_data();
+            let exposure = array![1.0, 2.0, 1.0, 4.0, 2.0, 1.0, 1.0, 3.0];
+            let fit = glm_rs(&x, &y, Family::Poisson, Link::Log, true, None, Some(&exposure), None, 100, 1e-12).unwrap();
+            // rates sum(y) / sum(exposure) per group
+            assert_close(fit.params[0], (8.0_f64 / 8.0).ln(), 1e-8);
+            assert_close(fit.params[1], (24.0_f64 / 7.0).ln() - (8.0_f64 / 8.0).ln(), 1e-8);
+            let offset = exposure.mapv(f64::ln);
+            let with_offset = glm_rs(&x, &y, Family::Poisson, Link::Log, true, Some(&offset), None, None, 100, 1e-12).unwrap();
+            assert_close(with_offset.params[1], fit.params[1], 1e-10);
+            let predicted = fit.predict(&array![[1.0]], None, Some(&array![2.0]), false).unwrap();
+            assert_close(predicted[0], 2.0 * 24.0 / 7.0, 1e-8);
+
+            // frequency weights match repeated rows
+            let weights = array![1.0, 2.0, 1.0, 1.0, 3.0, 1.0, 1.0, 2.0];
+            let weighted = glm_rs(&x, &y, Family::Poisson, Link::Log, true, None, None, Some(&weights), 100, 1e-12).unwrap();
+            let rows = [0, 1, 1, 2, 3, 4, 4, 4, 5, 6, 7, 7];
+            let repeated = fit_glm(&x.select(ndarray::Axis(0), &rows), &y.select(ndarray::Axis(0), &rows), Family::Poisson, Link::Log);
+            assert_close(weighted.params[1], repeated.params[1], 1e-8);
+            assert_close(weighted.standard_errors()[1], repeated.standard_errors()[1], 1e-8);
+            assert_close(weighted.deviance, repeated.deviance, 1e-8);
+            assert_eq!(weighted.nobs, 12.0);
+        }
+
+        #[test]
+        fn test_glm_errors() {
+            let (x, y) = count_data();
+            let negative = array![-1.0, 3.0, 2.0, 2.0, 5.0, 7.0, 4.0, 8.0];
+            assert_eq!(glm_rs(&x, &negative, Family::Poisson, Link::Log, true, None, None, None, 100, 1e-8).unwrap_err(),
+                       LinearModelError::InvalidInputValue);
+            let exposure = Array1::ones(8);
+            assert_eq!(glm_rs(&x, &y, Family::Poisson, Link::Identity, true, None, Some(&exposure), None, 100, 1e-8).unwrap_err(),
+                       LinearModelError::InvalidInputValue);
+            assert_eq!(glm_rs(&x, &y, Family::Poisson, Link::Log, true, Some(&array![0.0]), None, None, 100, 1e-8).unwrap_err(),
+                       LinearModelError::ShapeMismatch);
+            assert_eq!(Family::parse("tweedie", 1.0, 0.5).unwrap_err(), LinearModelError::InvalidInputValue);
+            assert_eq!(Link::parse("power", None).unwrap_err(), LinearModelError::InvalidInputValue);
+
+            let model = Glm::new_rs(Family::Poisson, None, true, 100, 1e-8).unwrap();
+            assert_eq!(model.link, Link::Log);
+            assert_eq!(LinearModel::predict(&model, &x).unwrap_err(), LinearModelError::NotFitted);
+        }
+    }
+
+    mod diagnostics_tests {
+        use super::*;
+
+        fn without_row(x: &Array2<f64>, y: &Array1<f64>, i: usize) -> (Array2<f64>, Array1<f64>) {
+            let rows: Vec<usize> = (0..y.len()).filter(|&r| r != i).collect();
+            (x.select(ndarray::Axis(0), &rows), y.select(ndarray::Axis(0), &rows))
+        }
+
+        #[test]
+        fn test_leverage_and_residuals() {
+            // h_i = 1 / n + (x_i - mean)^2 / sxx
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let leverage = leverage_rs(&fit);
+            for (h, expected) in leverage.iter().zip([0.6, 0.3, 0.2, 0.3, 0.6]) {
+                assert_close(*h, expected, 1e-12);
+            }
+            let standardized = residuals_rs(&fit, ResidualKind::Standardized);
+            assert_close(standardized[2], 0.1 / (fit.scale() * 0.8).sqrt(), 1e-10);
+
+            // the externally studentized residual uses the scale of the fit without the observation
+            let (x, y) = correlated_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let leverage = leverage_rs(&fit);
+            let studentized = residuals_rs(&fit, ResidualKind::Studentized);
+            let dfbetas = dfbetas_rs(&fit);
+            let cooks = cooks_distance_rs(&fit);
+            let dffits = dffits_rs(&fit);
+            for i in 0..8 {
+                let (x_loo, y_loo) = without_row(&x, &y, i);
+                let loo = ols_rs(&x_loo, &y_loo, true, Solver::Qr).unwrap();
+                let s = loo.scale().sqrt();
+                assert_close(studentized[i], fit.residuals[i] / (s * (1.0 - leverage[i]).sqrt()), 1e-8);
+                for j in 0..4 {
+                    let change = (fit.params[j] - loo.params[j]) / (s * fit.xtx_inverse[[j, j]].sqrt());
+                    assert_close(dfbetas[[i, j]], change, 1e-8);
+                }
+                let shift = &fit.fitted_values - &fit.design.dot(&loo.params);
+                assert_close(cooks[i], shift.dot(&shift) / (4.0 * fit.scale()), 1e-8);
+                assert_close(dffits[i], shift[i] / (s * leverage[i].sqrt()), 1e-8);
+            }
+        }
+
+        #[test]
+        fn test_variance_inflation_factors() {
+            // with two features both factors are 1 / (1 - r^2)
+            let (x, y) = correlated_data();
+            let two = x.slice(ndarray::s![.., ..2]).to_owned();
+            let fit = ols_rs(&two, &y, true, Solver::Qr).unwrap();
+            let (a, b) = (two.column(0), two.column(1));
+            let (ma, mb) = (a.mean().unwrap(), b.mean().unwrap());
+            let sab: f64 = a.iter().zip(b.iter()).map(|(u, v)| (u - ma) * (v - mb)).sum();
+            let saa: f64 = a.iter().map(|u| (u - ma).powi(2)).sum();
+            let sbb: f64 = b.iter().map(|v| (v - mb).powi(2)).sum();
+            let r2 = sab * sab / (saa * sbb);
+            let factors = variance_inflation_factors_rs(&fit).unwrap();
+            assert_close(factors[0], 1.0 / (1.0 - r2), 1e-10);
+            assert_close(factors[1], 1.0 / (1.0 - r2), 1e-10);
+        }
+
+        #[test]
+        fn test_specification_tests() {
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            // residuals 0.02, -0.04, 0.10, -0.16, 0.08
+            assert_close(durbin_watson_rs(&fit), 0.1484 / 0.044, 1e-10);
+
+            // with one regressor the auxiliary R^2 is the squared correlation of e^2 and x
+            let squared = fit.residuals.mapv(|e| e * e);
+            let ms = squared.mean().unwrap();
+            let sxe: f64 = squared.iter().zip(1..=5).map(|(e, x)| (e - ms) * (x as f64 - 3.0)).sum();
+            let see: f64 = squared.iter().map(|e| (e - ms).powi(2)).sum();
+            let (lm, p, df) = breusch_pagan_rs(&fit).unwrap();
+            assert_close(lm, 5.0 * sxe * sxe / (see * 10.0), 1e-10);
+            assert_eq!(df, 1.0);
+            assert!(p > 0.0 && p < 1.0);
+            let (_lm, _p, df) = white_rs(&fit).unwrap();
+            assert_eq!(df, 2.0);
+            // the square of a binary feature is the feature itself and drops out
+            let (binary, target) = binary_data();
+            let binary_fit = ols_rs(&binary, &target, true, Solver::Qr).unwrap();
+            assert_eq!(white_rs(&binary_fit).unwrap().2, 1.0);
+            // without an intercept only a constant column of the design can stand in for it
+            let (x, y) = simple_data();
+            let no_intercept = ols_rs(&x, &y, false, Solver::Qr).unwrap();
+            assert_eq!(breusch_pagan_rs(&no_intercept).unwrap_err(), LinearModelError::InvalidInputValue);
+            assert_eq!(white_rs(&no_intercept).unwrap_err(), LinearModelError::InvalidInputValue);
+            let explicit = ols_rs(&add_intercept_rs(&x), &y, false, Solver::Qr).unwrap();
+            assert_close(breusch_pagan_rs(&explicit).unwrap().0, lm, 1e-10);
+            assert_eq!(white_rs(&explicit).unwrap().2, 2.0);
+
+            // RESET is the F test of the fitted value powers added to the design
+            let (x, y) = correlated_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let mut augmented = Array2::<f64>::zeros((8, 5));
+            augmented.slice_mut(ndarray::s![.., ..3]).assign(&x);
+            augmented.column_mut(3).assign(&fit.fitted_values.mapv(|v| v * v));
+            augmented.column_mut(4).assign(&fit.fitted_values.mapv(|v| v * v * v));
+            let unrestricted = ols_rs(&augmented, &y, true, Solver::Qr).unwrap();
+            let (f, _p, q) = reset_rs(&fit, 3).unwrap();
+            assert_close(f, ((fit.ssr - unrestricted.ssr) / 2.0) / (unrestricted.ssr / 2.0), 1e-6);
+            assert_eq!(q, 2.0);
+            assert_eq!(reset_rs(&fit, 1).unwrap_err(), LinearModelError::InvalidInputValue);
+        }
+    }
+
+    mod covariance_tests {
+        use super::*;
+
+        #[test]
+        fn test_heteroskedasticity_consistent_covariance() {
+            // for the slope of a simple regression HC var = sum (x - mean)^2 e^2 w / sxx^2
+            let (x, y) = simple_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            let centered = [-2.0, -1.0, 0.0, 1.0, 2.0];
+            let leverage = [0.6, 0.3, 0.2, 0.3, 0.6];
+            let slope_variance = |weight: &dyn Fn(usize) -> f64| -> f64 {
+                (0..5).map(|i| centered[i] * centered[i] * fit.residuals[i].powi(2) * weight(i)).sum::<f64>() / 100.0
+            };
+            let hc0 = fit.with_cov_type(CovType::Hc0).unwrap();
+            assert_close(hc0.cov_params()[[1, 1]], 0.000544, 1e-12);
+            let hc1 = fit.with_cov_type(CovType::Hc1).unwrap();
+            assert_close(hc1.cov_params()[[1, 1]], 0.000544 * 5.0 / 3.0, 1e-12);
+            let hc2 = fit.with_cov_type(CovType::Hc2).unwrap();
+            assert_close(hc2.cov_params()[[1, 1]], slope_variance(&|i| 1.0 / (1.0 - leverage[i])), 1e-12);
+            let hc3 = fit.with_cov_type(CovType::Hc3).unwrap();
+            assert_close(hc3.cov_params()[[1, 1]], slope_variance(&|i| (1.0 - leverage[i]).powi(-2)), 1e-12);
+            // a single slope's Wald F is its squared robust t
+            assert_close(hc3.f_test().0, hc3.t_values()[1].powi(2), 1e-8);
+            assert_eq!(hc3.inference_df(), 3.0);
+            // the classical fit is untouched
+            assert_close(fit.standard_errors()[1], (fit.scale() / 10.0).sqrt(), 1e-12);
+        }
+
+        #[test]
+        fn test_cluster_and_hac_covariance() {
+            let (x, y) = correlated_data();
+            let fit = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            // singleton clusters reduce to HC0 with G / (G - 1) (n - 1) / (n - k) = n / (n - k), i.e. HC1
+            let singletons = CovType::Cluster((0..8).collect());
+            let clustered = fit.with_cov_type(singletons).unwrap();
+            let hc1 = fit.with_cov_type(CovType::Hc1).unwrap();
+            for (a, b) in clustered.cov_params().iter().zip(hc1.cov_params().iter()) {
+                assert_close(*a, *b, 1e-12);
+            }
+            assert_eq!(clustered.inference_df(), 7.0);
+
+            // a second dimension of singletons makes the intersection equal to it, leaving the first
+            let groups = vec![0, 0, 1, 1, 2, 2, 3, 3];
+            let one_way = fit.with_cov_type(CovType::Cluster(groups.clone())).unwrap();
+            let two_way = fit.with_cov_type(CovType::TwoWayCluster(groups, (0..8).collect())).unwrap();
+            for (a, b) in one_way.cov_params().iter().zip(two_way.cov_params().iter()) {
+                assert_close(*a, *b, 1e-12);
+            }
+            assert_eq!(two_way.inference_df(), 3.0);
+            let labels = array![[5.0], [5.0], [2.0], [2.0], [7.0], [7.0], [1.0], [1.0]];
+            assert_eq!(CovType::parse("cluster", Some(&labels), None, 8).unwrap(), one_way.cov_type);
+
+            // Newey-West with no lags is HC0; one lag adds half of the first autocovariance of the scores
+            let hc0 = fit.with_cov_type(CovType::Hc0).unwrap();
+            let lagless = fit.with_cov_type(CovType::NeweyWest(0)).unwrap();
+            assert_close(lagless.cov_params()[[1, 1]], hc0.cov_params()[[1, 1]], 1e-12);
+            let mut meat = Array2::<f64>::zeros((4, 4));
+            for t in 0..8 {
+                let score_t = fit.design.row(t).to_owned() * fit.residuals[t];
+                for (a, row) in score_t.iter().enumerate() {
+                    for b in 0..4 {
+                        meat[[a, b]] += row * score_t[b];
+                        if t > 0 {
+                            let score_lag = fit.design.row(t - 1).to_owned() * fit.residuals[t - 1];
+                            meat[[a, b]] += 0.5 * (row * score_lag[b] + score_lag[a] * score_t[b]);
+                        }
+                    }
+                }
+            }
+            let expected = fit.xtx_inverse.dot(&meat).dot(&fit.xtx_inverse);
+            let hac = fit.with_cov_type(CovType::NeweyWest(1)).unwrap();
+            for (a, b) in hac.cov_params().iter().zip(expected.iter()) {
+                assert_close(*a, *b, 1e-12);
+            }
+
+            assert_eq!(CovType::parse("hc1", Some(&labels), None, 8).unwrap_err(), LinearModelError::InvalidInputValue);
+            assert_eq!(CovType::parse("cluster", None, None, 8).unwrap_err(), LinearModelError::InvalidInputValue);
+            assert_eq!(CovType::parse("hac", None, None, 100).unwrap(), CovType::NeweyWest(4));
+            // clusters numbered by first appearance, -0 and 0 being one cluster
+            let labels = array![[2.0, 1.0], [-0.0, 1.0], [0.0, 3.0], [2.0, 3.0], [5.0, 1.0]];
+            assert_eq!(CovType::parse("cluster", Some(&labels.slice(ndarray::s![.., ..1]).to_owned()), None, 5).unwrap(),
+                       CovType::Cluster(vec![0, 1, 1, 0, 2]));
+            assert_eq!(CovType::parse("cluster", Some(&labels), None, 5).unwrap(),
+                       CovType::TwoWayCluster(vec![0, 1, 1, 0, 2], vec![0, 0, 1, 1, 0]));
+            assert_eq!(fit.with_cov_type(CovType::Cluster(vec![0; 8])).unwrap_err(), LinearModelError::InsufficientGroups);
+        }
+    }
+
+    mod robust_tests {
+        use super::*;
+
+        fn contaminated_data() -> (Array2<f64>, Array1<f64>) {
+            // y = 1 + 2x with small errors, and a gross outlier in the last observation
+            let x: Array1<f64> = (0..10).map(f64::from).collect();
+            let noise = array![0.1, -0.1, 0.05, -0.05, 0.0, 0.1, -0.1, 0.05, -0.05, 50.0];
+            let y = x.mapv(|v| 1.0 + 2.0 * v) + noise;
+            (x.insert_axis(ndarray::Axis(1)), y)
+        }
+
+        #[test]
+        fn test_m_estimation_downweights_outliers() {
+            let (x, y) = contaminated_data();
+            let ols = ols_rs(&x, &y, true, Solver::Qr).unwrap();
+            assert!((ols.params[1] - 2.0).abs() > 1.0);
+            for norm in [Norm::parse("huber", None).unwrap(), Norm::parse("bisquare", None).unwrap()] {
+                let fit = m_estimate_rs(&x, &y, norm, true, 50, 1e-10).unwrap();
+                assert!((fit.params[0] - 1.0).abs() < 0.2 && (fit.params[1] - 2.0).abs() < 0.05, "{:?}", fit.params);
+                // at convergence the params solve the weighted normal equations X'W(y - Xb) = 0
+                let design = add_intercept_rs(&x);
+                let residuals = &y - &design.dot(&fit.params);
+                let gradient = design.t().dot(&(&fit.weights * &residuals));
+                assert!(gradient.iter().all(|g| g.abs() < 1e-6), "{:?}", gradient);
+                assert!(fit.weights[9] < 0.05);
+            }
+            // the bisquare rejects the outlier outright
+            let tukey = m_estimate_rs(&x, &y, Norm::TukeyBisquare(4.685), true, 50, 1e-10).unwrap();
+            assert_eq!(tukey.weights[9], 0.0);
+            assert_close(Norm::Huber(1.345).weight(2.69), 0.5, 1e-12);
+            assert_eq!(Norm::parse("huber", Some(-1.0)).unwrap_err(), LinearModelError::InvalidInputValue);
+        }
+
+        #[test]
+        fn test_ransac_and_theil_sen_recover_exact_line() {
+            let x: Array1<f64> = (0..10).map(f64::from).collect();
+            let mut y = x.mapv(|v| 1.0 + 2.0 * v);
+            y[3] = 40.0;
+            y[8] = -5.0;
+            let x = x.insert_axis(ndarray::Axis(1));
+
+            let ransac = ransac_rs(&x, &y, true, None, Some(0.5), 100, 7).unwrap();
+            assert_close(ransac.params[0], 1.0, 1e-10);
+            assert_close(ransac.params[1], 2.0, 1e-10);
+            assert_eq!(ransac.weights.sum(), 8.0);
+            assert_eq!(ransac.weights[3], 0.0);
+            assert_eq!(ransac.weights[8], 0.0);
+            // the same seed gives the same fit
+            let again = ransac_rs(&x, &y, true, None, Some(0.5), 100, 7).unwrap();
+            assert_eq!(ransac.params, again.params);
+            assert_eq!(ransac_rs(&x, &y, true, Some(1), None, 100, 7).unwrap_err(), LinearModelError::InvalidInputValue);
+
+            // 28 of the 45 pairs lie on the line, and a majority point is the spatial median
+            let theil_sen = theil_sen_rs(&x, &y, true, 10000, 300, 1e-10, 0).unwrap();
+            assert_close(theil_sen.params[0], 1.0, 1e-8);
+            assert_close(theil_sen.params[1], 2.0, 1e-8);
+            let sampled = theil_sen_rs(&x, &y, true, 30, 300, 1e-10, 3).unwrap();
+            assert!((sampled.params[1] - 2.0).abs() < 0.5);
+        }
+
+        #[test]
+        fn test_quantile_regression_minimizes_check_loss() {
+            let (x, y) = correlated_data();
+            let x = x.slice(ndarray::s![.., ..1]).to_owned();
+            for quantile in [0.5, 0.8] {
+                let check_loss = |intercept: f64, slope: f64| -> f64 {
+                    (0..8).map(|i| {
+                        let r = y[i] - intercept - slope * x[[i, 0]];
+                        if r > 0.0 { quantile * r } else { (quantile - 1.0) * r }
+                    }).sum()
+                };
+                // an optimum passes through two observations
+                let mut best = f64::INFINITY;
+                for i in 0..8 {
+                    for j in i + 1..8 {
+                        let slope = (y[j] - y[i]) / (x[[j, 0]] - x[[i, 0]]);
+                        best = best.min(check_loss(y[i] - slope * x[[i, 0]], slope));
+                    }
+                }
+                let fit = quantile_regression_rs(&x, &y, quantile, true, 1000, 1e-8).unwrap();
+                assert_close(check_loss(fit.params[0], fit.params[1]), best, 1e-4);
+            }
+            assert_eq!(quantile_regression_rs(&x, &y, 1.0, true, 1000, 1e-8).unwrap_err(), LinearModelError::InvalidInputValue);
+            let model = QuantileRegression::new_rs(0.5, true, 1000, 1e-6).unwrap();
+            assert_eq!(LinearModel::predict(&model, &x).unwrap_err(), LinearModelError::NotFitted);
+        }
+    }
+
+    mod formula_tests {
+        use super::*;
+
+        fn formula_data() -> DataFrame {
+            DataFrame::new(vec![
+                Series::new("y", &[1.0, 2.0, 4.0, 3.0, 6.0, 5.0]),
+                Series::new("x", &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+                Series::new("z", &[2.0, 1.0, 0.5, 3.0, 1.5, 2.5]),
+                Series::new("g", &["b", "a", "c", "a", "b", "c"]),
+                Series::new("h", &["u", "v", "u", "v", "u", "v"]),
+            ]).unwrap()
+        }
+
+        #[test]
+        fn test_formula_parsing() {
+            let spec = parse_formula_rs("y ~ a * b - a + np.log(c) + C(g, Treatment(reference='b'))").unwrap();
+            assert_eq!(spec.response.as_ref().unwrap().0, "y");
+            assert!(spec.intercept);
+            let labels: Vec<String> = spec.terms.iter()
+                .map(|t| t.iter().map(|f| f.label()).collect::<Vec<&str>>().join(":"))
+                .collect();
+            assert_eq!(labels, vec!["b", "np.log(c)", "C(g, Treatment(reference='b'))", "a:b"]);
+            assert!(!parse_formula_rs("y ~ x - 1").unwrap().intercept);
+            assert!(!parse_formula_rs("y ~ 0 + x").unwrap().intercept);
+            assert!(parse_formula_rs("y ~ 0 + x + 1").unwrap().intercept);
+            for malformed in ["y ~ x +", "y x", "y ~ (x + z", "y ~ foo(x)", "y ~ C(g, Helmert)", "y ~ x $ z"] {
+                assert_eq!(parse_formula_rs(malformed).unwrap_err(), LinearModelError::InvalidFormula);
+            }
+        }
+
+        #[test]
+        fn test_formula_treatment_and_sum_coding() {
+            let data = formula_data();
+            let spec = parse_formula_rs("y ~ x + g").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["x", "g[T.b]", "g[T.c]"]);
+            assert_eq!(design.x.column(1).to_vec(), vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+            assert_eq!(design.levels, vec![("g".to_string(), vec!["a".to_string(), "b".to_string(), "c".to_string()])]);
+
+            let spec = parse_formula_rs("y ~ C(g, Treatment('c'))").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["C(g, Treatment('c'))[T.a]", "C(g, Treatment('c'))[T.b]"]);
+
+            let spec = parse_formula_rs("y ~ C(g, Sum)").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["C(g, Sum)[S.a]", "C(g, Sum)[S.b]"]);
+            assert_eq!(design.x.column(0).to_vec(), vec![0.0, 1.0, -1.0, 1.0, 0.0, -1.0]);
+
+            // without an intercept the categorical keeps every level
+            let spec = parse_formula_rs("y ~ g + x - 1").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["g[a]", "g[b]", "g[c]", "x"]);
+        }
+
+        #[test]
+        fn test_formula_interactions_and_transforms() {
+            let data = formula_data();
+            let spec = parse_formula_rs("np.log(y) ~ x:g + I(x ** 2 - z)").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            // without x in the model every level of g gets its own slope
+            assert_eq!(design.column_names, vec!["I(x ** 2 - z)", "x:g[a]", "x:g[b]", "x:g[c]"]);
+            assert_eq!(design.x.column(0).to_vec(), vec![-1.0, 3.0, 8.5, 13.0, 23.5, 33.5]);
+            assert_eq!(design.x.column(1).to_vec(), vec![0.0, 2.0, 0.0, 4.0, 0.0, 0.0]);
+            assert_eq!(design.x.column(2).to_vec(), vec![1.0, 0.0, 0.0, 0.0, 5.0, 0.0]);
+            assert_close(design.y.as_ref().unwrap()[2], 4.0f64.ln(), 1e-12);
+
+            let spec = parse_formula_rs("y ~ x + x:g").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["x", "x:g[T.b]", "x:g[T.c]"]);
+
+            // one column per cell of g by h next to the intercept, as patsy codes it
+            let spec = parse_formula_rs("y ~ g:h").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["h[T.v]", "g[T.b]:h[u]", "g[T.b]:h[v]", "g[T.c]:h[u]", "g[T.c]:h[v]"]);
+            let spec = parse_formula_rs("y ~ 0 + g + h").unwrap();
+            let design = design_matrices_rs(&spec, &data, None, true).unwrap();
+            assert_eq!(design.column_names, vec!["g[a]", "g[b]", "g[c]", "h[T.v]"]);
+        }
+
+        #[test]
+        fn test_formula_missing_and_unseen_values() {
+            let data = DataFrame::new(vec![
+                Series::new("y", &[Some(1.0), Some(2.0), None, Some(4.0)]),
+                Series::new("x", &[Some(1.0), Some(f64::NAN), Some(3.0), Some(4.0)]),
+                Series::new("g", &[Some("a"), Some("b"), Some("b"), None]),
+            ]).unwrap();
+            let spec = parse_formula_rs("y ~ x + g").unwrap();
+            assert_eq!(design_matrices_rs(&spec, &data, None, true).unwrap().rows, vec![0]);
+
+            let mut formula = Formula::new_rs("y ~ g").unwrap();
+            formula.design_matrices_rs(&formula_data()).unwrap();
+            let new_data = DataFrame::new(vec![Series::new("g", &["a", "d"])]).unwrap();
+            assert_eq!(formula.design_matrix_rs(&new_data).unwrap_err(), LinearModelError::InvalidInputValue);
+            let new_data = DataFrame::new(vec![Series::new("g", &["c", "a"])]).unwrap();
+            assert_eq!(formula.design_matrix_rs(&new_data).unwrap(), array![[0.0, 1.0], [0.0, 0.0]]);
+            let missing_column = DataFrame::new(vec![Series::new("h", &["a"])]).unwrap();
+            assert_eq!(formula.design_matrix_rs(&missing_column).unwrap_err(), LinearModelError::InvalidFormula);
+        }
+
+        #[test]
+        fn test_formula_ols_matches_manual_design() {
+            let mut formula = Formula::new_rs("y ~ x + g").unwrap();
+            let (y, x) = formula.design_matrices_rs(&formula_data()).unwrap();
+            let manual = array![
+                [1.0, 1.0, 0.0], [2.0, 0.0, 0.0], [3.0, 0.0, 1.0],
+                [4.0, 0.0, 0.0], [5.0, 1.0, 0.0], [6.0, 0.0, 1.0],
+            ];
+            let from_formula = ols_rs(&x, &y, formula.spec.intercept, Solver::Qr).unwrap();
+            let by_hand = ols_rs(&manual, &y, true, Solver::Qr).unwrap();
+            for (a, b) in from_formula.params.iter().zip(by_hand.params.iter()) {
+                assert_close(*a, *b, 1e-10);
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+// Resampling engine
+// bootstrap -- nonparametric, stratified, moving block and bayesian bootstrap of a statistic
+// jackknife -- leave-one-out replicates, standard error and bias
+//
+// The statistic is either the name of a descriptive statistic or a Python callable taking a
+// numpy array. Named statistics are evaluated in parallel, callables under the GIL one at a time.
+// The bayesian bootstrap reweights instead of resampling, so it takes the named statistics with a
+// weighted form (mean, variance and std) and no callables.
+// Replicate b always draws from its own stream of a ChaCha generator seeded with `seed`, so the
+// results are reproducible whatever the number of threads.
+
+use std::collections::HashMap;
+
+use ndarray::{Array1, Axis};
+use numpy::IntoPyArray;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::descriptive_statistics::{iqr_rs, kurtosis_rs, mean_rs, median_absolute_deviation_rs, median_rs,
+                                    percentile_rs, range_rs, sample_variance_rs, skewness_rs, trimmed_mean_rs};
+use crate::inferential_statistics::errors::*;
+use crate::utils::from_pyarray1;
+use crate::validate_statistical_input;
+#[cfg(test)]
+mod unit_tests;
+
+
+/// Rust Native Computations
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BootstrapKind {
+    Nonparametric,
+    // positions of the observations in each stratum, every stratum is resampled separately
+    Stratified(Vec<Vec<usize>>),
+    // moving blocks of the given length, for serially dependent data
+    Block(usize),
+    // Rubin's bayesian bootstrap, the statistic weighted by flat Dirichlet weights
+    Bayesian,
+}
+
+impl BootstrapKind {
+    pub fn parse(method: &str, strata: Option<&Array1<f64>>, block_length: Option<usize>, n: usize)
+                 -> Result<BootstrapKind, StatsError> {
+        match method {
+            "nonparametric" => Ok(BootstrapKind::Nonparametric),
+            "stratified" => {
+                let labels = strata.ok_or(StatsError::InvalidInputValue)?;
+                if labels.len() != n { return Err(StatsError::InconsistentLength); }
+                Ok(BootstrapKind::Stratified(strata_positions(labels)))
+            }
+            "block" => {
+                // default to the usual n^(1/3) block length
+                let length = block_length.unwrap_or(((n as f64).cbrt().round() as usize).max(1));
+                if length == 0 || length > n { return Err(StatsError::InvalidInputValue); }
+                Ok(BootstrapKind::Block(length))
+            }
+            "bayesian" => Ok(BootstrapKind::Bayesian),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    Percentile,
+    Bca,
+}
+
+impl Interval {
+    pub fn parse(interval: &str) -> Result<Interval, StatsError> {
+        match interval {
+            "percentile" => Ok(Interval::Percentile),
+            "bca" => Ok(Interval::Bca),
+            _ => Err(StatsError::InvalidInputValue),
+        }
+    }
+}
+
+pub type Statistic = Box<dyn Fn(&Array1<f64>) -> f64 + Send + Sync>;
+// statistic of the data under weights summing to one, equal weights giving the plain statistic
+pub type WeightedStatistic = fn(&Array1<f64>, &Array1<f64>) -> f64;
+
+fn std_rs(data: &Array1<f64>) -> f64 {
+    sample_variance_rs(data).sqrt()
+}
+
+// The same functions the descriptive_statistics pyfunctions of that name compute,
+// trimmed_mean cutting trim_percent from each end
+pub fn statistic_by_name(name: &str, trim_percent: f64) -> Result<Statistic, StatsError> {
+    let statistic: fn(&Array1<f64>) -> f64 = match name {
+        "mean" => mean_rs,
+        "median" => median_rs,
+        "trimmed_mean" => {
+            // every resample keeps at least one observation
+            if !(0.0..0.5).contains(&trim_percent) { return Err(StatsError::InvalidInputValue); }
+            return Ok(Box::new(move |data| trimmed_mean_rs(data, trim_percent)));
+        }
+        "variance" => sample_variance_rs,
+        "std" => std_rs,
+        "skewness" => skewness_rs,
+        "kurtosis" => kurtosis_rs,
+        "median_absolute_deviation" => median_absolute_deviation_rs,
+        "iqr" => iqr_rs,
+        "range" => range_rs,
+        _ => return Err(StatsError::InvalidInputValue),
+    };
+    Ok(Box::new(statistic))
+}
+
+pub fn weighted_mean_rs(data: &Array1<f64>, weights: &Array1<f64>) -> f64 {
+    data.dot(weights) / weights.sum()
+}
+
+// Weighted second central moment with the n / (n - 1) correction of the sample variance
+pub fn weighted_variance_rs(data: &Array1<f64>, weights: &Array1<f64>) -> f64 {
+    let n = data.len() as f64;
+    let mean = weighted_mean_rs(data, weights);
+    let moment = data.iter().zip(weights.iter()).map(|(x, w)| w * (x - mean).powi(2)).sum::<f64>() / weights.sum();
+    moment * n / (n - 1.0)
+}
+
+fn weighted_std_rs(data: &Array1<f64>, weights: &Array1<f64>) -> f64 {
+    weighted_variance_rs(data, weights).sqrt()
+}
+
+// Statistics the bayesian bootstrap can reweight
+pub fn weighted_statistic_by_name(name: &str) -> Result<WeightedStatistic, StatsError> {
+    match name {
+        "mean" => Ok(weighted_mean_rs),
+        "variance" => Ok(weighted_variance_rs),
+        "std" => Ok(weighted_std_rs),
+        _ => Err(StatsError::InvalidInputValue),
+    }
+}
+
+// Groups positions by label, strata ordered by first appearance
+fn strata_positions(labels: &Array1<f64>) -> Vec<Vec<usize>> {
+    let mut index: HashMap<u64, usize> = HashMap::new();
+    let mut strata: Vec<Vec<usize>> = Vec::new();
+    for (i, label) in labels.iter().enumerate() {
+        let k = *index.entry(label.to_bits()).or_insert_with(|| {
+            strata.push(Vec::new());
+            strata.len() - 1
+        });
+        strata[k].push(i);
+    }
+    strata
+}
+
+// Independent generator for replicate b, the same for a given seed whichever thread runs it
+pub(crate) fn replicate_rng(seed: u64, replicate: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(replicate as u64);
+    rng
+}
+
+// Positions of one bootstrap sample of size n
+pub fn resample_indices_rs(kind: &BootstrapKind, n: usize, rng: &mut ChaCha8Rng) -> Vec<usize> {
+    match kind {
+        BootstrapKind::Nonparametric => (0..n).map(|_| rng.gen_range(0..n)).collect(),
+        BootstrapKind::Stratified(strata) => strata.iter()
+            .flat_map(|stratum| (0..stratum.len()).map(|_| stratum[rng.gen_range(0..stratum.len())]).collect::<Vec<usize>>())
+            .collect(),
+        BootstrapKind::Block(length) => {
+            let mut indices = Vec::with_capacity(n + length);
+            while indices.len() < n {
+                let start = rng.gen_range(0..=n - length);
+                indices.extend(start..start + length);
+            }
+            indices.truncate(n);
+            indices
+        }
+        // every observation is kept and reweighted, see dirichlet_weights_rs
+        BootstrapKind::Bayesian => (0..n).collect(),
+    }
+}
+
+// Flat Dirichlet(1, ..., 1) weights, as normalised standard exponentials
+pub fn dirichlet_weights_rs(n: usize, rng: &mut ChaCha8Rng) -> Array1<f64> {
+    let draws: Array1<f64> = (0..n).map(|_| -(1.0 - rng.gen::<f64>()).ln()).collect();
+    let total = draws.sum();
+    draws / total
+}
+
+// One set of positions per replicate, generated in parallel
+pub fn bootstrap_indices_rs(kind: &BootstrapKind, n: usize, n_resamples: usize, seed: u64) -> Vec<Vec<usize>> {
+    (0..n_resamples).into_par_iter()
+        .map(|b| resample_indices_rs(kind, n, &mut replicate_rng(seed, b)))
+        .collect()
+}
+
+// The bayesian bootstrap needs the weighted form of the statistic and fails without one
+pub fn bootstrap_rs<S>(data: &Array1<f64>, statistic: S, weighted: Option<WeightedStatistic>, kind: &BootstrapKind,
+                       n_resamples: usize, seed: u64) -> Result<Array1<f64>, StatsError>
+    where S: Fn(&Array1<f64>) -> f64 + Sync {
+    if data.is_empty() { return Err(StatsError::EmptyDataSet); }
+    if n_resamples == 0 { return Err(StatsError::InvalidInputValue); }
+    if *kind == BootstrapKind::Bayesian {
+        let weighted = weighted.ok_or(StatsError::InvalidInputValue)?;
+        let replicates: Vec<f64> = (0..n_resamples).into_par_iter()
+            .map(|b| weighted(data, &dirichlet_weights_rs(data.len(), &mut replicate_rng(seed, b))))
+            .collect();
+        return Ok(Array1::from_vec(replicates));
+    }
+    let replicates: Vec<f64> = (0..n_resamples).into_par_iter()
+        .map(|b| {
+            let indices = resample_indices_rs(kind, data.len(), &mut replicate_rng(seed, b));
+            statistic(&data.select(Axis(0), &indices))
+        })
+        .collect();
+    Ok(Array1::from_vec(replicates))
+}
+
+// Bootstrap of a statistic of several samples, each resampled on its own so the sample sizes
+// stay fixed. Replicate b draws every sample from the same stream, in order.
+pub fn bootstrap_samples_rs<F>(samples: &[&Array1<f64>], statistic: F, n_resamples: usize,
+                               seed: u64) -> Result<Array1<f64>, StatsError>
+    where F: Fn(&[Array1<f64>]) -> Result<f64, StatsError> + Sync {
+    if samples.iter().any(|s| s.is_empty()) { return Err(StatsError::EmptyDataSet); }
+    if n_resamples == 0 { return Err(StatsError::InvalidInputValue); }
+    let replicates = (0..n_resamples).into_par_iter()
+        .map(|b| {
+            let mut rng = replicate_rng(seed, b);
+            let resampled: Vec<Array1<f64>> = samples.iter()
+                .map(|s| s.select(Axis(0), &resample_indices_rs(&BootstrapKind::Nonparametric, s.len(), &mut rng)))
+                .collect();
+            statistic(&resampled)
+        })
+        .collect::<Result<Vec<f64>, StatsError>>()?;
+    Ok(Array1::from_vec(replicates))
+}
+
+// The n samples with one observation left out
+pub fn leave_one_out_rs(data: &Array1<f64>) -> impl Iterator<Item = Array1<f64>> + '_ {
+    let n = data.len();
+    (0..n).map(move |i| {
+        let kept: Vec<usize> = (0..n).filter(|&j| j != i).collect();
+        data.select(Axis(0), &kept)
+    })
+}
+
+// Leave-one-out values of the statistic
+pub fn jackknife_rs<S: Fn(&Array1<f64>) -> f64>(data: &Array1<f64>, statistic: S) -> Result<Array1<f64>, StatsError> {
+    if data.len() < 2 { return Err(StatsError::InvalidInputValue); }
+    Ok(leave_one_out_rs(data).map(|sample| statistic(&sample)).collect())
+}
+
+// Returns (standard error, bias) of the jackknife replicates around the full sample estimate
+pub fn jackknife_summary_rs(estimate: f64, replicates: &Array1<f64>) -> (f64, f64) {
+    let n = replicates.len() as f64;
+    let replicate_mean = mean_rs(replicates);
+    let ss: f64 = replicates.iter().map(|v| (v - replicate_mean).powi(2)).sum();
+    (((n - 1.0) / n * ss).sqrt(), (n - 1.0) * (replicate_mean - estimate))
+}
+
+// Percentile or BCa interval from the bootstrap distribution, BCa takes the jackknife replicates
+pub fn bootstrap_interval_rs(estimate: f64, distribution: &Array1<f64>, confidence_level: f64,
+                             interval: Interval, jackknife: Option<&Array1<f64>>) -> Result<(f64, f64), StatsError> {
+    if confidence_level <= 0.0 || confidence_level >= 1.0 { return Err(StatsError::InvalidInputValue); }
+    let alpha = (1.0 - confidence_level) / 2.0;
+    let (lower, upper) = match interval {
+        Interval::Percentile => (alpha, 1.0 - alpha),
+        Interval::Bca => {
+            let jackknife = jackknife.ok_or(StatsError::InvalidInputValue)?;
+            let normal = Normal::new(0.0, 1.0).unwrap();
+            let below = distribution.iter().filter(|&&v| v < estimate).count() as f64;
+            let z0 = normal.inverse_cdf(below / distribution.len() as f64);
+            if !z0.is_finite() { return Err(StatsError::NoSolution); }
+
+            let jackknife_mean = mean_rs(jackknife);
+            let (mut num, mut den) = (0.0, 0.0);
+            for v in jackknife.iter() {
+                let d = jackknife_mean - v;
+                num += d.powi(3);
+                den += d.powi(2);
+            }
+            let acceleration = if den > 0.0 { num / (6.0 * den.powf(1.5)) } else { 0.0 };
+
+            let adjust = |q: f64| {
+                let z = normal.inverse_cdf(q);
+                normal.cdf(z0 + (z0 + z) / (1.0 - acceleration * (z0 + z)))
+            };
+            (adjust(alpha), adjust(1.0 - alpha))
+        }
+    };
+    Ok((percentile_rs(distribution, 100.0 * lower), percentile_rs(distribution, 100.0 * upper)))
+}
+
+
+/// Pyfunctions
+
+#[pyfunction]
+#[pyo3(signature = (x, statistic = None, method = "nonparametric", n_resamples = 9999, confidence_level = 0.95,
+                    interval = "percentile", strata = None, block_length = None, seed = None, trim_percent = 0.1))]
+#[allow(clippy::too_many_arguments)]
+pub fn bootstrap<'py>(py: Python<'py>, x: &PyAny, statistic: Option<&PyAny>, method: &str, n_resamples: usize,
+                      confidence_level: f64, interval: &str, strata: Option<&PyAny>, block_length: Option<usize>,
+                      seed: Option<u64>, trim_percent: f64) -> PyResult<&'py PyDict> {
+    // statistic: mean (default), median, trimmed_mean (trim_percent from each end), variance, std,
+    // skewness, kurtosis, median_absolute_deviation, iqr, range or a callable
+    // method: nonparametric, stratified (needs strata labels), block or bayesian. The bayesian
+    // bootstrap only takes mean, variance and std, the statistics with a weighted form, and
+    // raises ValueError for any other statistic or a callable
+    // interval: percentile or bca
+    // Returns a dict with distribution, estimate, standard_error, bias and confidence_interval
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    let strata_data = match strata {
+        Some(labels) => match from_pyarray1(labels) {
+            Ok(data) => Some(data),
+            Err(_e) => return Err(StatsError::Conversion.into()),
+        },
+        None => None,
+    };
+    let kind = BootstrapKind::parse(method, strata_data.as_ref(), block_length, x_data.len())?;
+    let interval = Interval::parse(interval)?;
+    if n_resamples == 0 { return Err(StatsError::InvalidInputValue.into()); }
+    if interval == Interval::Bca && x_data.len() < 2 { return Err(StatsError::InvalidInputValue.into()); }
+    let seed = seed.unwrap_or_else(rand::random);
+
+    let (estimate, distribution, jackknife) = match PyStatistic::extract(statistic, trim_percent)? {
+        PyStatistic::Named(statistic, weighted) => {
+            let distribution = bootstrap_rs(&x_data, &statistic, weighted, &kind, n_resamples, seed)?;
+            let jackknife = match interval {
+                Interval::Bca => Some(jackknife_rs(&x_data, &statistic)?),
+                Interval::Percentile => None,
+            };
+            (statistic(&x_data), distribution, jackknife)
+        }
+        PyStatistic::Callable(callable) => {
+            // a callable has no weighted form to hand the dirichlet weights to
+            if kind == BootstrapKind::Bayesian { return Err(StatsError::InvalidInputValue.into()); }
+            let evaluate = |data: Array1<f64>| call_statistic(py, callable, data);
+            let distribution = bootstrap_indices_rs(&kind, x_data.len(), n_resamples, seed).iter()
+                .map(|indices| evaluate(x_data.select(Axis(0), indices)))
+                .collect::<PyResult<Array1<f64>>>()?;
+            let jackknife = match interval {
+                Interval::Bca => Some(leave_one_out_rs(&x_data).map(evaluate).collect::<PyResult<Array1<f64>>>()?),
+                Interval::Percentile => None,
+            };
+            (evaluate(x_data.clone())?, distribution, jackknife)
+        }
+    };
+
+    let ci = bootstrap_interval_rs(estimate, &distribution, confidence_level, interval, jackknife.as_ref())?;
+    let result = PyDict::new(py);
+    result.set_item("estimate", estimate)?;
+    result.set_item("standard_error", sample_variance_rs(&distribution).sqrt())?;
+    result.set_item("bias", mean_rs(&distribution) - estimate)?;
+    result.set_item("confidence_interval", ci)?;
+    result.set_item("distribution", distribution.into_pyarray(py))?;
+    Ok(result)
+}
+
+#[pyfunction]
+#[pyo3(signature = (x, statistic = None, trim_percent = 0.1))]
+pub fn jackknife<'py>(py: Python<'py>, x: &PyAny, statistic: Option<&PyAny>, trim_percent: f64) -> PyResult<&'py PyDict> {
+    // Returns a dict with the leave-one-out replicates, estimate, standard_error and bias
+    // trim_percent is only used by the trimmed_mean statistic
+    let x_data = match from_pyarray1(x) {
+        Ok(data) => data,
+        Err(_e) => return Err(StatsError::Conversion.into()),
+    };
+    validate_statistical_input!(basic, x_data);
+    if x_data.len() < 2 { return Err(StatsError::InvalidInputValue.into()); }
+
+    let (estimate, replicates) = match PyStatistic::extract(statistic, trim_percent)? {
+        PyStatistic::Named(statistic, _) => (statistic(&x_data), jackknife_rs(&x_data, &statistic)?),
+        PyStatistic::Callable(callable) => {
+            let evaluate = |data: Array1<f64>| call_statistic(py, callable, data);
+            let replicates = leave_one_out_rs(&x_data).map(evaluate).collect::<PyResult<Array1<f64>>>()?;
+            (evaluate(x_data.clone())?, replicates)
+        }
+    };
+
+    let (standard_error, bias) = jackknife_summary_rs(estimate, &replicates);
+    let result = PyDict::new(py);
+    result.set_item("estimate", estimate)?;
+    result.set_item("standard_error", standard_error)?;
+    result.set_item("bias", bias)?;
+    result.set_item("replicates", replicates.into_pyarray(py))?;
+    Ok(result)
+}
+
+// The statistic argument, a descriptive statistic by name (mean when omitted) with its weighted
+// form if it has one, or a callable
+enum PyStatistic<'py> {
+    Named(Statistic, Option<WeightedStatistic>),
+    Callable(&'py PyAny),
+}
+
+impl<'py> PyStatistic<'py> {
+    fn extract(statistic: Option<&'py PyAny>, trim_percent: f64) -> PyResult<PyStatistic<'py>> {
+        match statistic {
+            None => Ok(PyStatistic::Named(Box::new(mean_rs), Some(weighted_mean_rs))),
+            Some(statistic) => match statistic.extract::<&str>() {
+                Ok(name) => Ok(PyStatistic::Named(statistic_by_name(name, trim_percent)?, weighted_statistic_by_name(name).ok())),
+                Err(_) if statistic.is_callable() => Ok(PyStatistic::Callable(statistic)),
+                Err(_) => Err(StatsError::InvalidInputValue.into()),
+            },
+        }
+    }
+}
+
+fn call_statistic(py: Python, callable: &PyAny, data: Array1<f64>) -> PyResult<f64> {
+    callable.call1((data.into_pyarray(py),))?.extract::<f64>()
+}
@@ -0,0 +1,162 @@
+// Unit Tests
+use crate::resampling::*;
+use crate::descriptive_statistics::{mean_rs, median_rs, sample_variance_rs, variance_rs};
+use rand::SeedableRng;
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{array, Array1};
+
+    fn assert_close(actual: f64, expected: f64, tol: f64) {
+        assert!((actual - expected).abs() <= tol, "expected {}, got {}", expected, actual);
+    }
+
+    fn sample() -> Array1<f64> {
+        array![2.1, 3.4, 1.9, 5.6, 4.4, 3.8, 2.7, 6.1, 3.3, 4.9, 2.2, 3.9]
+    }
+
+    mod bootstrap_tests {
+        use super::*;
+
+        #[test]
+        fn test_bootstrap_is_reproducible() {
+            let kinds = [BootstrapKind::Nonparametric, BootstrapKind::Block(3), BootstrapKind::Bayesian];
+            for kind in kinds.iter() {
+                let first = bootstrap_rs(&sample(), mean_rs, Some(weighted_mean_rs), kind, 500, 42).unwrap();
+                let second = bootstrap_rs(&sample(), mean_rs, Some(weighted_mean_rs), kind, 500, 42).unwrap();
+                assert_eq!(first, second);
+                assert_ne!(first, bootstrap_rs(&sample(), mean_rs, Some(weighted_mean_rs), kind, 500, 7).unwrap());
+            }
+        }
+
+        #[test]
+        fn test_bootstrap_standard_error_of_mean() {
+            // close to the plug-in standard error sqrt(m2 / n)
+            let data = sample();
+            let distribution = bootstrap_rs(&data, mean_rs, None, &BootstrapKind::Nonparametric, 20000, 1).unwrap();
+            let plug_in = (variance_rs(&data) / data.len() as f64).sqrt();
+            assert_close(sample_variance_rs(&distribution).sqrt(), plug_in, 0.02);
+            assert_close(mean_rs(&distribution), mean_rs(&data), 0.02);
+        }
+
+        #[test]
+        fn test_bayesian_bootstrap_of_the_mean() {
+            // with Dirichlet(1, ..., 1) weights the replicate variance is m2 / (n + 1)
+            let data = sample();
+            let distribution = bootstrap_rs(&data, mean_rs, Some(weighted_mean_rs), &BootstrapKind::Bayesian, 20000, 3).unwrap();
+            let expected = (variance_rs(&data) / (data.len() as f64 + 1.0)).sqrt();
+            assert_close(sample_variance_rs(&distribution).sqrt(), expected, 0.01);
+            assert_close(mean_rs(&distribution), mean_rs(&data), 0.01);
+            // equal weights give back the plain statistics
+            let equal = Array1::from_elem(data.len(), 1.0 / data.len() as f64);
+            assert_close(weighted_variance_rs(&data, &equal), sample_variance_rs(&data), 1e-12);
+            assert_eq!(bootstrap_rs(&data, median_rs, None, &BootstrapKind::Bayesian, 10, 3), Err(StatsError::InvalidInputValue));
+            assert!(weighted_statistic_by_name("median").is_err());
+        }
+
+        #[test]
+        fn test_named_statistics_match_descriptive_statistics() {
+            let data = sample();
+            let n = data.len() as f64;
+            let m2 = variance_rs(&data);
+            let m3 = data.iter().map(|x| (x - mean_rs(&data)).powi(3)).sum::<f64>() / n;
+            // adjusted skewness n^2 m3 / ((n - 1)(n - 2) m2^1.5), not the moment skewness
+            assert_close(statistic_by_name("skewness", 0.1).unwrap()(&data), n * n * m3 / ((n - 1.0) * (n - 2.0) * m2.powf(1.5)), 1e-12);
+            assert!(statistic_by_name("kurtosis", 0.1).unwrap()(&data) < 0.0);
+            assert_close(statistic_by_name("range", 0.1).unwrap()(&data), 4.2, 1e-12);
+            assert_close(statistic_by_name("median_absolute_deviation", 0.1).unwrap()(&data), 1.1, 1e-12);
+            assert_close(statistic_by_name("trimmed_mean", 0.1).unwrap()(&data), 3.63, 1e-12);
+            let quarter = statistic_by_name("trimmed_mean", 0.25).unwrap();
+            assert_close(quarter(&data), crate::descriptive_statistics::trimmed_mean_rs(&data, 0.25), 1e-12);
+            assert!(statistic_by_name("trimmed_mean", 0.5).is_err());
+        }
+
+        #[test]
+        fn test_stratified_keeps_stratum_sizes() {
+            let labels = array![0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 1.0, 0.0, 2.0];
+            let kind = BootstrapKind::parse("stratified", Some(&labels), None, 12).unwrap();
+            let indices = bootstrap_indices_rs(&kind, 12, 50, 3);
+            for sample in indices {
+                let counts = [0.0, 1.0, 2.0].map(|l| sample.iter().filter(|&&i| labels[i] == l).count());
+                assert_eq!(counts, [4, 3, 5]);
+            }
+        }
+
+        #[test]
+        fn test_block_samples_are_contiguous() {
+            let indices = resample_indices_rs(&BootstrapKind::Block(4), 12, &mut rand_chacha::ChaCha8Rng::seed_from_u64(5));
+            assert_eq!(indices.len(), 12);
+            for block in indices.chunks(4) {
+                assert!(block.windows(2).all(|w| w[1] == w[0] + 1));
+            }
+        }
+
+        #[test]
+        fn test_bootstrap_samples_keeps_sizes() {
+            // one sample reproduces the nonparametric bootstrap of the same seed
+            let single = bootstrap_samples_rs(&[&sample()], |s| Ok(mean_rs(&s[0])), 200, 3).unwrap();
+            assert_eq!(single, bootstrap_rs(&sample(), mean_rs, None, &BootstrapKind::Nonparametric, 200, 3).unwrap());
+            let short = array![1.0, 2.0, 3.0];
+            let sizes = bootstrap_samples_rs(&[&sample(), &short], |s| Ok((s[0].len() * 10 + s[1].len()) as f64), 50, 3).unwrap();
+            assert!(sizes.iter().all(|&v| v == 123.0));
+            assert_eq!(bootstrap_samples_rs(&[&sample(), &array![]], |_| Ok(0.0), 50, 3), Err(StatsError::EmptyDataSet));
+        }
+
+        #[test]
+        fn test_parse_errors() {
+            assert_eq!(BootstrapKind::parse("stratified", None, None, 5), Err(StatsError::InvalidInputValue));
+            assert_eq!(BootstrapKind::parse("block", None, Some(6), 5), Err(StatsError::InvalidInputValue));
+            assert_eq!(BootstrapKind::parse("block", None, None, 27), Ok(BootstrapKind::Block(3)));
+            assert!(statistic_by_name("mode", 0.1).is_err());
+        }
+    }
+
+    mod interval_tests {
+        use super::*;
+
+        #[test]
+        fn test_percentile_interval() {
+            let distribution: Array1<f64> = (0..=100).map(|v| v as f64).collect();
+            let (lo, hi) = bootstrap_interval_rs(50.0, &distribution, 0.9, Interval::Percentile, None).unwrap();
+            assert_close(lo, 5.0, 1e-12);
+            assert_close(hi, 95.0, 1e-12);
+        }
+
+        #[test]
+        fn test_bca_reduces_to_percentile_without_bias_or_skew() {
+            // symmetric distribution centred on the estimate and symmetric jackknife values
+            let distribution: Array1<f64> = (0..1000).map(|v| v as f64 + 0.5).collect();
+            let jackknife = array![-1.0, 0.0, 1.0];
+            let bca = bootstrap_interval_rs(500.0, &distribution, 0.9, Interval::Bca, Some(&jackknife)).unwrap();
+            let percentile = bootstrap_interval_rs(500.0, &distribution, 0.9, Interval::Percentile, None).unwrap();
+            assert_close(bca.0, percentile.0, 1e-6);
+            assert_close(bca.1, percentile.1, 1e-6);
+        }
+    }
+
+    mod jackknife_tests {
+        use super::*;
+
+        #[test]
+        fn test_jackknife_mean() {
+            // for the mean the jackknife standard error is s / sqrt(n) and the bias is zero
+            let data = sample();
+            let replicates = jackknife_rs(&data, mean_rs).unwrap();
+            let (se, bias) = jackknife_summary_rs(mean_rs(&data), &replicates);
+            assert_close(se, (sample_variance_rs(&data) / data.len() as f64).sqrt(), 1e-12);
+            assert_close(bias, 0.0, 1e-12);
+        }
+
+        #[test]
+        fn test_jackknife_variance_bias() {
+            // the jackknife bias of the plug-in variance is exactly -s^2 / n
+            let data = sample();
+            let replicates = jackknife_rs(&data, variance_rs).unwrap();
+            let (_, bias) = jackknife_summary_rs(variance_rs(&data), &replicates);
+            assert_close(bias, -sample_variance_rs(&data) / data.len() as f64, 1e-12);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use pyo3::exceptions::PyRuntimeError;
-use numpy::{PyArray1};
-use ndarray::{Array1};
+use numpy::{PyArray1, PyArray2};
+use ndarray::{Array1, Array2};
 use pyo3::{PyResult, PyAny, PyErr};
+use pyo3::types::PyTuple;
 
 
 
@@ -24,3 +25,256 @@ pub fn from_pyarray1(pyarray: &PyAny) -> PyResult<Array1<f64>> {
         Err(PyErr::new::<PyRuntimeError, _>("Unsupported numpy array type."))
     }
 }
+
+pub fn from_pyarray2(pyarray: &PyAny) -> PyResult<Array2<f64>> {
+    if let Ok(array) = pyarray.extract::<&PyArray2<i64>>() {
+        Ok(array.to_owned_array().mapv(|x| x as f64))
+    } else if let Ok(array) = pyarray.extract::<&PyArray2<f64>>() {
+        Ok(array.to_owned_array())
+    } else {
+        Err(PyErr::new::<PyRuntimeError, _>("Unsupported numpy array type."))
+    }
+}
+
+// Average ranks (1-based) of the data, ties receive the mean of the ranks they span.
+// Also returns the size of every tie group so callers can apply tie corrections.
+pub fn rank_rs(data: &[f64]) -> (Array1<f64>, Vec<usize>) {
+    let n = data.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| data[a].partial_cmp(&data[b]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut ranks = Array1::<f64>::zeros(n);
+    let mut ties: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let mut j = i + 1;
+        while j < n && data[order[j]] == data[order[i]] {
+            j += 1;
+        }
+        // positions i..j share the same value -> average of ranks i+1..=j
+        let avg_rank = (i + 1 + j) as f64 / 2.0;
+        for &idx in &order[i..j] {
+            ranks[idx] = avg_rank;
+        }
+        if j - i > 1 {
+            ties.push(j - i);
+        }
+        i = j;
+    }
+    (ranks, ties)
+}
+
+// Sum of t^3 - t over tie groups, the term every rank test tie correction is built on.
+pub fn tie_sum_rs(ties: &[usize]) -> f64 {
+    ties.iter().map(|&t| {
+        let t = t as f64;
+        t * t * t - t
+    }).sum()
+}
+
+// Converts every array in a python *args tuple, used by tests taking any number of groups.
+pub fn from_pygroups(groups: &PyTuple) -> PyResult<Vec<Array1<f64>>> {
+    groups.iter().map(from_pyarray1).collect()
+}
+
+// Bisection root finder for a monotone f on [lo, hi], f(lo) and f(hi) must bracket zero
+pub fn bisect_rs<F: Fn(f64) -> f64>(f: F, mut lo: f64, mut hi: f64, tol: f64) -> f64 {
+    let mut f_lo = f(lo);
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        let f_mid = f(mid);
+        if f_mid == 0.0 || (hi - lo) < tol {
+            return mid;
+        }
+        if (f_mid < 0.0) == (f_lo < 0.0) {
+            lo = mid;
+            f_lo = f_mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+// Residuals of the least squares fit of v on an intercept and the columns of `covariates`,
+// found by projecting out a modified Gram-Schmidt basis of those columns
+pub fn residualize_rs(v: &Array1<f64>, covariates: &Array2<f64>) -> Array1<f64> {
+    let n = v.len();
+    let mut basis: Vec<Array1<f64>> = Vec::with_capacity(covariates.ncols() + 1);
+    let columns = std::iter::once(Array1::<f64>::ones(n)).chain(covariates.columns().into_iter().map(|c| c.to_owned()));
+    for mut column in columns {
+        let original_norm = column.dot(&column).sqrt();
+        for q in &basis {
+            let projection = q.dot(&column);
+            column.scaled_add(-projection, q);
+        }
+        let norm = column.dot(&column).sqrt();
+        // drop columns that are (numerically) linear combinations of the previous ones
+        if norm > 1e-10 * original_norm {
+            basis.push(column / norm);
+        }
+    }
+    let mut residual = v.clone();
+    for q in &basis {
+        let projection = q.dot(&residual);
+        residual.scaled_add(-projection, q);
+    }
+    residual
+}
+
+// Inverse of a square matrix by Gauss-Jordan elimination with partial pivoting, None when singular
+pub fn invert_rs(matrix: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = matrix.nrows();
+    if n != matrix.ncols() { return None; }
+    let scale = matrix.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    if scale == 0.0 { return None; }
+    let mut a = matrix.clone();
+    let mut inverse = Array2::<f64>::eye(n);
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[[i, col]].abs().partial_cmp(&a[[j, col]].abs()).unwrap_or(std::cmp::Ordering::Equal))?;
+        if a[[pivot, col]].abs() <= 1e-13 * scale { return None; }
+        for k in 0..n {
+            a.swap([col, k], [pivot, k]);
+            inverse.swap([col, k], [pivot, k]);
+        }
+        let diagonal = a[[col, col]];
+        for k in 0..n {
+            a[[col, k]] /= diagonal;
+            inverse[[col, k]] /= diagonal;
+        }
+        for row in 0..n {
+            if row == col { continue; }
+            let factor = a[[row, col]];
+            if factor == 0.0 { continue; }
+            for k in 0..n {
+                a[[row, k]] -= factor * a[[col, k]];
+                inverse[[row, k]] -= factor * inverse[[col, k]];
+            }
+        }
+    }
+    Some(inverse)
+}
+
+// Nelder-Mead simplex minimizer of f from `start`, stopping once the simplex values agree to tol
+pub fn nelder_mead_rs<F: Fn(&[f64]) -> f64>(f: F, start: &[f64], tol: f64, max_iter: usize) -> Vec<f64> {
+    let n = start.len();
+    let mut simplex: Vec<Vec<f64>> = vec![start.to_vec()];
+    for i in 0..n {
+        let mut vertex = start.to_vec();
+        vertex[i] += if vertex[i] != 0.0 { 0.05 * vertex[i] } else { 0.00025 };
+        simplex.push(vertex);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|v| f(v)).collect();
+
+    for _ in 0..max_iter {
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap_or(std::cmp::Ordering::Greater));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+        if (values[n] - values[0]).abs() <= tol * (values[0].abs() + tol) { break; }
+
+        let centroid: Vec<f64> = (0..n).map(|j| simplex[..n].iter().map(|v| v[j]).sum::<f64>() / n as f64).collect();
+        let towards = |coefficient: f64| -> Vec<f64> {
+            (0..n).map(|j| centroid[j] + coefficient * (simplex[n][j] - centroid[j])).collect()
+        };
+        let reflected = towards(-1.0);
+        let f_reflected = f(&reflected);
+        if f_reflected < values[0] {
+            let expanded = towards(-2.0);
+            let f_expanded = f(&expanded);
+            if f_expanded < f_reflected {
+                simplex[n] = expanded;
+                values[n] = f_expanded;
+            } else {
+                simplex[n] = reflected;
+                values[n] = f_reflected;
+            }
+        } else if f_reflected < values[n - 1] {
+            simplex[n] = reflected;
+            values[n] = f_reflected;
+        } else {
+            let contracted = if f_reflected < values[n] { towards(-0.5) } else { towards(0.5) };
+            let f_contracted = f(&contracted);
+            if f_contracted < values[n].min(f_reflected) {
+                simplex[n] = contracted;
+                values[n] = f_contracted;
+            } else {
+                // shrink every vertex towards the best one
+                for i in 1..=n {
+                    simplex[i] = (0..n).map(|j| simplex[0][j] + 0.5 * (simplex[i][j] - simplex[0][j])).collect();
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+    let best = (0..=n).min_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap_or(std::cmp::Ordering::Greater)).unwrap();
+    simplex[best].clone()
+}
+
+// Limited memory BFGS minimizer of a smooth f, given as (value, gradient), with a backtracking
+// Armijo line search. Stops once no gradient component exceeds tol, and returns the minimizer,
+// the iterations used and whether that happened
+pub fn lbfgs_rs<F: Fn(&Array1<f64>) -> (f64, Array1<f64>)>(f: F, start: Array1<f64>, tol: f64, max_iter: usize)
+                                                            -> (Array1<f64>, usize, bool) {
+    const MEMORY: usize = 10;
+    let largest = |g: &Array1<f64>| g.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    let mut x = start;
+    let (mut value, mut gradient) = f(&x);
+    // (s, y, 1 / y's) of the most recent steps
+    let mut history: std::collections::VecDeque<(Array1<f64>, Array1<f64>, f64)> = std::collections::VecDeque::new();
+
+    for iteration in 0..max_iter {
+        if largest(&gradient) <= tol { return (x, iteration, true); }
+        let mut q = gradient.clone();
+        let mut coefficients = Vec::with_capacity(history.len());
+        for (s, y, rho) in history.iter().rev() {
+            let a = rho * s.dot(&q);
+            q.scaled_add(-a, y);
+            coefficients.push(a);
+        }
+        match history.back() {
+            Some((s, y, _)) => q *= s.dot(y) / y.dot(y),
+            None => q /= largest(&gradient).max(1.0),
+        }
+        for ((s, y, rho), a) in history.iter().zip(coefficients.iter().rev()) {
+            let b = rho * y.dot(&q);
+            q.scaled_add(a - b, s);
+        }
+        let mut direction = -q;
+        let mut slope = gradient.dot(&direction);
+        if slope >= 0.0 {
+            // not a descent direction, restart from steepest descent
+            history.clear();
+            direction = -&gradient / largest(&gradient).max(1.0);
+            slope = gradient.dot(&direction);
+        }
+
+        let mut step = 1.0;
+        let mut accepted = None;
+        for _ in 0..50 {
+            let candidate = &x + &(&direction * step);
+            let (candidate_value, candidate_gradient) = f(&candidate);
+            if candidate_value.is_finite() && candidate_value <= value + 1e-4 * step * slope {
+                accepted = Some((candidate, candidate_value, candidate_gradient));
+                break;
+            }
+            step *= 0.5;
+        }
+        let Some((candidate, candidate_value, candidate_gradient)) = accepted else {
+            // no further decrease is possible at this precision
+            return (x, iteration, largest(&gradient) <= tol);
+        };
+        let s = &candidate - &x;
+        let y = &candidate_gradient - &gradient;
+        let sy = s.dot(&y);
+        if sy > f64::EPSILON * y.dot(&y) {
+            history.push_back((s, y, 1.0 / sy));
+            if history.len() > MEMORY { history.pop_front(); }
+        }
+        x = candidate;
+        value = candidate_value;
+        gradient = candidate_gradient;
+    }
+    let converged = largest(&gradient) <= tol;
+    (x, max_iter, converged)
+}